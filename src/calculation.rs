@@ -0,0 +1,204 @@
+//! Torque and power of a run from the recorded roller rpm and time, with the calculation
+//! model of [`CalculationConfig`]. Used to recompute a run after the model or the roller
+//! inertia changed, for example after a calibration.
+use dyno_core::serde;
+
+use crate::formats::Record;
+
+const IDX_RPM_RODA: usize = 1;
+const IDX_TORQUE: usize = 3;
+const IDX_HORSEPOWER: usize = 4;
+/// watt per mechanical horsepower
+const WATT_PER_HP: f64 = 745.699_872;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum CalculationModel {
+    /// torque and power from the buffer processing of the sensor, times the load cell factor.
+    #[default]
+    LoadCell,
+    /// torque on the roller from its angular acceleration and its moment of inertia.
+    Inertia,
+    /// inertia power added to the power of the load cell, the inertia torque is converted to
+    /// the shaft of the recorded torque with the speed ratio of the recorded power and torque.
+    Combined,
+}
+
+impl CalculationModel {
+    pub const ALL: [Self; 3] = [Self::LoadCell, Self::Inertia, Self::Combined];
+}
+
+impl std::fmt::Display for CalculationModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::LoadCell => "Load Cell",
+            Self::Inertia => "Inertia",
+            Self::Combined => "Combined",
+        })
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct CalculationConfig {
+    pub model: CalculationModel,
+    /// moment of inertia of the rollers, kg m^2
+    pub inertia: f64,
+    /// friction of the rollers (spin-down), added in inertia and combined model, Nm
+    pub loss_torque: f64,
+    /// samples on each side for the derivative of the roller speed.
+    pub smoothing: usize,
+    /// correction of the recorded torque, from the load reference calibration.
+    pub load_factor: f64,
+}
+
+impl Default for CalculationConfig {
+    fn default() -> Self {
+        Self {
+            model: CalculationModel::default(),
+            inertia: 0.0,
+            loss_torque: 0.0,
+            smoothing: 2,
+            load_factor: 1.0,
+        }
+    }
+}
+
+#[inline]
+fn omega(record: &Record) -> f64 {
+    record.values[IDX_RPM_RODA] * std::f64::consts::TAU / 60.0
+}
+
+/// angular acceleration of the roller at `idx`, rad/s^2, from the central difference over
+/// `window` samples on each side.
+fn acceleration(records: &[Record], idx: usize, window: usize) -> f64 {
+    let from = idx.saturating_sub(window);
+    let to = (idx + window).min(records.len() - 1);
+    let dt = (records[to].time_stamp - records[from].time_stamp) as f64 / 1000.0;
+    if dt <= 0.0 {
+        return 0.0;
+    }
+    (omega(&records[to]) - omega(&records[from])) / dt
+}
+
+impl CalculationConfig {
+    /// `true` if the torque is computed, not only the recorded value.
+    #[inline]
+    pub fn is_computed(&self) -> bool {
+        self.model != CalculationModel::LoadCell || (self.load_factor - 1.0).abs() > f64::EPSILON
+    }
+
+    /// recompute the torque and horsepower of `records` from the roller rpm and time, the
+    /// `records` must be the torque and power as recorded for the load cell and combined model.
+    pub fn recompute(&self, records: &mut [Record]) {
+        let window = self.smoothing.max(1);
+        let values = (0..records.len())
+            .map(|idx| {
+                let record = &records[idx];
+                let torque = record.values[IDX_TORQUE] * self.load_factor;
+                let power = record.values[IDX_HORSEPOWER] * self.load_factor * WATT_PER_HP;
+                let inertia = self.inertia * acceleration(records, idx, window) + self.loss_torque;
+                let inertia_power = inertia * omega(record);
+                match self.model {
+                    CalculationModel::LoadCell => (torque, power),
+                    CalculationModel::Inertia => (inertia, inertia_power),
+                    CalculationModel::Combined => {
+                        // angular speed of the shaft the torque is recorded on.
+                        let shaft = match power / torque {
+                            speed if speed.is_finite() && speed > f64::EPSILON => speed,
+                            _ => omega(record),
+                        };
+                        let inertia = match inertia_power / shaft {
+                            converted if converted.is_finite() => converted,
+                            _ => inertia,
+                        };
+                        (torque + inertia, power + inertia_power)
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        for (record, (torque, power)) in records.iter_mut().zip(values) {
+            record.values[IDX_TORQUE] = torque;
+            record.values[IDX_HORSEPOWER] = power / WATT_PER_HP;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{buffer_from_records, rebuild_buffer, records};
+
+    const IDX_RPM_ENGINE: usize = 2;
+
+    /// roller accelerate 60 rpm every second, alpha = 2pi rad/s^2, with 10 Nm recorded on
+    /// a shaft turning `ratio` times the roller.
+    fn accelerating(ratio: f64) -> Vec<Record> {
+        (0..10)
+            .map(|i| {
+                let mut record = Record {
+                    time_stamp: i * 100,
+                    ..Default::default()
+                };
+                record.values[IDX_RPM_RODA] = 600.0 + 6.0 * i as f64;
+                record.values[IDX_RPM_ENGINE] = record.values[IDX_RPM_RODA] * ratio;
+                record.values[IDX_TORQUE] = 10.0;
+                record.values[IDX_HORSEPOWER] = 10.0 * omega(&record) * ratio / WATT_PER_HP;
+                record
+            })
+            .collect()
+    }
+
+    const COMBINED: CalculationConfig = CalculationConfig {
+        model: CalculationModel::Combined,
+        inertia: 2.0,
+        loss_torque: 1.0,
+        smoothing: 2,
+        load_factor: 1.0,
+    };
+
+    #[test]
+    fn inertia_power() {
+        let mut records = accelerating(1.0);
+        COMBINED.recompute(&mut records);
+
+        let torque = 10.0 + 2.0 * std::f64::consts::TAU + 1.0;
+        for record in &records {
+            assert!((record.values[IDX_TORQUE] - torque).abs() < 1e-9);
+            let hp = torque * omega(record) / WATT_PER_HP;
+            assert!((record.values[IDX_HORSEPOWER] - hp).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn combined_on_engine_shaft() {
+        // torque recorded on the engine, turning 3 times the roller.
+        let mut records = accelerating(3.0);
+        COMBINED.recompute(&mut records);
+
+        let inertia = 2.0 * std::f64::consts::TAU + 1.0;
+        for record in &records {
+            assert!((record.values[IDX_TORQUE] - (10.0 + inertia / 3.0)).abs() < 1e-9);
+            let watt = 10.0 * omega(record) * 3.0 + inertia * omega(record);
+            assert!((record.values[IDX_HORSEPOWER] - watt / WATT_PER_HP).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn recompute_through_buffer() {
+        // the recomputed records are kept by the buffer the run is shown and saved from.
+        let buffer = buffer_from_records(accelerating(3.0));
+        let recorded = records(&buffer);
+        let mut recomputed = recorded.clone();
+        COMBINED.recompute(&mut recomputed);
+        let buffer = rebuild_buffer(&buffer, recomputed.clone());
+        assert_eq!(buffer.len(), recorded.len());
+        for (record, expected) in records(&buffer).iter().zip(&recomputed) {
+            assert_eq!(record.time_stamp, expected.time_stamp);
+            for idx in [IDX_RPM_RODA, IDX_TORQUE, IDX_HORSEPOWER] {
+                assert!((record.values[idx] - expected.values[idx]).abs() < 1e-4);
+            }
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Headless command line mode, record from the dyno serial port, convert and summarize
+//! saved runs without opening the GUI window.
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    formats::{self, markers::Markers, raw::RawFrames, summary::RunSummary},
+    paths::DynoPaths,
+    service::SerialService,
+    state::DynoFileType,
+    AsyncMsg, PACKAGE_INFO,
+};
+use dyno_core::{
+    crossbeam_channel::{unbounded, RecvTimeoutError},
+    ignore_err, log, BufferData, DynoConfig, DynoErr, DynoResult, LoggerBuilder,
+};
+
+pub const USAGE: &str = "\
+USAGE:
+    dynotest_app [FILE]
+    dynotest_app --headless record [OPTIONS]
+    dynotest_app --headless convert <INPUT> <OUTPUT>
+    dynotest_app --headless summary [--json] <FILE>...
+
+RECORD OPTIONS:
+    -o, --output <FILE>       output file, the type is taken from the extension [default: dynotest_<time>.dyno]
+    -d, --duration <SECS>     stop recording after SECS seconds
+        --until-rpm <RPM>     stop recording when the engine rpm reach RPM
+        --until-speed <KMH>   stop recording when the speed reach KMH
+        --until-file <FILE>   stop recording when FILE exists
+    -i, --interval <MS>       interval of printed live values in milliseconds [default: 500]
+    -q, --quiet               do not print live values
+
+FILE TYPES (by extension):
+    .dyno .dbin, .csv .dynocsv, .xlsx, .jsonl .ndjson, .parquet, .mf4 .mdf
+
+OTHER:
+    -h, --help                print this help
+    -V, --version             print version";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordArgs {
+    pub output: Option<PathBuf>,
+    pub duration: Option<Duration>,
+    pub until_rpm: Option<f64>,
+    pub until_speed: Option<f64>,
+    pub until_file: Option<PathBuf>,
+    pub interval: Duration,
+    pub quiet: bool,
+}
+
+impl Default for RecordArgs {
+    fn default() -> Self {
+        Self {
+            output: None,
+            duration: None,
+            until_rpm: None,
+            until_speed: None,
+            until_file: None,
+            interval: Duration::from_millis(500),
+            quiet: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// run the GUI, optionally opening a file at startup.
+    Gui(Option<PathBuf>),
+    Record(RecordArgs),
+    Convert {
+        input: PathBuf,
+        output: PathBuf,
+    },
+    Summary {
+        files: Vec<PathBuf>,
+        json: bool,
+    },
+    Help,
+    Version,
+}
+
+fn value_of<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = OsString>,
+    flag: &str,
+) -> DynoResult<T> {
+    let value = args
+        .next()
+        .ok_or_else(|| DynoErr::validation_error(format!("missing value for `{flag}`")))?;
+    value
+        .to_string_lossy()
+        .parse()
+        .map_err(|_| DynoErr::validation_error(format!("invalid value for `{flag}`: {value:?}")))
+}
+
+fn parse_record(mut args: impl Iterator<Item = OsString>) -> DynoResult<RecordArgs> {
+    let mut record = RecordArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.to_string_lossy().as_ref() {
+            "-o" | "--output" => record.output = Some(value_of(&mut args, "--output")?),
+            "-d" | "--duration" => {
+                let secs: f64 = value_of(&mut args, "--duration")?;
+                record.duration = Some(Duration::from_secs_f64(secs.max(0.0)));
+            }
+            "--until-rpm" => record.until_rpm = Some(value_of(&mut args, "--until-rpm")?),
+            "--until-speed" => record.until_speed = Some(value_of(&mut args, "--until-speed")?),
+            "--until-file" => record.until_file = Some(value_of(&mut args, "--until-file")?),
+            "-i" | "--interval" => {
+                record.interval = Duration::from_millis(value_of(&mut args, "--interval")?)
+            }
+            "-q" | "--quiet" => record.quiet = true,
+            other => {
+                return Err(DynoErr::validation_error(format!(
+                    "unknown record option `{other}`"
+                )))
+            }
+        }
+    }
+    Ok(record)
+}
+
+impl Command {
+    /// parse the command line arguments, without the program name.
+    pub fn parse(args: impl IntoIterator<Item = OsString>) -> DynoResult<Self> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(Self::Help);
+        }
+        if args.iter().any(|a| a == "-V" || a == "--version") {
+            return Ok(Self::Version);
+        }
+
+        let mut args = args.into_iter();
+        match args.next() {
+            Some(first) if first == "--headless" => {}
+            Some(file) => {
+                if let Some(extra) = args.next() {
+                    return Err(DynoErr::validation_error(format!(
+                        "unexpected argument {extra:?}, use `--headless` for the command line mode"
+                    )));
+                }
+                return Ok(Self::Gui(Some(PathBuf::from(file))));
+            }
+            None => return Ok(Self::Gui(None)),
+        }
+
+        let subcommand = args.next().ok_or_else(|| {
+            DynoErr::validation_error(
+                "missing subcommand for `--headless` (record, convert, summary)",
+            )
+        })?;
+        match subcommand.to_string_lossy().as_ref() {
+            "record" => parse_record(args).map(Self::Record),
+            "convert" => match (args.next(), args.next(), args.next()) {
+                (Some(input), Some(output), None) => Ok(Self::Convert {
+                    input: input.into(),
+                    output: output.into(),
+                }),
+                _ => Err(DynoErr::validation_error(
+                    "convert needs exactly <INPUT> and <OUTPUT> files",
+                )),
+            },
+            "summary" | "summarize" => {
+                let mut json = false;
+                let mut files = Vec::new();
+                for arg in args {
+                    if arg == "--json" {
+                        json = true;
+                    } else {
+                        files.push(PathBuf::from(arg));
+                    }
+                }
+                if files.is_empty() {
+                    return Err(DynoErr::validation_error(
+                        "summary needs at least one <FILE>",
+                    ));
+                }
+                Ok(Self::Summary { files, json })
+            }
+            other => Err(DynoErr::validation_error(format!(
+                "unknown subcommand `{other}` (record, convert, summary)"
+            ))),
+        }
+    }
+
+    /// run the headless command, [`Command::Gui`] is handled by the caller.
+    pub fn run(self) -> DynoResult<()> {
+        match self {
+            Self::Gui(_) => Ok(()),
+            Self::Help => {
+                println!(
+                    "{} {}\n\n{USAGE}",
+                    PACKAGE_INFO.app_name, PACKAGE_INFO.version
+                );
+                Ok(())
+            }
+            Self::Version => {
+                println!("{} {}", PACKAGE_INFO.app_name, PACKAGE_INFO.version);
+                Ok(())
+            }
+            Self::Record(args) => record(args),
+            Self::Convert { input, output } => convert(&input, &output),
+            Self::Summary { files, json } => summary(&files, json),
+        }
+    }
+}
+
+fn file_type(path: &Path) -> DynoResult<DynoFileType> {
+    DynoFileType::from_path(path).ok_or_else(|| {
+        DynoErr::validation_error(format!("unknown file type of `{}`", path.display()))
+    })
+}
+
+fn open(path: &Path) -> DynoResult<BufferData> {
+    formats::open_from_path(file_type(path)?, path)
+}
+
+fn convert(input: &Path, output: &Path) -> DynoResult<()> {
+    let (buffer, markers) = formats::open_with_markers(file_type(input)?, input)?;
+    formats::save_to_path(file_type(output)?, &buffer, &markers, output)?;
+    println!(
+        "converted {} records from `{}` to `{}`",
+        buffer.len(),
+        input.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn summary(files: &[PathBuf], json: bool) -> DynoResult<()> {
+    for file in files {
+        let summary = RunSummary::from_buffer(&open(file)?);
+        if json {
+            let value = serde_json::json!({
+                "file": file.display().to_string(),
+                "summary": summary,
+            });
+            println!("{value}");
+        } else {
+            println!("{}\n{summary}\n", file.display());
+        }
+    }
+    Ok(())
+}
+
+fn record(args: RecordArgs) -> DynoResult<()> {
+    ignore_err!(LoggerBuilder::new()
+        .set_max_level(log::LevelFilter::Warn)
+        .build_console_logger());
+
+    // the active config of the application, kept in `config.toml` by the application.
+    let paths = DynoPaths::new(PACKAGE_INFO.app_name)?;
+    let mut config = paths
+        .get_config::<DynoConfig>("config.toml")
+        .unwrap_or_else(|err| {
+            eprintln!("WARNING: {err}, recording with the default config");
+            DynoConfig::default()
+        });
+    let output = args.output.clone().unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "dynotest_{}",
+            crate::paths::file_name_timestamp("dyno")
+        ))
+    });
+    let tp = file_type(&output)?;
+
+    let mut serial = SerialService::new()
+        .ok_or_else(|| DynoErr::service_error("no dyno serial port device found"))?;
+    let (tx, rx) = unbounded();
+    let handle = serial.start(tx)?;
+    eprintln!("recording from serial port, output `{}`", output.display());
+
+    let mut buffer = BufferData::new();
+    let mut raw = RawFrames::default();
+    let started = Instant::now();
+    let mut last_print = started;
+    let reason = loop {
+        if handle.is_finished() {
+            break "serial port closed";
+        }
+        if args.duration.map_or(false, |d| started.elapsed() >= d) {
+            break "duration reached";
+        }
+        if args.until_file.as_ref().map_or(false, |f| f.exists()) {
+            break "stop file found";
+        }
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(AsyncMsg::OnSerialData(data, frame)) => {
+                buffer.push_from_serial(&mut config, data);
+                raw.push(buffer.last().time_stamp.and_utc().timestamp_millis(), frame);
+            }
+            Ok(AsyncMsg::OnError(err)) => eprintln!("ERROR: {err}"),
+            Ok(_) | Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break "serial port closed",
+        }
+
+        let last = buffer.last();
+        if !args.quiet && last_print.elapsed() >= args.interval {
+            last_print = Instant::now();
+            println!(
+                "{:9.3} s | {:7.2} km/h | {:6.0} rpm | {:7.2} Nm | {:7.2} HP | {:6.2} °C",
+                started.elapsed().as_secs_f64(),
+                last.speed.value(),
+                last.rpm_engine.value(),
+                last.torque.value(),
+                last.horsepower.value(),
+                last.temp.value(),
+            );
+        }
+        if args
+            .until_rpm
+            .map_or(false, |rpm| last.rpm_engine.value() >= rpm)
+        {
+            break "rpm trigger reached";
+        }
+        if args
+            .until_speed
+            .map_or(false, |kmh| last.speed.value() >= kmh)
+        {
+            break "speed trigger reached";
+        }
+    };
+    serial.stop();
+    eprintln!("recording stopped, {reason}");
+
+    if buffer.is_empty() {
+        return Err(DynoErr::service_error("no data received from the dyno"));
+    }
+    formats::save_to_path(tp, &buffer, &Markers::default(), &output)?;
+    raw.save(&RawFrames::sidecar_path(&output))?;
+    println!(
+        "saved `{}`\n{}",
+        output.display(),
+        RunSummary::from_buffer(&buffer)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> DynoResult<Command> {
+        Command::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(parse(&[]).unwrap(), Command::Gui(None));
+        assert_eq!(
+            parse(&["run.dyno"]).unwrap(),
+            Command::Gui(Some("run.dyno".into()))
+        );
+        assert_eq!(
+            parse(&[
+                "--headless",
+                "record",
+                "-o",
+                "a.csv",
+                "-d",
+                "2.5",
+                "--until-rpm",
+                "9000"
+            ])
+            .unwrap(),
+            Command::Record(RecordArgs {
+                output: Some("a.csv".into()),
+                duration: Some(Duration::from_millis(2500)),
+                until_rpm: Some(9000.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--headless", "summary", "--json", "a.dyno"]).unwrap(),
+            Command::Summary {
+                files: vec!["a.dyno".into()],
+                json: true
+            }
+        );
+        assert!(parse(&["--headless", "convert", "a.dyno"]).is_err());
+        assert!(parse(&["--headless", "record", "--bogus"]).is_err());
+    }
+}
@@ -5,10 +5,16 @@ use eframe::{
 
 use crate::{
     assets::ICO_LOGO,
+    calculation::CalculationConfig,
+    i18n::Language,
+    keymap::KeyMap,
     open_option_icon, row_label_value,
+    state::DynoFileType,
+    tr,
     widgets::{DisplayStylePreset, DynoWidgets},
 };
 use dyno_core::serde;
+use std::path::{Path, PathBuf};
 
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -17,6 +23,18 @@ pub struct ApplicationConfig {
     pub segment_display_style: DisplayStylePreset,
     pub app_options: AppOptions,
     pub show_startup: bool,
+    #[serde(default)]
+    pub recent_files: RecentFiles,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub server: ServerProfiles,
+    #[serde(default)]
+    pub calculation: CalculationConfig,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub keymap: KeyMap,
 }
 
 impl ApplicationConfig {
@@ -26,19 +44,244 @@ impl ApplicationConfig {
         }
     }
     pub fn draw(&mut self, ui: &mut eframe::egui::Ui) {
-        ui.checkbox(&mut self.show_startup, "Show Startup Window");
+        ui.combobox_from_iter(
+            tr!("Language"),
+            &mut self.language,
+            Language::ALL.into_iter(),
+        );
+        ui.checkbox(&mut self.show_startup, tr!("Show Startup Window"));
+        ui.horizontal(|ui| {
+            ui.add(
+                eframe::egui::DragValue::new(&mut self.recent_files.max_files).clamp_range(1..=50),
+            );
+            ui.label(tr!("Recent Files"));
+        });
         ui.separator();
         self.app_options.ui(ui);
 
         let iter = self.segment_display_style.get_iter();
         ui.combobox_from_iter(
-            "Style for SevenSegment",
+            tr!("Style for SevenSegment"),
             &mut self.segment_display_style,
             iter,
         );
     }
 }
 
+/// API server endpoint used by [`crate::service::ApiService`].
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct ServerProfile {
+    pub name: String,
+    pub base_url: String,
+    /// accept self signed or invalid TLS certificate (only for local or staging server).
+    pub accept_invalid_certs: bool,
+    /// additional root certificate in PEM format.
+    pub ca_certificate: Option<PathBuf>,
+    /// request timeout in seconds.
+    pub timeout: u64,
+}
+
+impl Default for ServerProfile {
+    fn default() -> Self {
+        Self {
+            name: "local".to_owned(),
+            base_url: "http://127.0.0.1:3000".to_owned(),
+            accept_invalid_certs: false,
+            ca_certificate: None,
+            timeout: 30,
+        }
+    }
+}
+
+impl ServerProfile {
+    /// file name of the login session for this profile, a token is only valid for its server.
+    pub fn session_file(&self) -> String {
+        let name = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("session_{name}.json")
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct ServerProfiles {
+    /// name of the active profile.
+    pub active: String,
+    pub profiles: Vec<ServerProfile>,
+}
+
+impl Default for ServerProfiles {
+    fn default() -> Self {
+        let local = ServerProfile::default();
+        Self {
+            active: local.name.clone(),
+            profiles: vec![local],
+        }
+    }
+}
+
+impl ServerProfiles {
+    /// the active profile, `DYNO_SERVER_URL` environment variable override the base url of the
+    /// default (`local`) profile, the other profiles are always used as saved.
+    pub fn active_profile(&self) -> ServerProfile {
+        let mut profile = self
+            .profiles
+            .iter()
+            .find(|p| p.name == self.active)
+            .or_else(|| self.profiles.first())
+            .cloned()
+            .unwrap_or_default();
+        if profile.name != ServerProfile::default().name {
+            return profile;
+        }
+        if let Ok(url) = std::env::var("DYNO_SERVER_URL") {
+            dyno_core::log::info!(
+                "Using DYNO_SERVER_URL `{url}` for server `{}`",
+                profile.name
+            );
+            profile.base_url = url;
+        }
+        profile
+    }
+
+    /// rename the profile in `idx`, the active profile stays active,
+    /// `false` if `name` is empty or the name of another profile.
+    pub fn rename(&mut self, idx: usize, name: &str) -> bool {
+        let name = name.trim();
+        let taken = self
+            .profiles
+            .iter()
+            .enumerate()
+            .any(|(i, p)| i != idx && p.name == name);
+        if name.is_empty() || taken || idx >= self.profiles.len() {
+            return false;
+        }
+        if self.profiles[idx].name == self.active {
+            self.active = name.to_owned();
+        }
+        self.profiles[idx].name = name.to_owned();
+        true
+    }
+
+    /// add a default profile with an unused name, returns the index of the profile.
+    pub fn add(&mut self) -> usize {
+        let name = (self.profiles.len() + 1..)
+            .map(|n| format!("server {n}"))
+            .find(|name| self.profiles.iter().all(|p| &p.name != name))
+            .unwrap_or_default();
+        self.profiles.push(ServerProfile {
+            name,
+            ..Default::default()
+        });
+        self.profiles.len() - 1
+    }
+}
+
+/// local live telemetry server, see [`crate::service::TelemetryService`].
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct TelemetryConfig {
+    /// start the server when the application start.
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: format!("{}:8080", Self::LOCAL_HOST),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// only reachable from this computer, the default.
+    pub const LOCAL_HOST: &'static str = "127.0.0.1";
+    /// reachable from every device in the local network.
+    pub const SHARED_HOST: &'static str = "0.0.0.0";
+
+    /// the server is reachable from the local network.
+    pub fn is_shared(&self) -> bool {
+        self.address
+            .rsplit_once(':')
+            .map_or(false, |(host, _)| host == Self::SHARED_HOST)
+    }
+
+    /// switch the host of the address between [`Self::SHARED_HOST`] and [`Self::LOCAL_HOST`],
+    /// the port is kept.
+    pub fn set_shared(&mut self, shared: bool) {
+        let host = if shared {
+            Self::SHARED_HOST
+        } else {
+            Self::LOCAL_HOST
+        };
+        let port = self
+            .address
+            .rsplit_once(':')
+            .map_or("8080", |(_, port)| port);
+        self.address = format!("{host}:{port}");
+    }
+}
+
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct RecentFile {
+    pub path: PathBuf,
+    pub file_type: DynoFileType,
+}
+
+/// last opened or saved files, listed in `File > Open Recent`.
+#[cfg_attr(debug_assertions, derive(Debug))]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct RecentFiles {
+    pub max_files: usize,
+    pub files: Vec<RecentFile>,
+}
+
+impl Default for RecentFiles {
+    fn default() -> Self {
+        Self {
+            max_files: 10,
+            files: vec![],
+        }
+    }
+}
+
+impl RecentFiles {
+    /// move `path` to the top of the list, and drop the oldest files over `max_files`.
+    pub fn push(&mut self, file_type: DynoFileType, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.files.retain(|f| f.path != path);
+        self.files.insert(0, RecentFile { path, file_type });
+        self.files.truncate(self.max_files);
+    }
+
+    pub fn remove(&mut self, path: impl AsRef<Path>) {
+        self.files.retain(|f| f.path != path.as_ref());
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.files.clear();
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
 #[cfg_attr(debug_assertions, derive(Debug))]
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[serde(default, crate = "serde")]
@@ -67,17 +310,17 @@ pub struct AppOptions {
 impl AppOptions {
     pub fn ui(&mut self, ui: &mut eframe::egui::Ui) -> eframe::egui::Response {
         use eframe::egui::{Grid, RichText};
-        ui.heading("Application Options Setting");
+        ui.heading(tr!("Application Options Setting"));
 
         let ui_grid_appoptions = |ui: &mut eframe::egui::Ui| {
             row_label_value!(
                 ui => ui.optional_value_widget(&mut self.icon_path, |ui, value| {
                     let text = RichText::new(value.to_string())
                         .background_color(ui.visuals().extreme_bg_color);
-                    let response = ui.link(text).on_hover_text("Left Click to Edit");
+                    let response = ui.link(text).on_hover_text(tr!("Left Click to Edit"));
                     if response.clicked() {
                         if let Some(path) =
-                            crate::widgets::DynoFileManager::pick_folder("Change Path", &value)
+                            crate::widgets::DynoFileManager::pick_folder(tr!("Change Path"), &value)
                         {
                             *value = path.display().to_string();
                         }
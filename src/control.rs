@@ -1,26 +1,44 @@
 use crate::{
     config::ApplicationConfig,
-    paths::DynoPaths,
+    formats::{
+        self,
+        export::{export_to_path, ExportOptions, ExportTable},
+        import::{self, ImportProfile},
+        markers::Markers,
+        raw::{self, RawFrames, RevisionInfo},
+        Record,
+    },
+    i18n,
+    keymap::Action,
+    paths::{DynoPaths, PathsMigration},
     row_label_value,
-    service::{ApiService, PortInfo, SerialService},
+    service::{
+        scan_local, ApiRequest, ApiService, CalibrationLog, CalibrationStatus, Outbox, PortInfo,
+        Presets, SerialService, SyncEvent, SyncService, TelemetryService, TransferKind, Transfers,
+        VehicleDb,
+    },
+    settings::{SettingsFile, SettingsSection},
     state::{DynoFileType, DynoState, OperatorData},
-    toast_error, toast_info, toast_success,
+    toast_error, toast_info, toast_success, toast_warn, tr,
     widgets::{
         button::ButtonExt, segment_display::SegmentedDisplay, DynoFileManager, Gauge, RealtimePlot,
     },
-    windows::{open_server::OpenServerWindow, WSIdx, WindowStack},
+    windows::{open_server::OpenServerWindow, review::ReviewWindow, WSIdx, WindowStack},
     AsyncMsg,
 };
 use dyno_core::{
     asyncify,
     chrono::{NaiveDateTime, Utc},
     crossbeam_channel::{unbounded, Receiver, Sender},
-    ignore_err, log, serde, BufferData, CompresedSaver, CsvSaver, Data, DynoConfig, ExcelSaver,
+    ignore_err, log, serde, BufferData, Data, DynoConfig, DynoErr, DynoResult,
 };
 use eframe::egui::*;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -49,9 +67,69 @@ pub struct DynoControl {
     serial: Option<SerialService>,
 
     #[serde(skip)]
-    #[serde(default = "ApiService::new")]
+    #[serde(default)]
     api_service: Option<ApiService>,
 
+    #[serde(skip)]
+    #[serde(default)]
+    pub telemetry: TelemetryService,
+
+    #[serde(skip)]
+    #[serde(default)]
+    pub outbox: Outbox,
+
+    #[serde(skip)]
+    #[serde(default)]
+    pub sync: SyncService,
+
+    #[serde(skip)]
+    #[serde(default)]
+    pub vehicles: VehicleDb,
+
+    /// named `DynoConfig` presets in config dir.
+    #[serde(skip)]
+    #[serde(default)]
+    pub presets: Presets,
+
+    /// name of the preset the config is loaded from.
+    #[serde(default)]
+    pub preset: Option<String>,
+
+    /// dated calibration records of the roller and load constants.
+    #[serde(skip)]
+    #[serde(default)]
+    pub calibration: CalibrationLog,
+
+    /// id of the vehicle in `vehicles` for the next runs.
+    #[serde(skip)]
+    #[serde(default)]
+    pub vehicle: Option<String>,
+
+    /// records of the buffer as recorded, before the first recompute of the torque and power.
+    #[serde(skip)]
+    #[serde(default)]
+    raw_records: Option<Vec<Record>>,
+
+    /// raw serial frames of the buffer, to process the run again with a different config.
+    #[serde(skip)]
+    #[serde(default)]
+    raw_frames: RawFrames,
+
+    /// event markers of the buffer, saved next to the run file.
+    #[serde(skip)]
+    #[serde(default)]
+    markers: Markers,
+
+    /// file of the buffer, `None` if the buffer is not saved or opened from a file.
+    #[serde(skip)]
+    #[serde(default)]
+    run_file: Option<PathBuf>,
+
+    /// progress of the running uploads and downloads.
+    #[serde(skip)]
+    #[serde(default)]
+    pub transfers: Transfers,
+
     plots: RealtimePlot,
 
     #[serde(skip)]
@@ -74,6 +152,19 @@ pub struct DynoControl {
     #[serde(skip)]
     #[serde(default)]
     buffer_saved: bool,
+
+    #[serde(skip)]
+    #[serde(default)]
+    pending_file: Option<(DynoFileType, PathBuf)>,
+
+    #[serde(skip)]
+    #[serde(default)]
+    pending_operator: OperatorData,
+
+    /// requests rejected because the session expired, sent again after login.
+    #[serde(skip)]
+    #[serde(default)]
+    pending_requests: Vec<ApiRequest>,
 }
 
 impl Default for DynoControl {
@@ -85,6 +176,19 @@ impl Default for DynoControl {
             buffer: Default::default(),
             serial: Default::default(),
             api_service: Default::default(),
+            telemetry: Default::default(),
+            outbox: Default::default(),
+            sync: Default::default(),
+            vehicles: Default::default(),
+            presets: Default::default(),
+            preset: Default::default(),
+            calibration: Default::default(),
+            vehicle: Default::default(),
+            raw_records: Default::default(),
+            raw_frames: Default::default(),
+            markers: Default::default(),
+            run_file: Default::default(),
+            transfers: Default::default(),
             plots: Default::default(),
             async_channels: unbounded(),
             start_time: Default::default(),
@@ -92,11 +196,17 @@ impl Default for DynoControl {
             stop: Default::default(),
             loadings: Default::default(),
             buffer_saved: Default::default(),
+            pending_file: Default::default(),
+            pending_operator: Default::default(),
+            pending_requests: Default::default(),
         }
     }
 }
 
 impl DynoControl {
+    /// default file name of File -> Export Settings.
+    pub const SETTINGS_FILE: &'static str = "dynotests_settings.toml";
+
     pub fn new() -> Self {
         let paths = DynoPaths::new(crate::PACKAGE_INFO.app_name).unwrap_or_else(|err| {
             dyno_core::log::error!("{err}");
@@ -125,28 +235,55 @@ impl DynoControl {
             plots: RealtimePlot::new(),
             buffer_saved: true,
             async_channels: unbounded(),
-            api_service: ApiService::new(),
             serial: SerialService::new(),
             ..Default::default()
         }
     }
 
     pub fn init(&mut self) {
-        match self.api() {
-            Some(api) => api.set_active(self.config.clone(), self.tx().clone()),
-            None => self.reconnect_api(),
-        }
+        i18n::set_language(self.app_config.language);
+        self.load_stores();
+        self.reconnect_api();
         if self.serial.is_none() {
             self.reconnect_serial();
         }
+        if self.app_config.telemetry.enabled {
+            self.start_telemetry();
+        }
+        match self.calibration.status(&self.config) {
+            CalibrationStatus::Never => toast_warn!(tr!("The dyno is never calibrated, run the Calibration wizard before testing.")),
+            CalibrationStatus::Changed => toast_warn!(tr!("Roller constants changed since the last calibration, run the Calibration wizard to verify them.")),
+            CalibrationStatus::Stale(days) => toast_warn!(tr!("Last calibration is {days} days ago, run the Calibration wizard to verify the constants.", days = days)),
+            CalibrationStatus::Valid => {}
+        }
+    }
+
+    /// load the stores kept in the data and config dir of `paths`, they are not saved in the app
+    /// storage and must be loaded again after the control is restored.
+    fn load_stores(&mut self) {
+        self.outbox = Outbox::load(&self.paths);
+        self.sync = SyncService::load(&self.paths);
+        self.vehicles = VehicleDb::load(&self.paths);
+        self.calibration = CalibrationLog::load(&self.paths);
+        self.presets = Presets::load(&self.paths);
     }
+
     pub fn deinit(&mut self) {
+        self.telemetry.stop();
+        // keep the login session for the next launch, only set the dyno as non active.
         if let Some(api) = self.api() {
-            api.logout(self.tx().clone());
             api.set_non_active(self.tx().clone());
         }
     }
 
+    /// keep the active `DynoConfig` in `config.toml`, the config of the headless `record` and of
+    /// a launch without the application storage.
+    pub fn save_active_config(&self) {
+        if let Err(err) = self.paths.set_config(&self.config, "config.toml") {
+            dyno_core::log::error!("Failed to save DynoTests Configuration file ({err})");
+        }
+    }
+
     #[inline(always)]
     pub fn last_buffer(&self) -> Data {
         self.buffer.last().clone()
@@ -163,6 +300,69 @@ impl DynoControl {
         &mut self.buffer
     }
 
+    fn clean_buffer(&mut self) {
+        self.buffer.clean();
+        self.raw_records = None;
+        self.raw_frames.clear();
+        self.markers.clear();
+        self.run_file = None;
+        self.telemetry.reset();
+    }
+
+    #[inline]
+    pub fn raw_frames(&self) -> &RawFrames {
+        &self.raw_frames
+    }
+
+    #[inline]
+    pub fn markers(&self) -> &Markers {
+        &self.markers
+    }
+
+    /// markers to edit, the buffer is marked as unsaved.
+    #[inline]
+    pub fn markers_mut(&mut self) -> &mut Markers {
+        self.buffer_saved = false;
+        &mut self.markers
+    }
+
+    /// add an event marker at the last record of the buffer.
+    pub fn mark_event(&mut self, text: impl Into<String>) {
+        if self.buffer.is_empty() {
+            toast_warn!(tr!("There is no data to mark, start the recording first"));
+            return;
+        }
+        let time_stamp = self.buffer.time_stamp.last_value();
+        let secs = (time_stamp - self.buffer.time_stamp.first_value()) as f64 / 1000.0;
+        self.markers_mut().push(time_stamp, text);
+        toast_info!(tr!("Event marked at {time} s", time = format!("{secs:.1}")));
+    }
+
+    #[inline]
+    pub fn run_file(&self) -> Option<&PathBuf> {
+        self.run_file.as_ref()
+    }
+
+    /// recompute the torque and power of the buffer with the calculation model in app config,
+    /// always from the records as recorded, so it can be repeated after the model changed.
+    pub fn recompute_power(&mut self) {
+        if self.buffer.is_empty() {
+            toast_warn!(tr!("No data to recompute"));
+            return;
+        }
+        let raw = self
+            .raw_records
+            .get_or_insert_with(|| formats::records(&self.buffer));
+        let mut records = raw.clone();
+        self.app_config.calculation.recompute(&mut records);
+        self.buffer = formats::rebuild_buffer(&self.buffer, records);
+        self.buffer_saved = false;
+        toast_info!(tr!(
+            "Torque and power recomputed with `{model}` model",
+            model = self.app_config.calculation.model
+        ));
+    }
+
     #[inline]
     pub fn start_time(&self) -> String {
         let seconds = (self.start_time / 1000) % 60;
@@ -190,10 +390,23 @@ impl DynoControl {
         self.api_service.as_ref()
     }
 
-    #[inline]
+    /// (re)create the api service from the active server profile,
+    /// the dyno is set as non active on the previous server.
     pub fn reconnect_api(&mut self) {
-        if let Some(api) = ApiService::new() {
-            toast_success!("SUCCES! connected to Api Endpoint: {}", api.url);
+        let profile = self.app_config.server.active_profile();
+        if let Some(mut api) = ApiService::new(&profile) {
+            if let Some(old) = self.api_service.take() {
+                if old.url != api.url {
+                    old.set_non_active(self.tx().clone());
+                }
+            }
+            toast_success!(tr!(
+                "Connected to API server [{profile}]: {url}",
+                profile = api.profile,
+                url = api.url
+            ));
+            api.restore_session(self.paths.get_config_dir_file(profile.session_file()));
+            api.detect_features();
             api.set_active(self.config.clone(), self.tx().clone());
             self.api_service = Some(api);
         }
@@ -202,16 +415,195 @@ impl DynoControl {
     #[inline]
     pub fn reconnect_serial(&mut self) {
         if let Some(serial) = SerialService::new() {
-            toast_success!(
-                "SUCCES! connected to Serial: [{}]:[{}-{}]",
-                serial.info.port_name,
-                serial.info.vid,
-                serial.info.pid
-            );
+            toast_success!(tr!(
+                "Connected to serial port [{port}]:[{vid}-{pid}]",
+                port = serial.info.port_name,
+                vid = serial.info.vid,
+                pid = serial.info.pid
+            ));
             self.serial = Some(serial);
         }
     }
 
+    /// start the serial service, does nothing if it is already running.
+    pub fn start_recording(&mut self) {
+        let Some(serial) = &mut self.serial else {
+            toast_warn!(tr!("Serial port is not connected"));
+            return;
+        };
+        if serial.is_open() {
+            return;
+        }
+        if let Err(err) = serial.start(self.async_channels.0.clone()) {
+            toast_error!(tr!("Serial Service Failed to start - {err}", err = err))
+        }
+        self.start = Some(Utc::now().naive_utc());
+    }
+
+    pub fn stop_recording(&mut self) {
+        match &self.serial {
+            Some(serial) if serial.is_open() => serial.stop(),
+            _ => return,
+        }
+        self.stop = Some(Utc::now().naive_utc());
+        if self.app_config.calculation.is_computed() {
+            self.recompute_power();
+        }
+    }
+
+    /// stop the serial service and clear the recorded data.
+    pub fn reset_recording(&mut self) {
+        if let Some(serial) = self.serial.as_ref().filter(|s| s.is_open()) {
+            serial.stop();
+        }
+        self.clean_buffer();
+    }
+
+    pub fn start_telemetry(&mut self) {
+        match self.telemetry.start(&self.app_config.telemetry.address) {
+            Ok(addr) => toast_success!(tr!(
+                "Live Telemetry Server running on http://{addr}",
+                addr = addr
+            )),
+            Err(err) => toast_error!(tr!(
+                "Failed to start Live Telemetry Server - {err}",
+                err = err
+            )),
+        }
+    }
+
+    /// select the vehicle for the next runs, the `DynoConfig` is filled with its motor and
+    /// roller constants. `None` to stop linking the runs to a vehicle.
+    pub fn select_vehicle(&mut self, id: Option<String>) {
+        match id.as_deref().and_then(|id| self.vehicles.vehicle(id)) {
+            Some(vehicle) => {
+                vehicle.apply(&mut self.config);
+                toast_info!(tr!(
+                    "Vehicle `{plate}` ({motor}) is selected",
+                    plate = vehicle.plate,
+                    motor = vehicle.motor
+                ));
+            }
+            None if id.is_some() => {
+                toast_error!(tr!("Vehicle is not found in the database"));
+                return;
+            }
+            None => {}
+        }
+        self.vehicle = id;
+    }
+
+    /// load the config of the preset `name`.
+    pub fn apply_preset(&mut self, name: &str) {
+        match self.presets.get(name) {
+            Some(preset) => {
+                self.config = preset.config.clone();
+                self.preset = Some(preset.name.clone());
+                toast_info!(tr!("Preset `{name}` is loaded", name = name));
+            }
+            None => toast_error!(tr!("Preset `{name}` is not found", name = name)),
+        }
+    }
+
+    /// settings file with every section of the settings.
+    pub fn settings_file(&self) -> DynoResult<SettingsFile> {
+        SettingsFile::new()
+            .with(SettingsSection::App, &self.app_config)?
+            .with(SettingsSection::Paths, &self.paths)?
+            .with(SettingsSection::Dyno, &self.config)?
+            .with(SettingsSection::Plot, &self.plots)
+    }
+
+    /// load the sections in the settings `file`, the sections not in the file are kept.
+    /// nothing is changed if one of the sections is invalid.
+    pub fn import_settings(&mut self, file: &SettingsFile) -> DynoResult<Vec<SettingsSection>> {
+        let app = file.get::<ApplicationConfig>(SettingsSection::App)?;
+        let paths = file.get::<DynoPaths>(SettingsSection::Paths)?;
+        let config = file.get::<DynoConfig>(SettingsSection::Dyno)?;
+        let plots = file.get::<RealtimePlot>(SettingsSection::Plot)?;
+        if let Some(paths) = paths {
+            self.set_paths(paths, false)?;
+        }
+        if let Some(app) = app {
+            self.app_config = app;
+            self.reconnect_api();
+        }
+        if let Some(config) = config {
+            self.config = config;
+            self.preset = None;
+        }
+        if let Some(plots) = plots {
+            self.plots = plots;
+        }
+        Ok(file.sections().collect())
+    }
+
+    /// set the `section` of the settings to the default.
+    pub fn reset_settings(&mut self, section: SettingsSection) {
+        match section {
+            SettingsSection::App => {
+                self.app_config = ApplicationConfig::default();
+                self.reconnect_api();
+            }
+            SettingsSection::Paths => {
+                let paths = DynoPaths::new(crate::PACKAGE_INFO.app_name).unwrap_or_else(|err| {
+                    log::error!("{err}");
+                    Default::default()
+                });
+                if let Err(err) = self.set_paths(paths, false) {
+                    toast_error!(tr!("Failed to reset the paths - {err}", err = err));
+                    return;
+                }
+            }
+            SettingsSection::Dyno => {
+                self.config = DynoConfig::default();
+                self.preset = None;
+            }
+            SettingsSection::Plot => self.plots = RealtimePlot::new(),
+        }
+        toast_success!(tr!(
+            "{section} settings are reset to default",
+            section = section
+        ));
+    }
+
+    /// check the folders of `paths` and use them, the data in the old folders is moved to the
+    /// new folders if `move_data`. The stores in the folders are loaded again.
+    pub fn set_paths(&mut self, paths: DynoPaths, move_data: bool) -> DynoResult<PathsMigration> {
+        if self.sync.is_running() || self.outbox.entries().iter().any(|e| e.is_uploading()) {
+            return Err(DynoErr::input_error(tr!(
+                "Wait until the sync and upload are finished before changing the paths"
+            )));
+        }
+        paths.validate()?;
+        let migration = if move_data {
+            paths.migrate_from(&self.paths)?
+        } else {
+            PathsMigration::default()
+        };
+        for path in &migration.skipped {
+            log::warn!(
+                "`{}` is not moved, it exists in the new folder",
+                path.display()
+            );
+        }
+        let log_moved = paths.cache_dir != self.paths.cache_dir;
+        if !self.paths.check_is_changed(&paths) {
+            return Ok(migration);
+        }
+        self.load_stores();
+        if log_moved {
+            if let Err(err) = crate::init_logger(self.paths.get_cache_dir_file(DynoPaths::LOG_FILE))
+            {
+                log::warn!("Failed to move the log file - {err}");
+                toast_warn!(tr!(
+                    "The log file is moved to the new cache dir after restarting the application"
+                ));
+            }
+        }
+        Ok(migration)
+    }
+
     #[inline]
     pub fn set_loading(&self) {
         self.loadings.store(true, Ordering::Relaxed);
@@ -227,16 +619,78 @@ impl DynoControl {
     pub fn on_pos_render(&mut self, window_stack: &mut WindowStack, state: &mut DynoState) {
         if let Ok(msg) = self.async_channels.1.try_recv() {
             match msg {
-                AsyncMsg::OnSerialData(serial_data) => {
+                AsyncMsg::OnSerialData(serial_data, frame) => {
                     self.start_time += serial_data.period as u64;
                     self.buffer.push_from_serial(&mut self.config, serial_data);
+                    let time_stamp = self.buffer.last().time_stamp.and_utc().timestamp_millis();
+                    self.raw_frames.push(time_stamp, frame);
+                    self.raw_records = None;
+                    self.run_file = None;
+                    self.telemetry.publish(self.buffer.last());
                     self.buffer_saved = false;
                 }
                 AsyncMsg::OnOpenBuffer(buffer) => {
                     self.buffer = *buffer;
+                    self.raw_records = None;
+                    self.raw_frames.clear();
+                    self.markers.clear();
+                    self.run_file = None;
                     self.buffer_saved = false;
                     self.unset_loading();
                 }
+                AsyncMsg::OnOpenRaw(path, frames) => {
+                    self.raw_frames = *frames;
+                    self.run_file = Some(path);
+                }
+                AsyncMsg::OnOpenMarkers(markers) => self.markers = markers,
+                AsyncMsg::OnOpenCompare(name, buffer) => {
+                    toast_info!(tr!("Added `{name}` to the plot comparison", name = name));
+                    self.plots.add_compare(name, &buffer);
+                    self.unset_loading();
+                }
+                AsyncMsg::OnRecentFile(tp, path) => self.app_config.recent_files.push(tp, path),
+                AsyncMsg::OnSavedFile(tp, path) => {
+                    self.run_file = Some(path.clone());
+                    if let Some(vehicle) = &self.vehicle {
+                        self.vehicles.add_run(vehicle, Some(path.clone()), None);
+                    }
+                    self.app_config.recent_files.push(tp, path);
+                }
+                AsyncMsg::OnUploaded(id, server_id) => {
+                    self.transfers.remove(&id);
+                    let vehicle = self
+                        .outbox
+                        .entries()
+                        .iter()
+                        .find(|e| e.id == id)
+                        .and_then(|e| e.vehicle.clone());
+                    if let Some(vehicle) = vehicle {
+                        self.vehicles.add_run(&vehicle, None, Some(server_id));
+                    }
+                    toast_success!(tr!("Data saved to server with id {id}", id = server_id));
+                    if let Err(err) = self.outbox.remove(&id) {
+                        toast_error!(tr!(
+                            "Failed to remove uploaded data from outbox - {err}",
+                            err = err
+                        ));
+                    }
+                }
+                AsyncMsg::OnUploadFailed(id, err) => {
+                    self.transfers.remove(&id);
+                    let attempts = self
+                        .outbox
+                        .entries()
+                        .iter()
+                        .find(|e| e.id == id)
+                        .map_or(0, |e| e.attempts);
+                    // only notify the first failure, retries are shown in pending uploads.
+                    if attempts == 0 {
+                        toast_warn!(tr!("Upload to server failed, kept in Pending Uploads and retried later - {err}", err = err));
+                    } else {
+                        log::warn!("Retry upload `{id}` failed ({attempts}) - {err}");
+                    }
+                    self.outbox.set_failed(&id, &err);
+                }
                 AsyncMsg::OnError(err) => {
                     toast_error!("{err}");
                     self.unset_loading();
@@ -246,15 +700,29 @@ impl DynoControl {
                     if state.quitable() {
                         state.set_quit(true);
                     }
+                    // continue the operator that was waiting for the buffer to be saved.
+                    if self.pending_operator != OperatorData::Noop {
+                        state.set_operator(self.pending_operator.take());
+                    }
                     self.unset_loading();
                 }
                 AsyncMsg::OnCheckHealthApi(s) => {
                     if s.is_success() {
-                        toast_success!("API Check Health is Success");
+                        toast_success!(tr!("API server is healthy"));
                     }
                     self.unset_loading();
                 }
                 AsyncMsg::OnMessage(msg) => toast_info!("{msg}"),
+                AsyncMsg::OnImportSettings(path, file) => match self.import_settings(&file) {
+                    Ok(sections) if sections.is_empty() => {
+                        toast_warn!(tr!("No settings found in `{path}`", path = path.display()))
+                    }
+                    Ok(_) => toast_success!(tr!(
+                        "Settings imported from `{path}`",
+                        path = path.display()
+                    )),
+                    Err(err) => toast_error!(tr!("Failed to import settings - {err}", err = err)),
+                },
                 AsyncMsg::OnApiLoadDyno(data) => {
                     match window_stack.idx_mut::<OpenServerWindow>(WSIdx::OpenServer) {
                         Some(window) => window.set_data(data),
@@ -262,13 +730,68 @@ impl DynoControl {
                     }
                     self.unset_loading();
                 }
-                AsyncMsg::OnApiLogin | AsyncMsg::OnApiRegister => {
+                AsyncMsg::OnApiLogin => {
+                    window_stack.set_open(WSIdx::Auth, false);
+                    self.outbox.retry_now();
+                    for request in std::mem::take(&mut self.pending_requests) {
+                        match (request, &self.api_service) {
+                            (ApiRequest::Sync, _) => self.start_sync(),
+                            (request, Some(api)) => {
+                                api.retry_request(request, self.async_channels.0.clone())
+                            }
+                            (_, None) => {}
+                        }
+                    }
+                    self.unset_loading();
+                }
+                AsyncMsg::OnApiRegister => {
                     window_stack.set_swap_open(WSIdx::Auth);
                     self.unset_loading();
                 }
+                AsyncMsg::OnSync(event) => {
+                    if let SyncEvent::Failed { checksum, .. } = &event {
+                        self.transfers.remove(checksum);
+                    }
+                    if let Some(err) = self.sync.on_event(event) {
+                        toast_error!(tr!("Failed to sync with server - {err}", err = err));
+                    }
+                }
+                AsyncMsg::OnTransfer(progress) => self.transfers.update(progress),
+                AsyncMsg::OnReview(event) => {
+                    match window_stack.idx_mut::<ReviewWindow>(WSIdx::Review) {
+                        Some(window) => window.on_event(event),
+                        None => dyno_core::log::error!("Failed to Downcast winddow stack"),
+                    }
+                    self.unset_loading();
+                }
+                AsyncMsg::OnApiUnauthorized(request) => {
+                    if let Some(api) = self.api() {
+                        api.clear_session();
+                    }
+                    if request == ApiRequest::Sync {
+                        self.sync.cancel();
+                    }
+                    if let ApiRequest::Upload(id) = &request {
+                        self.outbox
+                            .set_failed(id, &DynoErr::api_error(tr!("Login session is expired")));
+                    } else if !self.pending_requests.contains(&request) {
+                        self.pending_requests.push(request);
+                    }
+                    toast_warn!(tr!(
+                        "Login session is expired, please Login again to continue."
+                    ));
+                    window_stack.set_open(WSIdx::Auth, true);
+                    self.unset_loading();
+                }
             }
         }
 
+        if let Some(api) = &self.api_service {
+            api.refresh_session(&self.async_channels.0);
+        }
+        self.poll_outbox();
+        self.poll_sync();
+
         match (state.get_operator(), self.is_buffer_saved()) {
             // if buffer is saved and operator want to save, do save the buffer, or if buffer
             // already saved, ignore the operator
@@ -276,72 +799,299 @@ impl DynoControl {
             // if buffer is saved and operator want to open file, do open the file to buffer,
             // or is buffer unsaved but operator want ot open file, show popup to save buffer first
             (OperatorData::OpenFile(tp), true) => self.on_open(tp),
-            (OperatorData::OpenFile(_), false) => {
+            (OperatorData::OpenPath, true) => {
+                if let Some((tp, path)) = self.pending_file.take() {
+                    self.on_open_path(tp, path);
+                }
+            }
+            (op @ (OperatorData::OpenFile(_) | OperatorData::OpenPath), false) => {
+                self.pending_operator = op;
                 window_stack.set_open(WSIdx::ConfirmUnsaved, true)
             }
             _ => {}
         }
     }
+
+    /// write the current buffer to the upload outbox, and upload it to the server.
+    pub fn save_to_server(&mut self) {
+        let start = self.start.unwrap_or_default();
+        let stop = self.stop.unwrap_or_default();
+        let id = match self.outbox.push(
+            &self.buffer,
+            &self.markers,
+            self.config.clone(),
+            start,
+            stop,
+            self.vehicle.clone(),
+        ) {
+            Ok(id) => id,
+            Err(err) => {
+                toast_error!(tr!(
+                    "Failed to write data to upload outbox - {err}",
+                    err = err
+                ));
+                return;
+            }
+        };
+        match &self.api_service {
+            Some(api) => api.save_dyno(
+                id.clone(),
+                self.outbox.data_path(&id),
+                self.config.clone(),
+                start,
+                stop,
+                self.async_channels.0.clone(),
+            ),
+            None => {
+                self.outbox
+                    .set_failed(&id, &DynoErr::api_error(tr!("Not connected to API")));
+                toast_warn!(tr!("Not connected to API, the data is kept in Pending Uploads and uploaded when the server is reachable."));
+            }
+        }
+    }
+
+    /// retry the next pending upload in outbox that is due, one upload at a time.
+    fn poll_outbox(&mut self) {
+        let api = match &self.api_service {
+            Some(api) if api.is_logined() => api,
+            _ => return,
+        };
+        if let Some(entry) = self.outbox.take_due() {
+            let path = self.outbox.data_path(&entry.id);
+            api.retry_save_dyno(
+                entry.id,
+                path,
+                entry.config,
+                entry.start,
+                entry.stop,
+                self.async_channels.0.clone(),
+            );
+        }
+    }
+
+    /// start the sync of the local `Saved` runs with the server.
+    pub fn start_sync(&mut self) {
+        let api = match &self.api_service {
+            Some(api) if api.is_logined() => api,
+            _ => {
+                toast_warn!(tr!("Login to the server to sync the runs."));
+                return;
+            }
+        };
+        if !self.sync.start() {
+            return;
+        }
+        api.sync_list(self.async_channels.0.clone());
+
+        let dir = self.sync.dir().to_path_buf();
+        let cache = self.sync.local_cache();
+        let tx = self.async_channels.0.clone();
+        dyno_core::tokio::spawn(async move {
+            let event = match asyncify!(move || scan_local(&dir, &cache)) {
+                Ok(local) => SyncEvent::Local(local),
+                Err(err) => SyncEvent::ScanFailed(err),
+            };
+            ignore_err!(tx.send(AsyncMsg::OnSync(event)));
+        });
+    }
+
+    /// start the uploads and downloads of the running sync.
+    fn poll_sync(&mut self) {
+        let api = match &self.api_service {
+            Some(api) if api.is_logined() => api,
+            _ => return,
+        };
+        while let Some(task) = self.sync.take_next() {
+            api.sync_transfer(task, self.async_channels.0.clone());
+        }
+    }
+
+    /// discard the unsaved buffer and continue the operator waiting for it (open file).
+    pub fn discard_unsaved(&mut self, state: &mut DynoState) {
+        if self.pending_operator != OperatorData::Noop {
+            self.buffer_saved = true;
+            state.set_operator(self.pending_operator.take());
+        }
+    }
+
+    /// cancel the operator waiting for the unsaved buffer.
+    pub fn cancel_pending(&mut self) {
+        self.pending_operator = OperatorData::Noop;
+        self.pending_file = None;
+    }
+
+    /// open file from command line argument or recent files, with the same unsaved
+    /// buffer confirmation as [`OperatorData::OpenFile`].
+    pub fn open_path(&mut self, path: PathBuf, state: &mut DynoState) {
+        if !path.exists() {
+            toast_warn!(tr!("File `{path}` doesn't exists!", path = path.display()));
+            self.app_config.recent_files.remove(&path);
+            return;
+        }
+        match DynoFileType::from_path(&path) {
+            Some(tp) => {
+                self.pending_file = Some((tp, path));
+                state.set_operator(OperatorData::OpenPath);
+            }
+            None => toast_warn!(tr!("Unsupported file type `{path}`", path = path.display())),
+        }
+    }
+
+    /// read the files dropped to the window, a single file is opened to the buffer
+    /// and several files are opened as comparison runs.
+    pub fn handle_dropped_files(&mut self, ctx: &Context, state: &mut DynoState) {
+        if !self.app_config.app_options.drag_and_drop_support {
+            return;
+        }
+        let (hovered, dropped) =
+            ctx.input(|i| (!i.raw.hovered_files.is_empty(), i.raw.dropped_files.clone()));
+        if hovered {
+            let screen_rect = ctx.input(|i| i.screen_rect());
+            let painter = ctx.layer_painter(LayerId::new(
+                Order::Foreground,
+                Id::new("dyno_file_drop_target"),
+            ));
+            painter.rect_filled(screen_rect, 0.0, Color32::from_black_alpha(192));
+            painter.text(
+                screen_rect.center(),
+                Align2::CENTER_CENTER,
+                tr!("Drop files to open (multiple files opened as comparison)"),
+                TextStyle::Heading.resolve(&ctx.style()),
+                Color32::WHITE,
+            );
+        }
+
+        let files = dropped
+            .into_iter()
+            .filter_map(|file| file.path)
+            .filter_map(|path| {
+                let tp = DynoFileType::from_path(&path);
+                if tp.is_none() {
+                    toast_warn!(tr!("Unsupported file type `{path}`", path = path.display()));
+                }
+                tp.map(|tp| (tp, path))
+            })
+            .collect::<Vec<_>>();
+        match files.len() {
+            0 => {}
+            1 => {
+                self.pending_file = files.into_iter().next();
+                state.set_operator(OperatorData::OpenPath);
+            }
+            _ => {
+                for (tp, path) in files {
+                    self.on_open_compare(tp, path);
+                }
+            }
+        }
+    }
     pub fn on_save(&mut self, tp: DynoFileType) {
         use dyno_core::tokio;
 
         let buffer = self.buffer.clone();
+        let frames = self.raw_frames.clone();
+        let markers = self.markers.clone();
+        let config = self.config.clone();
         let loadings = self.loadings.clone();
         let tx = self.async_channels.0.clone();
 
         let dirpath = tp.path(self.paths.get_data_dir_folder("Saved"));
         tokio::spawn(async move {
             loadings.store(true, Ordering::Relaxed);
-            match tp {
+            let file_name = format!("dynotest_{}.{}", Utc::now().timestamp(), tp.extension());
+            let file = match tp {
                 DynoFileType::Dyno => {
-                    match DynoFileManager::save_binaries_async(
-                        format!("dynotest_{}.dyno", Utc::now().timestamp()),
-                        dirpath,
-                    )
-                    .await
-                    {
-                        Some(file) => match asyncify!(move || buffer.compress_to_path(file.path()))
-                        {
-                            Ok(()) => ignore_err!(tx.send(AsyncMsg::OnSavedBuffer(()))),
-                            Err(err) => ignore_err!(tx.send(AsyncMsg::OnError(err))),
-                        },
-                        None => dyno_core::log::debug!("FileManager ppick file canceled"),
-                    }
+                    DynoFileManager::save_binaries_async(file_name, dirpath).await
                 }
-                DynoFileType::Csv => {
-                    match DynoFileManager::save_csv_async(
-                        format!("dynotest_{}.csv", Utc::now().timestamp()),
-                        dirpath,
-                    )
-                    .await
-                    {
-                        Some(file) => {
-                            match asyncify!(move || buffer.save_csv_from_path(file.path())) {
-                                Ok(()) => ignore_err!(tx.send(AsyncMsg::OnSavedBuffer(()))),
-                                Err(err) => ignore_err!(tx.send(AsyncMsg::OnError(err))),
-                            }
+                DynoFileType::Csv => DynoFileManager::save_csv_async(file_name, dirpath).await,
+                DynoFileType::Excel => DynoFileManager::save_excel_async(file_name, dirpath).await,
+                DynoFileType::Json => DynoFileManager::save_json_async(file_name, dirpath).await,
+                DynoFileType::Parquet => {
+                    DynoFileManager::save_parquet_async(file_name, dirpath).await
+                }
+                DynoFileType::Mdf => DynoFileManager::save_mdf_async(file_name, dirpath).await,
+            };
+            match file {
+                Some(file) => {
+                    let path = file.path().to_path_buf();
+                    let save_path = path.clone();
+                    match asyncify!(move || {
+                        formats::save_to_path(tp, &buffer, &markers, &save_path)?;
+                        raw::save_config(&save_path, &config)?;
+                        if frames.is_empty() {
+                            return Ok(());
                         }
-                        None => dyno_core::log::debug!("FileManager ppick file canceled"),
+                        frames.save(&RawFrames::sidecar_path(&save_path))
+                    }) {
+                        Ok(()) => {
+                            ignore_err!(tx.send(AsyncMsg::OnSavedFile(tp, path)));
+                            ignore_err!(tx.send(AsyncMsg::OnSavedBuffer(())));
+                        }
+                        Err(err) => ignore_err!(tx.send(AsyncMsg::OnError(err))),
                     }
                 }
-                DynoFileType::Excel => match DynoFileManager::save_excel_async(
-                    format!("dynotest_{}.xlsx", Utc::now().timestamp()),
-                    dirpath,
-                )
-                .await
-                {
-                    Some(file) => match asyncify!(move || buffer.save_excel_from_path(file.path()))
-                    {
-                        Ok(()) => ignore_err!(tx.send(AsyncMsg::OnSavedBuffer(()))),
-                        Err(err) => ignore_err!(tx.send(AsyncMsg::OnError(err))),
-                    },
-                    None => dyno_core::log::debug!("FileManager ppick file canceled"),
-                },
-            };
+                None => dyno_core::log::debug!("FileManager ppick file canceled"),
+            }
             loadings.store(false, Ordering::Relaxed);
         });
     }
 
+    pub fn on_export_settings(&self) {
+        use dyno_core::tokio;
+
+        let file = match self.settings_file() {
+            Ok(file) => file,
+            Err(err) => {
+                toast_error!(tr!("Failed to export settings - {err}", err = err));
+                return;
+            }
+        };
+        let tx = self.async_channels.0.clone();
+        let dir = std::env::current_dir().unwrap_or_default();
+        tokio::spawn(async move {
+            let Some(handle) = DynoFileManager::save_toml_async(Self::SETTINGS_FILE, dir).await
+            else {
+                dyno_core::log::debug!("FileManager save file canceled");
+                return;
+            };
+            let path = handle.path().to_path_buf();
+            let save_path = path.clone();
+            let msg = match asyncify!(move || file.save(&save_path)) {
+                Ok(()) => {
+                    AsyncMsg::message(tr!("Settings exported to `{path}`", path = path.display()))
+                }
+                Err(err) => AsyncMsg::error(DynoErr::input_output_error(tr!(
+                    "Failed to export settings - {err}",
+                    err = err
+                ))),
+            };
+            ignore_err!(tx.send(msg));
+        });
+    }
+
+    pub fn on_import_settings(&self) {
+        use dyno_core::tokio;
+
+        let tx = self.async_channels.0.clone();
+        let dir = std::env::current_dir().unwrap_or_default();
+        tokio::spawn(async move {
+            let Some(handle) = DynoFileManager::pick_toml_async(dir).await else {
+                dyno_core::log::debug!("FileManager pick file canceled");
+                return;
+            };
+            let path = handle.path().to_path_buf();
+            let open_path = path.clone();
+            let msg = match asyncify!(move || SettingsFile::open(&open_path)) {
+                Ok(file) => AsyncMsg::OnImportSettings(path, file),
+                Err(err) => AsyncMsg::error(DynoErr::input_error(tr!(
+                    "Failed to import settings - {err}",
+                    err = err
+                ))),
+            };
+            ignore_err!(tx.send(msg));
+        });
+    }
+
     pub fn on_open(&mut self, tp: DynoFileType) {
         use dyno_core::tokio;
 
@@ -351,34 +1101,187 @@ impl DynoControl {
 
         tokio::spawn(async move {
             loadings.store(true, Ordering::Relaxed);
-            match tp {
-                DynoFileType::Dyno => match DynoFileManager::pick_binaries_async(dirpath).await {
-                    Some(file) => {
-                        match asyncify!(move || BufferData::decompress_from_path(file.path())) {
-                            Ok(data) => ignore_err!(tx.send(AsyncMsg::open_buffer(data))),
-                            Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
-                        }
-                    }
-                    None => dyno_core::log::debug!("FileManager ppick file canceled"),
-                },
-                DynoFileType::Csv => match DynoFileManager::pick_csv_async(dirpath).await {
-                    Some(file) => {
-                        match asyncify!(move || BufferData::open_csv_from_path(file.path())) {
-                            Ok(data) => ignore_err!(tx.send(AsyncMsg::open_buffer(data))),
-                            Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
-                        }
-                    }
-                    None => dyno_core::log::debug!("FileManager ppick file canceled"),
-                },
-                DynoFileType::Excel => match DynoFileManager::pick_excel_async(dirpath).await {
-                    Some(file) => {
-                        match asyncify!(move || BufferData::open_excel_from_path(file.path())) {
-                            Ok(data) => ignore_err!(tx.send(AsyncMsg::open_buffer(data))),
-                            Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
-                        }
-                    }
-                    None => dyno_core::log::debug!("FileManager ppick file canceled"),
+            let file = match tp {
+                DynoFileType::Dyno => DynoFileManager::pick_binaries_async(dirpath).await,
+                DynoFileType::Csv => DynoFileManager::pick_csv_async(dirpath).await,
+                DynoFileType::Excel => DynoFileManager::pick_excel_async(dirpath).await,
+                DynoFileType::Json => DynoFileManager::pick_json_async(dirpath).await,
+                DynoFileType::Parquet => DynoFileManager::pick_parquet_async(dirpath).await,
+                DynoFileType::Mdf => DynoFileManager::pick_mdf_async(dirpath).await,
+            };
+            match file {
+                Some(file) => open_path_task(tp, file.path().to_path_buf(), &tx).await,
+                None => dyno_core::log::debug!("FileManager ppick file canceled"),
+            }
+            loadings.store(false, Ordering::Relaxed);
+        });
+    }
+}
+
+/// open the file in `path` to the buffer, and add it to the recent files on success.
+async fn open_path_task(tp: DynoFileType, path: PathBuf, tx: &Sender<AsyncMsg>) {
+    let open_path = path.clone();
+    match asyncify!(move || {
+        let (data, markers) = formats::open_with_markers(tp, &open_path)?;
+        let frames = RawFrames::open_sidecar(&open_path);
+        Ok((data, frames, markers))
+    }) {
+        Ok((data, frames, markers)) => {
+            ignore_err!(tx.send(AsyncMsg::recent_file(tp, path.clone())));
+            ignore_err!(tx.send(AsyncMsg::open_buffer(data)));
+            ignore_err!(tx.send(AsyncMsg::OnOpenRaw(path, Box::new(frames))));
+            ignore_err!(tx.send(AsyncMsg::OnOpenMarkers(markers)));
+        }
+        Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
+    }
+}
+
+impl DynoControl {
+    pub fn on_export(&mut self, tp: DynoFileType, options: ExportOptions) {
+        use dyno_core::tokio;
+
+        let buffer = self.buffer.clone();
+        let config = self.config.clone();
+        let markers = self.markers.clone();
+        let (start, stop) = (self.start, self.stop);
+        let loadings = self.loadings.clone();
+        let tx = self.async_channels.0.clone();
+
+        let dirpath = tp.path(self.paths.get_data_dir_folder("Exported"));
+        tokio::spawn(async move {
+            loadings.store(true, Ordering::Relaxed);
+            let file_name = format!("dynotest_{}.{}", Utc::now().timestamp(), tp.extension());
+            let file = match tp {
+                DynoFileType::Excel => DynoFileManager::save_excel_async(file_name, dirpath).await,
+                _ => DynoFileManager::save_csv_async(file_name, dirpath).await,
+            };
+            match file {
+                Some(file) => match asyncify!(move || {
+                    let table =
+                        ExportTable::new(&buffer, &config, &markers, start, stop, &options)?;
+                    export_to_path(tp, &table, file.path()).map(|_| file.path().to_path_buf())
+                }) {
+                    Ok(path) => ignore_err!(tx.send(AsyncMsg::message(tr!(
+                        "Data exported to `{path}`",
+                        path = path.display()
+                    )))),
+                    Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
                 },
+                None => dyno_core::log::debug!("FileManager ppick file canceled"),
+            }
+            loadings.store(false, Ordering::Relaxed);
+        });
+    }
+}
+
+impl DynoControl {
+    /// save `buffer` processed with `config` from the raw frames as a new revision next to
+    /// the run file, and open the revision.
+    pub fn save_revision(&mut self, buffer: BufferData, config: DynoConfig) {
+        use dyno_core::tokio;
+
+        let source = match &self.run_file {
+            Some(path) => path.clone(),
+            None => {
+                toast_warn!(tr!("Save the run to a file before saving a revision"));
+                return;
+            }
+        };
+        let tp = match DynoFileType::from_path(&source) {
+            Some(tp) => tp,
+            None => {
+                toast_warn!(tr!(
+                    "Unsupported file type `{path}`",
+                    path = source.display()
+                ));
+                return;
+            }
+        };
+        let frames = self.raw_frames.clone();
+        let markers = self.markers.clone();
+        let loadings = self.loadings.clone();
+        let tx = self.async_channels.0.clone();
+        tokio::spawn(async move {
+            loadings.store(true, Ordering::Relaxed);
+            let path = raw::revision_path(&source);
+            let save_path = path.clone();
+            let result = asyncify!(move || {
+                formats::save_to_path(tp, &buffer, &markers, &save_path)?;
+                frames.save(&RawFrames::sidecar_path(&save_path))?;
+                let info = RevisionInfo {
+                    source,
+                    time: Utc::now().naive_local(),
+                    config,
+                };
+                let info_path = RevisionInfo::info_path(&save_path);
+                std::fs::write(info_path, dyno_core::toml::to_string(&info)?)?;
+                Ok((buffer, frames, markers))
+            });
+            match result {
+                Ok((buffer, frames, markers)) => {
+                    ignore_err!(tx.send(AsyncMsg::open_buffer(buffer)));
+                    ignore_err!(tx.send(AsyncMsg::OnOpenRaw(path.clone(), Box::new(frames))));
+                    ignore_err!(tx.send(AsyncMsg::OnOpenMarkers(markers)));
+                    ignore_err!(tx.send(AsyncMsg::message(tr!(
+                        "Revision saved to `{path}`",
+                        path = path.display()
+                    ))));
+                    ignore_err!(tx.send(AsyncMsg::OnSavedFile(tp, path)));
+                    ignore_err!(tx.send(AsyncMsg::OnSavedBuffer(())));
+                }
+                Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
+            }
+            loadings.store(false, Ordering::Relaxed);
+        });
+    }
+
+    pub fn on_open_path(&mut self, tp: DynoFileType, path: PathBuf) {
+        use dyno_core::tokio;
+
+        let loadings = self.loadings.clone();
+        let tx = self.async_channels.0.clone();
+        tokio::spawn(async move {
+            loadings.store(true, Ordering::Relaxed);
+            open_path_task(tp, path, &tx).await;
+            loadings.store(false, Ordering::Relaxed);
+        });
+    }
+
+    pub fn on_open_compare(&mut self, tp: DynoFileType, path: PathBuf) {
+        use dyno_core::tokio;
+
+        let loadings = self.loadings.clone();
+        let tx = self.async_channels.0.clone();
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| tp.to_string());
+        tokio::spawn(async move {
+            loadings.store(true, Ordering::Relaxed);
+            match asyncify!(move || formats::open_from_path(tp, &path)) {
+                Ok(data) => ignore_err!(tx.send(AsyncMsg::open_compare(name, data))),
+                Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
+            }
+            loadings.store(false, Ordering::Relaxed);
+        });
+    }
+
+    pub fn on_import(&mut self, path: PathBuf, profile: ImportProfile, as_compare: bool) {
+        use dyno_core::tokio;
+
+        let loadings = self.loadings.clone();
+        let tx = self.async_channels.0.clone();
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| profile.name.clone());
+
+        tokio::spawn(async move {
+            loadings.store(true, Ordering::Relaxed);
+            match asyncify!(move || import::import(&path, &profile)) {
+                Ok(data) if as_compare => ignore_err!(tx.send(AsyncMsg::open_compare(name, data))),
+                Ok(data) => ignore_err!(tx.send(AsyncMsg::open_buffer(data))),
+                Err(err) => ignore_err!(tx.send(AsyncMsg::error(err))),
             }
             loadings.store(false, Ordering::Relaxed);
         });
@@ -393,92 +1296,231 @@ impl DynoControl {
         window_stack: &mut WindowStack,
         state: &mut DynoState,
     ) {
-        ui.menu_button("File", |menu_ui| {
+        ui.menu_button(tr!("File"), |menu_ui| {
             if menu_ui.open_button().clicked() {
                 log::debug!("Open Button menu clicked");
                 state.set_operator(OperatorData::OpenFile(DynoFileType::Dyno));
             }
-            menu_ui.menu_button("Open As..", |submenu_ui| {
-                if submenu_ui.button("Csv File").clicked() {
+            menu_ui.menu_button(tr!("Open As.."), |submenu_ui| {
+                if submenu_ui.button(tr!("Csv File")).clicked() {
                     state.set_operator(OperatorData::OpenFile(DynoFileType::Csv));
                     log::debug!("Open as Csv file submenu clicked");
                 }
-                if submenu_ui.button("Excel File").clicked() {
+                if submenu_ui.button(tr!("Excel File")).clicked() {
                     state.set_operator(OperatorData::OpenFile(DynoFileType::Excel));
                     log::debug!("Open as Excel file submenu clicked");
                 }
-                if submenu_ui.button("Binaries File").clicked() {
+                if submenu_ui.button(tr!("Binaries File")).clicked() {
                     state.set_operator(OperatorData::OpenFile(DynoFileType::Dyno));
                     log::debug!("Open as Binaries file submenu clicked");
                 }
+                submenu_ui.separator();
+                if submenu_ui.button(tr!("Json Lines File")).clicked() {
+                    state.set_operator(OperatorData::OpenFile(DynoFileType::Json));
+                    log::debug!("Open as Json Lines file submenu clicked");
+                }
+                if submenu_ui.button(tr!("Parquet File")).clicked() {
+                    state.set_operator(OperatorData::OpenFile(DynoFileType::Parquet));
+                    log::debug!("Open as Parquet file submenu clicked");
+                }
+                if submenu_ui.button(tr!("MDF4 File")).clicked() {
+                    state.set_operator(OperatorData::OpenFile(DynoFileType::Mdf));
+                    log::debug!("Open as MDF4 file submenu clicked");
+                }
+            });
+            menu_ui.add_enabled_ui(!self.app_config.recent_files.is_empty(), |menu_ui| {
+                menu_ui.menu_button(tr!("Open Recent"), |submenu_ui| {
+                    let mut selected = None;
+                    for recent in &self.app_config.recent_files.files {
+                        let name = recent
+                            .path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        if submenu_ui
+                            .button(format!("[{}] {name}", recent.file_type))
+                            .on_hover_text(recent.path.display().to_string())
+                            .clicked()
+                        {
+                            selected = Some(recent.path.clone());
+                        }
+                    }
+                    submenu_ui.separator();
+                    if submenu_ui.button(tr!("Clear Recent")).clicked() {
+                        self.app_config.recent_files.clear();
+                        submenu_ui.close_menu();
+                    }
+                    if let Some(path) = selected {
+                        log::debug!("Open recent file `{}` clicked", path.display());
+                        self.open_path(path, state);
+                        submenu_ui.close_menu();
+                    }
+                });
             });
             if menu_ui.save_button().clicked() {
                 log::debug!("Save file menu clicked");
                 state.set_operator(OperatorData::SaveFile(DynoFileType::Dyno));
             }
-            menu_ui.menu_button("Save As..", |submenu_ui| {
-                if submenu_ui.button("Csv File").clicked() {
+            menu_ui.menu_button(tr!("Save As.."), |submenu_ui| {
+                if submenu_ui.button(tr!("Csv File")).clicked() {
                     log::debug!("Save as Csv file submenu clicked");
                     state.set_operator(OperatorData::SaveFile(DynoFileType::Csv));
                 }
-                if submenu_ui.button("Excel File").clicked() {
+                if submenu_ui.button(tr!("Excel File")).clicked() {
                     log::debug!("Save as Excel file submenu clicked");
                     state.set_operator(OperatorData::SaveFile(DynoFileType::Excel));
                 }
-                if submenu_ui.button("Binaries File").clicked() {
+                if submenu_ui.button(tr!("Binaries File")).clicked() {
                     log::debug!("Save as Binaries file submenu clicked");
                     state.set_operator(OperatorData::SaveFile(DynoFileType::Dyno));
                 }
+                submenu_ui.separator();
+                if submenu_ui.button(tr!("Json Lines File")).clicked() {
+                    log::debug!("Save as Json Lines file submenu clicked");
+                    state.set_operator(OperatorData::SaveFile(DynoFileType::Json));
+                }
+                if submenu_ui.button(tr!("Parquet File")).clicked() {
+                    log::debug!("Save as Parquet file submenu clicked");
+                    state.set_operator(OperatorData::SaveFile(DynoFileType::Parquet));
+                }
+                if submenu_ui.button(tr!("MDF4 File")).clicked() {
+                    log::debug!("Save as MDF4 file submenu clicked");
+                    state.set_operator(OperatorData::SaveFile(DynoFileType::Mdf));
+                }
             });
-            if menu_ui.button("Quit").clicked() {
+            if menu_ui.button(tr!("Import..")).clicked() {
+                log::debug!("Import submenu clicked");
+                window_stack.set_open(WSIdx::Import, true);
+            }
+            if menu_ui.button(tr!("Export..")).clicked() {
+                log::debug!("Export submenu clicked");
+                window_stack.set_open(WSIdx::Export, true);
+            }
+            if menu_ui.button(tr!("Reprocess..")).clicked() {
+                log::debug!("Reprocess submenu clicked");
+                window_stack.set_open(WSIdx::Reprocess, true);
+            }
+            if menu_ui.button(tr!("Event Markers..")).clicked() {
+                log::debug!("Event Markers submenu clicked");
+                window_stack.set_open(WSIdx::Markers, true);
+            }
+            menu_ui.separator();
+            if menu_ui
+                .button(tr!("Export Settings.."))
+                .on_hover_text(tr!(
+                    "Save all settings to one TOML file, for a backup or another PC"
+                ))
+                .clicked()
+            {
+                log::debug!("Export Settings submenu clicked");
+                self.on_export_settings();
+                menu_ui.close_menu();
+            }
+            if menu_ui
+                .button(tr!("Import Settings.."))
+                .on_hover_text(tr!(
+                    "Load the settings from a TOML file, the sections not in the file are kept"
+                ))
+                .clicked()
+            {
+                log::debug!("Import Settings submenu clicked");
+                self.on_import_settings();
+                menu_ui.close_menu();
+            }
+            menu_ui.separator();
+            if menu_ui.button(tr!("Quit")).clicked() {
                 log::debug!("Exit submenu clicked");
                 window_stack.set_open(WSIdx::ConfirmQuit, true);
             }
         });
-        ui.menu_button("View", |submenu_ui| {
-            submenu_ui.checkbox(state.show_bottom_panel_mut(), "Bottom Panel");
-            submenu_ui.checkbox(state.show_left_panel_mut(), "Left Panel");
+        ui.menu_button(tr!("View"), |submenu_ui| {
+            submenu_ui.checkbox(state.show_bottom_panel_mut(), tr!("Bottom Panel"));
+            submenu_ui.checkbox(state.show_left_panel_mut(), tr!("Left Panel"));
             if submenu_ui
-                .checkbox(state.show_logger_window_mut(), "Logger Window")
+                .checkbox(state.show_logger_window_mut(), tr!("Logger Window"))
                 .changed()
             {
                 window_stack.set_open(WSIdx::Logger, state.show_logger_window())
             }
         });
-        if ui.button("Config").clicked() {
+        if ui.button(tr!("Config")).clicked() {
             log::debug!("Config submenu clicked");
             window_stack.set_swap_open(WSIdx::Setting);
         }
-        if ui.button("Help").clicked() {
+        if ui.button(tr!("Calibration")).clicked() {
+            log::debug!("Calibration submenu clicked");
+            window_stack.set_swap_open(WSIdx::Calibration);
+        }
+        if ui.button(tr!("Vehicles")).clicked() {
+            log::debug!("Vehicles submenu clicked");
+            window_stack.set_swap_open(WSIdx::Vehicles);
+        }
+        if ui.button(tr!("Runs")).clicked() {
+            log::debug!("Runs submenu clicked");
+            window_stack.set_swap_open(WSIdx::Runs);
+        }
+        if ui.button(tr!("Help")).clicked() {
             log::debug!("Help submenu clicked");
             window_stack.set_swap_open(WSIdx::Help);
         }
-        if ui.button("About").clicked() {
+        if ui.button(tr!("About")).clicked() {
             log::debug!("About submenu clicked");
             window_stack.set_swap_open(WSIdx::About);
         }
 
         ui.with_layout(Layout::right_to_left(Align::Center), |rtl_ui| {
             eframe::egui::widgets::global_dark_light_mode_switch(rtl_ui);
+            if !self.transfers.is_empty() {
+                let text = self
+                    .transfers
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                rtl_ui.spinner().on_hover_text(text);
+            }
+            if !self.outbox.is_empty()
+                && rtl_ui
+                    .button(tr!(
+                        "⏳ Pending Uploads ({count})",
+                        count = self.outbox.len()
+                    ))
+                    .on_hover_text(tr!(
+                        "data that failed to upload to server, retried automatically"
+                    ))
+                    .clicked()
+            {
+                window_stack.set_swap_open(WSIdx::Outbox);
+            }
             match &self.api_service {
                 Some(api) if api.is_logined() => {
-                    if rtl_ui.button("Logout").clicked() {
+                    if rtl_ui.button(tr!("Logout")).clicked() {
                         log::info!("Logout button clicked");
                         api.logout(self.tx().clone());
                     }
-                    if rtl_ui.button("Open from Server").clicked() {
+                    if api.is_reviewer()
+                        && rtl_ui
+                            .button(tr!("📝 Review"))
+                            .on_hover_text(tr!("verify or reject the runs uploaded by students"))
+                            .clicked()
+                    {
+                        window_stack.set_swap_open(WSIdx::Review);
+                    }
+                    if rtl_ui.button(tr!("Open from Server")).clicked() {
                         log::info!("Opening Window Open Project from server...");
                         window_stack.set_swap_open(WSIdx::OpenServer);
                     }
-                    if rtl_ui.button("Save to Server").clicked() {
+                    if rtl_ui.button(tr!("Save to Server")).clicked() {
                         log::info!("Opening Window Save Project to to server...");
                         window_stack.set_swap_open(WSIdx::SaveServer);
                     }
                 }
                 _ => {
                     if rtl_ui
-                        .button("Login")
-                        .on_hover_text("login first to access server, like saving data to server.")
+                        .button(tr!("Login"))
+                        .on_hover_text(tr!(
+                            "login first to access server, like saving data to server."
+                        ))
                         .clicked()
                     {
                         log::info!("Login bottom clicked");
@@ -491,13 +1533,14 @@ impl DynoControl {
 
     #[inline(always)]
     pub fn bottom_status(&mut self, ui: &mut Ui) {
+        let mut action = None;
         let layout_ui_status = |ltr_ui: &mut Ui| match &mut self.serial {
             Some(serial) => {
                 let serial_open = serial.is_open();
                 let (status, color) = if serial_open {
-                    ("STATUS: Running", Color32::BLUE)
+                    (tr!("STATUS: Running"), Color32::BLUE)
                 } else {
-                    ("STATUS: Connected", Color32::GREEN)
+                    (tr!("STATUS: Connected"), Color32::GREEN)
                 };
                 let PortInfo {
                     port_name,
@@ -507,65 +1550,78 @@ impl DynoControl {
                 }: &PortInfo = serial.get_info();
                 Label::new(RichText::new(status).color(color))
                     .ui(ltr_ui)
-                    .on_hover_text(format!("PORT INFO: [{port_name}] ({vid}:{pid})"));
+                    .on_hover_text(tr!(
+                        "PORT INFO: [{port}] ({vid}:{pid})",
+                        port = port_name,
+                        vid = vid,
+                        pid = pid
+                    ));
                 ltr_ui.separator();
                 let btn_start = ltr_ui
                     .small_play_button()
-                    .on_hover_text("Click to Start the Service");
+                    .on_hover_text(tr!("Click to Start the Service"));
                 let btn_stop = ltr_ui
                     .small_stop_button()
-                    .on_hover_text("Click to Stop/Pause the Service");
+                    .on_hover_text(tr!("Click to Stop/Pause the Service"));
                 let btn_reset = ltr_ui
                     .small_reset_button()
-                    .on_hover_text("Click to Stop and Reset recorded data buffer");
-                match (
-                    btn_start.clicked(),
-                    btn_stop.clicked(),
-                    btn_reset.clicked(),
-                    serial_open,
-                ) {
-                    (true, _, _, false) => {
-                        if let Err(err) = serial.start(self.async_channels.0.clone()) {
-                            toast_error!("Serial Service Failed to start - {err}")
-                        }
-                        self.start = Some(Utc::now().naive_utc());
-                    }
-                    (_, true, _, true) => {
-                        serial.stop();
-                        self.stop = Some(Utc::now().naive_utc());
-                    }
-                    (_, _, true, true) => {
-                        serial.stop();
-                        self.buffer.clean();
-                    }
-                    (_, _, true, _) => self.buffer.clean(),
-                    _ => {}
+                    .on_hover_text(tr!("Click to Stop and Reset recorded data buffer"));
+                let btn_mark = ltr_ui
+                    .small_button("🚩")
+                    .on_hover_text(tr!("Click to mark an event in the run"));
+                if btn_start.clicked() {
+                    action = Some(Action::StartRecording);
+                } else if btn_stop.clicked() {
+                    action = Some(Action::StopRecording);
+                } else if btn_reset.clicked() {
+                    action = Some(Action::ResetRecording);
+                } else if btn_mark.clicked() {
+                    action = Some(Action::MarkEvent);
                 }
             }
             None => {
-                Label::new(RichText::new("STATUS: Not Initialize / Connected").color(Color32::RED))
-                    .sense(Sense::union(Sense::click(), Sense::hover()))
-                    .ui(ltr_ui)
-                    .on_hover_text(
-                        "PORT INFO: [NO PORT DETECTED] (XX:XX), click to try Initialize the port",
-                    );
-                if ltr_ui.button("\u{1F50C} Try Reconnect").clicked() {
+                Label::new(
+                    RichText::new(tr!("STATUS: Not Initialize / Connected")).color(Color32::RED),
+                )
+                .sense(Sense::union(Sense::click(), Sense::hover()))
+                .ui(ltr_ui)
+                .on_hover_text(tr!(
+                    "PORT INFO: [NO PORT DETECTED] (XX:XX), click to try Initialize the port"
+                ));
+                if ltr_ui.button(tr!("🔌 Try Reconnect")).clicked() {
                     if let Some(serial) = SerialService::new() {
-                        toast_success!(
-                            "SUCCES! connected to [{}] - [{}:{}]",
-                            serial.info.port_name,
-                            serial.info.vid,
-                            serial.info.pid
-                        );
+                        toast_success!(tr!(
+                            "Connected to serial port [{port}]:[{vid}-{pid}]",
+                            port = serial.info.port_name,
+                            vid = serial.info.vid,
+                            pid = serial.info.pid
+                        ));
                         self.serial = Some(serial);
                     }
                 }
             }
         };
         ui.with_layout(Layout::left_to_right(Align::Center), layout_ui_status);
+        match action {
+            Some(Action::StartRecording) => self.start_recording(),
+            Some(Action::StopRecording) => self.stop_recording(),
+            Some(Action::ResetRecording) => self.reset_recording(),
+            Some(Action::MarkEvent) => self.mark_event(String::new()),
+            _ => {}
+        }
         ui.separator();
         ui.with_layout(Layout::right_to_left(Align::Center), |rtl_ui| {
-            rtl_ui.small(format!("Active Info: {}", self.config.motor_type));
+            rtl_ui.small(tr!("Active Info: {motor}", motor = self.config.motor_type));
+            if let Some(vehicle) = self
+                .vehicle
+                .as_deref()
+                .and_then(|id| self.vehicles.vehicle(id))
+            {
+                rtl_ui.small(tr!("Vehicle: {plate}", plate = vehicle.plate));
+            }
+            if let Some(preset) = &self.preset {
+                rtl_ui.small(tr!("Preset: {name}", name = preset));
+            }
         });
     }
 
@@ -600,13 +1656,13 @@ impl DynoControl {
             row_label_value!(
                 grid_ui,
                 rpm_engine,
-                "Rpm ENgine (Mesin)",
+                "Rpm Engine (Sensor)",
                 "calculated from rotational engine from rpm driver sensor in dynotests chasis",
             );
             row_label_value!(
                 grid_ui,
                 rpm_roda,
-                "Rpm Tire (Roda)",
+                "Rpm Tire",
                 "calculated from rotational of the roller in dynotests",
             );
             grid_ui.end_row();
@@ -626,11 +1682,8 @@ impl DynoControl {
             row_label_value!(
                 grid_ui,
                 odo,
-                "ODO (Jarak Tempuh)",
-                r#"
-Distance Traveled / Jarak Tempuh calculated distance 
-from rotational rounds of the roller in dynotests chasis
-                "#,
+                "ODO",
+                "distance traveled, calculated from the rotation count of the roller in dynotests chasis",
             );
             row_label_value!(
                 grid_ui,
@@ -656,7 +1709,7 @@ from rotational rounds of the roller in dynotests chasis
             uis[0].add(Gauge::speed(*speed).diameter(uis[0].available_width()));
             uis[1].add(Gauge::rpm_engine(*rpm_engine).diameter(uis[1].available_width()));
         });
-        CollapsingHeader::new("Gauges Other")
+        CollapsingHeader::new(tr!("Gauges Other"))
             .id_source("dyno_gauges_other_collapse_id")
             .show(ui, |ui| {
                 ui.columns(3, |uis| {
@@ -682,8 +1735,12 @@ from rotational rounds of the roller in dynotests chasis
             ..
         } = self.buffer.last();
         const MULTPL_WIDTH: f32 = 0.19;
-        const HEADING_SEGMENTS: [&str; 4] =
-            ["Speed (km/h)", "RPM x 1000", "ODO (km)", "Time (HH:MM:SS)"];
+        let heading_segments = [
+            tr!("Speed (km/h)"),
+            tr!("RPM x 1000"),
+            tr!("ODO (km)"),
+            tr!("Time (HH:MM:SS)"),
+        ];
         let value_segments = [
             format!("{:7.2}", speed.value()),
             format!("{:7.2}", rpm_engine.value() * 0.001),
@@ -693,7 +1750,7 @@ from rotational rounds of the roller in dynotests chasis
         let iter_segmented_ui = |(idx, segment_ui): (usize, &mut Ui)| {
             segment_ui.group(|uigroup_inner| {
                 uigroup_inner.vertical_centered(|uivert_inner| {
-                    uivert_inner.strong(HEADING_SEGMENTS[idx]);
+                    uivert_inner.strong(heading_segments[idx]);
                     let digit_height = uivert_inner.available_width() * MULTPL_WIDTH;
                     SegmentedDisplay::dyno_seven_segment(&value_segments[idx])
                         .style_preset(self.app_config.segment_display_style)
@@ -709,11 +1766,46 @@ from rotational rounds of the roller in dynotests chasis
                 .for_each(iter_segmented_ui);
         });
         ui.separator();
-        self.plots.ui(ui, &self.buffer);
+        self.plots.ui(ui, &self.buffer, &self.markers);
+    }
+
+    /// run the actions of the pressed shortcuts, after the windows took their key events.
+    pub fn key_events(
+        &mut self,
+        ctx: &Context,
+        window_stack: &mut WindowStack,
+        state: &mut DynoState,
+    ) {
+        for action in self.app_config.keymap.pressed(ctx) {
+            log::debug!("Shortcut of `{action:?}` pressed");
+            self.on_action(action, window_stack, state);
+        }
+    }
+
+    pub fn on_action(
+        &mut self,
+        action: Action,
+        window_stack: &mut WindowStack,
+        state: &mut DynoState,
+    ) {
+        match action {
+            Action::StartRecording => self.start_recording(),
+            Action::StopRecording => self.stop_recording(),
+            Action::ResetRecording => self.reset_recording(),
+            Action::Open => state.set_operator(OperatorData::OpenFile(DynoFileType::Dyno)),
+            Action::Save => state.set_operator(OperatorData::save_default()),
+            Action::ToggleLeftPanel => state.swap_show_left_panel(),
+            Action::ToggleBottomPanel => state.swap_show_bottom_panel(),
+            Action::MarkEvent => self.mark_event(String::new()),
+            Action::NextPlotPanel => self.plots.next_panel(),
+            Action::OpenSettings => window_stack.set_swap_open(WSIdx::Setting),
+        }
     }
 
     #[inline]
     pub fn handle_states(&mut self, ctx: &Context) {
+        // the language can be changed from settings, import or reset of the settings.
+        i18n::set_language(self.app_config.language);
         if self.loadings.load(Ordering::Relaxed) {
             ctx.layer_painter(LayerId::new(
                 Order::Background,
@@ -728,11 +1820,24 @@ from rotational rounds of the roller in dynotests chasis
                 .order(Order::Foreground)
                 .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, 0.0))
                 .show(ctx, |ui| {
-                    ui.add(
-                        Spinner::new()
-                            .color(crate::COLOR_BLUE_DYNO)
-                            .size(ctx.available_rect().height() / 2.),
-                    )
+                    ui.vertical_centered(|ui| {
+                        ui.add(
+                            Spinner::new()
+                                .color(crate::COLOR_BLUE_DYNO)
+                                .size(ctx.available_rect().height() / 2.),
+                        );
+                        for progress in self
+                            .transfers
+                            .iter()
+                            .filter(|p| p.kind == TransferKind::Download)
+                        {
+                            ui.add(
+                                ProgressBar::new(progress.fraction())
+                                    .desired_width(240.)
+                                    .text(progress.to_string()),
+                            );
+                        }
+                    });
                 });
         }
     }
@@ -0,0 +1,109 @@
+//! Event markers of a csv save. The records are written and read by `dyno_core`, the markers
+//! are appended after the records as an extra section: an empty line, a `marker,time_stamp,text`
+//! header and one `marker` row for every marker. A save without markers is the plain csv of
+//! `dyno_core`, the section is removed again before `dyno_core` reads the records.
+use std::{fs::OpenOptions, io::Write as _, path::Path};
+
+use super::{format_error, markers::Markers};
+use dyno_core::{BufferData, DynoResult};
+
+const MARKER_TAG: &str = "marker";
+const SECTION_HEADER: &str = "marker,time_stamp,text";
+
+/// append the markers section to the csv in `path`, nothing is written without markers.
+pub fn append_markers(markers: &Markers, path: &Path) -> DynoResult<()> {
+    if markers.is_empty() {
+        return Ok(());
+    }
+    let err_fn = |err| format_error("Csv", path, err);
+    let mut section = csv::Writer::from_writer(vec![]);
+    section
+        .write_record(SECTION_HEADER.split(','))
+        .map_err(err_fn)?;
+    for marker in markers.iter() {
+        let time_stamp = marker.time_stamp.to_string();
+        section
+            .write_record([MARKER_TAG, &time_stamp, &marker.text])
+            .map_err(err_fn)?;
+    }
+    let section = section
+        .into_inner()
+        .map_err(|err| format_error("Csv", path, err))?;
+
+    let ends_with_newline = std::fs::read(path)?.ends_with(b"\n");
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    if !ends_with_newline {
+        file.write_all(b"\n")?;
+    }
+    file.write_all(b"\n")?;
+    file.write_all(&section).map_err(From::from)
+}
+
+/// start of the markers section header in `data`, after the empty line.
+fn section_start(data: &[u8]) -> Option<usize> {
+    let header = SECTION_HEADER.as_bytes();
+    (1..data.len()).find(|&idx| data[idx..].starts_with(header) && data[..idx].ends_with(b"\n\n"))
+}
+
+/// open the records with `dyno_core` and the markers of [`append_markers`].
+pub fn open(path: &Path) -> DynoResult<(BufferData, Markers)> {
+    let data = std::fs::read(path)?;
+    let Some(start) = section_start(&data) else {
+        return Ok((BufferData::open_csv_from_path(path)?, Markers::default()));
+    };
+
+    let mut markers = Markers::default();
+    let mut reader = csv::Reader::from_reader(&data[start..]);
+    for row in reader.records() {
+        let row = row.map_err(|err| format_error("Csv", path, err))?;
+        if row.get(0) != Some(MARKER_TAG) {
+            continue;
+        }
+        let time_stamp = row.get(1).unwrap_or_default();
+        let Ok(time_stamp) = time_stamp.parse::<i64>() else {
+            return Err(format_error(
+                "Csv",
+                path,
+                format!("invalid marker time stamp `{time_stamp}`"),
+            ));
+        };
+        markers.push(time_stamp, row.get(2).unwrap_or_default());
+    }
+
+    // `dyno_core` only reads a csv file, give it the records without the section.
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let records = std::env::temp_dir().join(format!("dynotest-{}-{file_name}", std::process::id()));
+    std::fs::write(&records, &data[..start - 1])?;
+    let buffer = BufferData::open_csv_from_path(&records);
+    std::fs::remove_file(&records).ok();
+    Ok((buffer?, markers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{buffer_from_records, records, sample_markers, sample_records, test_path};
+
+    #[test]
+    fn markers_after_native_records() {
+        let path = test_path("run.csv");
+        let sample = sample_records();
+        let markers = sample_markers(&sample);
+        buffer_from_records(sample)
+            .save_csv_from_path(&path)
+            .unwrap();
+        let native = std::fs::read(&path).unwrap();
+        let native_records = records(&BufferData::open_csv_from_path(&path).unwrap());
+
+        append_markers(&markers, &path).unwrap();
+        let saved = std::fs::read(&path).unwrap();
+        let opened = open(&path);
+        std::fs::remove_file(&path).ok();
+
+        assert!(saved.starts_with(&native));
+        assert_eq!(section_start(&native), None);
+        let (buffer, opened_markers) = opened.unwrap();
+        assert_eq!(records(&buffer), native_records);
+        assert_eq!(opened_markers, markers);
+    }
+}
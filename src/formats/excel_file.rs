@@ -0,0 +1,201 @@
+//! Event markers of an excel save. The records are written and read by `dyno_core`, the markers
+//! are added to the saved workbook as an extra `Markers` worksheet part, with the `time_stamp`
+//! and `marker` columns. The parts written by `dyno_core` are copied as they are, a save without
+//! markers is the plain workbook of `dyno_core`.
+use std::{
+    io::{Cursor, Read as _, Write as _},
+    path::Path,
+};
+
+use calamine::{open_workbook_auto, DataType, Reader as _};
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use super::{format_error, markers::Markers, TIME_CHANNEL};
+use dyno_core::{BufferData, DynoResult};
+
+const MARKER_SHEET: &str = "Markers";
+const MARKER_COLUMN: &str = "marker";
+const MARKER_PART: &str = "xl/worksheets/dyno_markers.xml";
+const MARKER_REL_ID: &str = "rIdDynoMarkers";
+
+const WORKBOOK: &str = "xl/workbook.xml";
+const WORKBOOK_RELS: &str = "xl/_rels/workbook.xml.rels";
+const CONTENT_TYPES: &str = "[Content_Types].xml";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn text_cell(cell: &str, text: &str) -> String {
+    format!(
+        r#"<c r="{cell}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+        escape(text)
+    )
+}
+
+fn marker_sheet(markers: &Markers) -> String {
+    let mut rows = format!(
+        r#"<row r="1">{}{}</row>"#,
+        text_cell("A1", TIME_CHANNEL.name),
+        text_cell("B1", MARKER_COLUMN)
+    );
+    for (idx, marker) in markers.iter().enumerate() {
+        let row = idx + 2;
+        rows.push_str(&format!(
+            r#"<row r="{row}"><c r="A{row}"><v>{}</v></c>{}</row>"#,
+            marker.time_stamp,
+            text_cell(&format!("B{row}"), &marker.text)
+        ));
+    }
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><cols><col min="1" max="1" width="16" customWidth="1"/><col min="2" max="2" width="40" customWidth="1"/></cols><sheetData>{rows}</sheetData></worksheet>"#
+    )
+}
+
+/// `part` inserted in `xml` before the last `end_tag`.
+fn insert_before(xml: &str, end_tag: &str, part: &str) -> Option<String> {
+    let idx = xml.rfind(end_tag)?;
+    Some([&xml[..idx], part, &xml[idx..]].concat())
+}
+
+/// the workbook part `name` with the markers sheet added.
+fn add_sheet(name: &str, xml: &str) -> Option<String> {
+    match name {
+        WORKBOOK => {
+            if !xml.contains("xmlns:r=") {
+                return None;
+            }
+            let sheet_id = xml
+                .split("sheetId=\"")
+                .skip(1)
+                .filter_map(|s| s.split('"').next()?.parse::<u32>().ok())
+                .max()
+                .unwrap_or_default()
+                + 1;
+            let sheet = format!(
+                r#"<sheet name="{MARKER_SHEET}" sheetId="{sheet_id}" r:id="{MARKER_REL_ID}"/>"#
+            );
+            insert_before(xml, "</sheets>", &sheet)
+        }
+        WORKBOOK_RELS => insert_before(
+            xml,
+            "</Relationships>",
+            &format!(
+                r#"<Relationship Id="{MARKER_REL_ID}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/dyno_markers.xml"/>"#
+            ),
+        ),
+        _ => insert_before(
+            xml,
+            "</Types>",
+            &format!(
+                r#"<Override PartName="/{MARKER_PART}" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+            ),
+        ),
+    }
+}
+
+/// add the markers sheet to the workbook in `path`, nothing is written without markers.
+pub fn add_markers(markers: &Markers, path: &Path) -> DynoResult<()> {
+    if markers.is_empty() {
+        return Ok(());
+    }
+    let err_fn = |err| format_error("Excel", path, err);
+    let mut archive = ZipArchive::new(Cursor::new(std::fs::read(path)?)).map_err(err_fn)?;
+    let mut output = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for idx in 0..archive.len() {
+        let name = archive.by_index_raw(idx).map_err(err_fn)?.name().to_owned();
+        if ![WORKBOOK, WORKBOOK_RELS, CONTENT_TYPES].contains(&name.as_str()) {
+            let part = archive.by_index_raw(idx).map_err(err_fn)?;
+            output.raw_copy_file(part).map_err(err_fn)?;
+            continue;
+        }
+        let mut xml = String::new();
+        archive
+            .by_index(idx)
+            .map_err(err_fn)?
+            .read_to_string(&mut xml)?;
+        let Some(xml) = add_sheet(&name, &xml) else {
+            return Err(format_error(
+                "Excel",
+                path,
+                format!("unsupported layout of `{name}`, the markers are not saved"),
+            ));
+        };
+        output.start_file(name, options).map_err(err_fn)?;
+        output.write_all(xml.as_bytes())?;
+    }
+    output.start_file(MARKER_PART, options).map_err(err_fn)?;
+    output.write_all(marker_sheet(markers).as_bytes())?;
+
+    let data = output.finish().map_err(err_fn)?.into_inner();
+    std::fs::write(path, data).map_err(From::from)
+}
+
+/// open the records with `dyno_core` and the markers of [`add_markers`].
+pub fn open(path: &Path) -> DynoResult<(BufferData, Markers)> {
+    let buffer = BufferData::open_excel_from_path(path)?;
+    let mut workbook = open_workbook_auto(path).map_err(|err| format_error("Excel", path, err))?;
+    let mut markers = Markers::default();
+    if let Some(range) = workbook.worksheet_range(MARKER_SHEET) {
+        let range = range.map_err(|err| format_error("Excel", path, err))?;
+        for row in range.rows().skip(1) {
+            if let Some(time_stamp) = row.first().and_then(DataType::as_f64) {
+                let text = row.get(1).and_then(DataType::as_string);
+                markers.push(time_stamp as i64, text.unwrap_or_default());
+            }
+        }
+    }
+    Ok((buffer, markers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{buffer_from_records, records, sample_markers, sample_records, test_path};
+
+    fn parts(path: &Path) -> Vec<(String, Vec<u8>)> {
+        let mut archive = ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+        (0..archive.len())
+            .map(|idx| {
+                let mut part = archive.by_index(idx).unwrap();
+                let mut data = vec![];
+                part.read_to_end(&mut data).unwrap();
+                (part.name().to_owned(), data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn markers_sheet_in_native_workbook() {
+        let path = test_path("run.xlsx");
+        let sample = sample_records();
+        let markers = sample_markers(&sample);
+        buffer_from_records(sample)
+            .save_excel_from_path(&path)
+            .unwrap();
+        let native = parts(&path);
+        let native_records = records(&BufferData::open_excel_from_path(&path).unwrap());
+
+        add_markers(&markers, &path).unwrap();
+        let saved = parts(&path);
+        let opened = open(&path);
+        std::fs::remove_file(&path).ok();
+
+        // only the sheet list, relationships and content types are changed.
+        for (name, data) in &native {
+            let (_, saved_data) = saved.iter().find(|(n, _)| n == name).unwrap();
+            let changed = [WORKBOOK, WORKBOOK_RELS, CONTENT_TYPES].contains(&name.as_str());
+            assert_eq!(saved_data != data, changed, "{name}");
+        }
+        assert!(saved.iter().any(|(name, _)| name == MARKER_PART));
+        let (buffer, opened_markers) = opened.unwrap();
+        assert_eq!(records(&buffer), native_records);
+        assert_eq!(opened_markers, markers);
+    }
+}
@@ -0,0 +1,630 @@
+//! Export of [`BufferData`] to csv or excel with only the selected channels, time window,
+//! sampling, filtering and unit system, prefixed by a header block with the run metadata
+//! and the [`DynoConfig`] values, so the spreadsheet can be read as is. The event markers in
+//! the exported range are written after the header block (csv) or in the `Markers` sheet (excel).
+use std::{fmt::Write as _, ops::Range, path::Path};
+
+use rust_xlsxwriter::{Format, Workbook};
+
+use super::{
+    datetime_from_millis, format_error, markers::Markers, records, Record, CHANNELS, CHANNEL_SIZE,
+};
+use crate::{state::DynoFileType, tr};
+use dyno_core::{
+    chrono::{Local, NaiveDateTime},
+    serde, toml, BufferData, DynoConfig, DynoErr, DynoResult,
+};
+
+/// index of `rpm_roda` in [`CHANNELS`], the roller speed is always available to detect a pull.
+const PULL_CHANNEL: usize = 1;
+/// how far the roller rpm may drop from the peak before the pull is considered finished.
+const PULL_DROP_TOLERANCE: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum ExportRange {
+    #[default]
+    All,
+    Window,
+    DetectedPull,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum Sampling {
+    #[default]
+    Original,
+    Decimate,
+    Resample,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum ValueKind {
+    Filtered,
+    #[default]
+    Raw,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+impl ExportRange {
+    pub const ALL: [Self; 3] = [Self::All, Self::Window, Self::DetectedPull];
+}
+impl Sampling {
+    pub const ALL: [Self; 3] = [Self::Original, Self::Decimate, Self::Resample];
+}
+impl ValueKind {
+    pub const ALL: [Self; 2] = [Self::Filtered, Self::Raw];
+}
+impl UnitSystem {
+    pub const ALL: [Self; 2] = [Self::Metric, Self::Imperial];
+
+    /// unit label of channel at `idx` in [`CHANNELS`] for this unit system.
+    pub fn unit(self, idx: usize) -> &'static str {
+        match (self, CHANNELS[idx].name) {
+            (Self::Imperial, "speed") => "mph",
+            (Self::Imperial, "torque") => "lb·ft",
+            (Self::Imperial, "odo") => "mi",
+            (Self::Imperial, "temp") => "°F",
+            _ => CHANNELS[idx].unit,
+        }
+    }
+
+    /// convert `value` of channel at `idx` from the recorded (metric) unit.
+    pub fn convert(self, idx: usize, value: f64) -> f64 {
+        match (self, CHANNELS[idx].name) {
+            (Self::Imperial, "speed" | "odo") => value * 0.621_371,
+            (Self::Imperial, "torque") => value * 0.737_562,
+            (Self::Imperial, "temp") => value * 9.0 / 5.0 + 32.0,
+            _ => value,
+        }
+    }
+}
+
+impl std::fmt::Display for ExportRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::All => tr!("All Data"),
+            Self::Window => tr!("Time Window"),
+            Self::DetectedPull => tr!("Detected Pull"),
+        })
+    }
+}
+impl std::fmt::Display for Sampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Original => tr!("Original"),
+            Self::Decimate => tr!("Decimate"),
+            Self::Resample => tr!("Resample"),
+        })
+    }
+}
+impl std::fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Filtered => tr!("Filtered"),
+            Self::Raw => tr!("Raw"),
+        })
+    }
+}
+impl std::fmt::Display for UnitSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Metric => tr!("Metric"),
+            Self::Imperial => tr!("Imperial"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ExportOptions {
+    pub channels: [bool; CHANNEL_SIZE],
+    pub range: ExportRange,
+    /// start and end of [`ExportRange::Window`], in seconds from the first record.
+    pub window: (f64, f64),
+    pub sampling: Sampling,
+    /// keep every n-th record on [`Sampling::Decimate`].
+    pub decimation: usize,
+    /// output rate in Hz on [`Sampling::Resample`].
+    pub resample_hz: f64,
+    pub values: ValueKind,
+    /// moving average length in records on [`ValueKind::Filtered`].
+    pub filter_window: usize,
+    pub units: UnitSystem,
+    pub header: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            channels: [true; CHANNEL_SIZE],
+            range: ExportRange::All,
+            window: (0.0, 0.0),
+            sampling: Sampling::Original,
+            decimation: 2,
+            resample_hz: 10.0,
+            values: ValueKind::Raw,
+            filter_window: 5,
+            units: UnitSystem::Metric,
+            header: true,
+        }
+    }
+}
+
+/// find the longest acceleration (pull) in the roller rpm, returned as range of record indexes.
+pub fn detect_pull(records: &[Record]) -> Option<Range<usize>> {
+    let rpm = |idx: usize| records[idx].values[PULL_CHANNEL];
+    let mut best: Option<(Range<usize>, f64)> = None;
+    let (mut start, mut peak) = (0usize, 0usize);
+
+    for idx in 1..=records.len() {
+        let finished = idx == records.len() || rpm(idx) < rpm(peak) * (1.0 - PULL_DROP_TOLERANCE);
+        if finished {
+            let rise = rpm(peak) - rpm(start);
+            if peak > start && rise > 0.0 && best.as_ref().map_or(true, |(_, r)| rise > *r) {
+                best = Some((start..peak + 1, rise));
+            }
+            (start, peak) = (idx, idx);
+        } else if peak == start && rpm(idx) <= rpm(start) {
+            (start, peak) = (idx, idx);
+        } else if rpm(idx) >= rpm(peak) {
+            peak = idx;
+        }
+    }
+    best.map(|(range, _)| range)
+}
+
+fn moving_average(records: &mut [Record], window: usize) {
+    if window < 2 || records.len() < 2 {
+        return;
+    }
+    let half = window / 2;
+    let source = records.to_vec();
+    for (idx, record) in records.iter_mut().enumerate() {
+        let lo = idx.saturating_sub(half);
+        let hi = (idx + half + 1).min(source.len());
+        let count = (hi - lo) as f64;
+        for ch in 0..CHANNEL_SIZE {
+            record.values[ch] = source[lo..hi].iter().map(|r| r.values[ch]).sum::<f64>() / count;
+        }
+    }
+}
+
+fn resample(records: &[Record], hz: f64) -> Vec<Record> {
+    let (first, last) = match (records.first(), records.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return vec![],
+    };
+    let step = (1000.0 / hz.max(0.001)).max(1.0);
+    let mut output = Vec::new();
+    let mut idx = 0usize;
+    let mut time = first.time_stamp as f64;
+    while time <= last.time_stamp as f64 {
+        while idx + 1 < records.len() && (records[idx + 1].time_stamp as f64) < time {
+            idx += 1;
+        }
+        let (a, b) = (records[idx], records[(idx + 1).min(records.len() - 1)]);
+        let span = (b.time_stamp - a.time_stamp) as f64;
+        let t = if span > 0.0 {
+            ((time - a.time_stamp as f64) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        output.push(Record {
+            time_stamp: time.round() as i64,
+            values: std::array::from_fn(|ch| a.values[ch] + (b.values[ch] - a.values[ch]) * t),
+        });
+        time += step;
+    }
+    output
+}
+
+/// flatten a toml table into `prefix.key = value` rows.
+fn flatten_toml(prefix: &str, value: &toml::Value, rows: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let key = match prefix.is_empty() {
+                    true => key.to_owned(),
+                    false => format!("{prefix}.{key}"),
+                };
+                flatten_toml(&key, value, rows);
+            }
+        }
+        toml::Value::String(s) => rows.push((prefix.to_owned(), s.to_owned())),
+        other => rows.push((prefix.to_owned(), other.to_string())),
+    }
+}
+
+/// the processed data ready to be written, with the header block and the column names.
+#[derive(Debug, Clone, Default)]
+pub struct ExportTable {
+    pub header: Vec<(String, String)>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<f64>>,
+    /// time in seconds like the time column, and the text of the markers.
+    pub markers: Vec<(f64, String)>,
+}
+
+impl ExportTable {
+    pub fn new(
+        buffer: &BufferData,
+        config: &DynoConfig,
+        markers: &Markers,
+        start: Option<NaiveDateTime>,
+        stop: Option<NaiveDateTime>,
+        options: &ExportOptions,
+    ) -> DynoResult<Self> {
+        let mut all = records(buffer);
+        let first_time = all.first().map(|r| r.time_stamp).unwrap_or_default();
+
+        let range = match options.range {
+            ExportRange::All => 0..all.len(),
+            ExportRange::Window => {
+                let (from, to) = options.window;
+                let in_window = |r: &Record| {
+                    let secs = (r.time_stamp - first_time) as f64 / 1000.0;
+                    secs >= from && secs <= to
+                };
+                let lo = all.iter().position(in_window).unwrap_or(all.len());
+                let hi = all.iter().rposition(in_window).map_or(lo, |i| i + 1);
+                lo..hi
+            }
+            ExportRange::DetectedPull => detect_pull(&all).ok_or_else(|| {
+                DynoErr::validation_error("No pull (acceleration) detected in the data")
+            })?,
+        };
+        if range.is_empty() {
+            return Err(DynoErr::validation_error(
+                "There is no data to export in the selected time range",
+            ));
+        }
+        all.truncate(range.end);
+        let mut selected = all.split_off(range.start);
+
+        if options.values == ValueKind::Filtered {
+            moving_average(&mut selected, options.filter_window);
+        }
+        let selected = match options.sampling {
+            Sampling::Original => selected,
+            Sampling::Decimate => selected
+                .into_iter()
+                .step_by(options.decimation.max(1))
+                .collect(),
+            Sampling::Resample => resample(&selected, options.resample_hz),
+        };
+
+        let channels = (0..CHANNEL_SIZE)
+            .filter(|idx| options.channels[*idx])
+            .collect::<Vec<_>>();
+        let columns = std::iter::once("Time [s]".to_owned())
+            .chain(
+                channels
+                    .iter()
+                    .map(|&idx| format!("{} [{}]", CHANNELS[idx].desc, options.units.unit(idx))),
+            )
+            .collect();
+        let rows = selected
+            .iter()
+            .map(|record| {
+                std::iter::once((record.time_stamp - first_time) as f64 / 1000.0)
+                    .chain(
+                        channels
+                            .iter()
+                            .map(|&idx| options.units.convert(idx, record.values[idx])),
+                    )
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let markers = match (selected.first(), selected.last()) {
+            (Some(a), Some(b)) => markers
+                .iter()
+                .filter(|m| (a.time_stamp..=b.time_stamp).contains(&m.time_stamp))
+                .map(|m| {
+                    let secs = (m.time_stamp - first_time) as f64 / 1000.0;
+                    (secs, m.text.clone())
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        let mut header = vec![];
+        if options.header {
+            let start =
+                start.unwrap_or_else(|| datetime_from_millis(buffer.time_stamp.first_value()));
+            let stop = stop.unwrap_or_else(|| datetime_from_millis(buffer.time_stamp.last_value()));
+            let sampling = match options.sampling {
+                Sampling::Original => Sampling::Original.to_string(),
+                Sampling::Decimate => tr!(
+                    "Decimate (every {count})",
+                    count = options.decimation.max(1)
+                ),
+                Sampling::Resample => tr!("Resample ({hz} Hz)", hz = options.resample_hz),
+            };
+            let values = match options.values {
+                ValueKind::Filtered => {
+                    tr!(
+                        "Filtered (moving average {count})",
+                        count = options.filter_window
+                    )
+                }
+                ValueKind::Raw => ValueKind::Raw.to_string(),
+            };
+            let export_range = match (selected.first(), selected.last()) {
+                (Some(a), Some(b)) => format!(
+                    "{} ({:.3}s - {:.3}s)",
+                    options.range,
+                    (a.time_stamp - first_time) as f64 / 1000.0,
+                    (b.time_stamp - first_time) as f64 / 1000.0
+                ),
+                _ => options.range.to_string(),
+            };
+            header.extend([
+                (
+                    tr!("Application").to_owned(),
+                    format!(
+                        "{} {}",
+                        crate::PACKAGE_INFO.name,
+                        crate::PACKAGE_INFO.version
+                    ),
+                ),
+                (
+                    tr!("Exported At").to_owned(),
+                    Local::now().naive_local().to_string(),
+                ),
+                (tr!("Run Start").to_owned(), start.to_string()),
+                (tr!("Run Stop").to_owned(), stop.to_string()),
+                (
+                    tr!("Run Duration [s]").to_owned(),
+                    format!("{:.3}", (stop - start).num_milliseconds() as f64 / 1000.0),
+                ),
+                (tr!("Recorded Samples").to_owned(), buffer.len().to_string()),
+                (tr!("Exported Samples").to_owned(), rows.len().to_string()),
+                (tr!("Range").to_owned(), export_range),
+                (tr!("Sampling").to_owned(), sampling),
+                (tr!("Values").to_owned(), values),
+                (tr!("Unit System").to_owned(), options.units.to_string()),
+            ]);
+            match toml::Value::try_from(config) {
+                Ok(value) => flatten_toml("config", &value, &mut header),
+                Err(err) => {
+                    dyno_core::log::warn!("Failed to write config in export header - {err}")
+                }
+            }
+        }
+
+        Ok(Self {
+            header,
+            columns,
+            rows,
+            markers,
+        })
+    }
+
+    pub fn write_csv(&self, path: &Path) -> DynoResult<()> {
+        fn field(out: &mut String, value: &str) {
+            if value.contains([',', '"', '\n']) {
+                out.push('"');
+                out.push_str(&value.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(value);
+            }
+        }
+
+        let mut out = String::new();
+        for (key, value) in &self.header {
+            field(&mut out, key);
+            out.push(',');
+            field(&mut out, value);
+            out.push('\n');
+        }
+        if !self.header.is_empty() {
+            out.push('\n');
+        }
+        for (secs, text) in &self.markers {
+            field(&mut out, tr!("Marker"));
+            let _ = write!(out, ",{secs},");
+            field(&mut out, text);
+            out.push('\n');
+        }
+        if !self.markers.is_empty() {
+            out.push('\n');
+        }
+        for (idx, column) in self.columns.iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            field(&mut out, column);
+        }
+        out.push('\n');
+        for row in &self.rows {
+            for (idx, value) in row.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{value}");
+            }
+            out.push('\n');
+        }
+        std::fs::write(path, out).map_err(|err| format_error("Csv", path, err))
+    }
+
+    pub fn write_excel(&self, path: &Path) -> DynoResult<()> {
+        let err_fn = |err| format_error("Excel", path, err);
+        let bold = Format::new().set_bold();
+
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("DynoTests").map_err(err_fn)?;
+
+        let mut row = 0u32;
+        for (key, value) in &self.header {
+            sheet
+                .write_with_format(row, 0, key, &bold)
+                .map_err(err_fn)?;
+            sheet.write(row, 1, value).map_err(err_fn)?;
+            row += 1;
+        }
+        if !self.header.is_empty() {
+            row += 1;
+        }
+        for (col, column) in self.columns.iter().enumerate() {
+            sheet
+                .write_with_format(row, col as u16, column, &bold)
+                .map_err(err_fn)?;
+        }
+        sheet.set_freeze_panes(row + 1, 0).map_err(err_fn)?;
+        for values in &self.rows {
+            row += 1;
+            for (col, value) in values.iter().enumerate() {
+                sheet.write(row, col as u16, *value).map_err(err_fn)?;
+            }
+        }
+        sheet.set_column_width(0, 24).map_err(err_fn)?;
+        if self.columns.len() > 1 {
+            let last = self.columns.len() as u16 - 1;
+            sheet.set_column_range_width(1, last, 18).map_err(err_fn)?;
+        }
+
+        if !self.markers.is_empty() {
+            let sheet = workbook.add_worksheet();
+            sheet.set_name("Markers").map_err(err_fn)?;
+            sheet
+                .write_with_format(0, 0, tr!("Time [s]"), &bold)
+                .and_then(|sheet| sheet.write_with_format(0, 1, tr!("Marker"), &bold))
+                .and_then(|sheet| sheet.set_column_width(1, 40))
+                .map_err(err_fn)?;
+            for (row, (secs, text)) in self.markers.iter().enumerate() {
+                let row = row as u32 + 1;
+                sheet
+                    .write(row, 0, *secs)
+                    .and_then(|sheet| sheet.write(row, 1, text))
+                    .map_err(err_fn)?;
+            }
+        }
+        workbook.save(path).map_err(err_fn)
+    }
+}
+
+/// export the buffer as csv or excel, other file types are not supported by the export.
+pub fn export_to_path(tp: DynoFileType, table: &ExportTable, path: &Path) -> DynoResult<()> {
+    match tp {
+        DynoFileType::Csv => table.write_csv(path),
+        DynoFileType::Excel => table.write_excel(path),
+        other => Err(DynoErr::validation_error(format!(
+            "Export to {other} file is not supported, use csv or excel"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{buffer_from_records, sample_markers, sample_records};
+
+    fn record(time_stamp: i64, value: f64) -> Record {
+        Record {
+            time_stamp,
+            values: [value; CHANNEL_SIZE],
+        }
+    }
+
+    #[test]
+    fn detect_pull_in_records() {
+        // idle, pull from record 10 to 30, then coast down.
+        let records = (0..50)
+            .map(|i| {
+                let rpm = match i {
+                    0..=9 => 100.0,
+                    10..=30 => 100.0 + (i - 10) as f64 * 50.0,
+                    _ => 1100.0 - (i - 30) as f64 * 40.0,
+                };
+                record(i * 100, rpm)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(detect_pull(&records), Some(10..31));
+        assert_eq!(detect_pull(&records[..10]), None);
+    }
+
+    #[test]
+    fn moving_average_at_the_edges() {
+        let mut records = [0.0, 3.0, 6.0, 30.0].map(|v| record(0, v));
+        moving_average(&mut records, 3);
+        let values = records.map(|r| r.values[0]);
+        assert_eq!(values, [1.5, 3.0, 13.0, 18.0]);
+    }
+
+    #[test]
+    fn resample_interpolates() {
+        let records = [record(0, 0.0), record(250, 10.0), record(500, 30.0)];
+        let resampled = resample(&records, 8.0);
+        assert_eq!(
+            resampled.iter().map(|r| r.time_stamp).collect::<Vec<_>>(),
+            [0, 125, 250, 375, 500]
+        );
+        assert_eq!(
+            resampled.iter().map(|r| r.values[3]).collect::<Vec<_>>(),
+            [0.0, 5.0, 10.0, 20.0, 30.0]
+        );
+        assert!(resample(&[], 8.0).is_empty());
+    }
+
+    #[test]
+    fn window_in_imperial_units() {
+        let records = sample_records();
+        let markers = sample_markers(&records);
+        let mut channels = [false; CHANNEL_SIZE];
+        for idx in [0, 3, 6] {
+            channels[idx] = true;
+        }
+        let options = ExportOptions {
+            channels,
+            range: ExportRange::Window,
+            window: (2.0, 3.0),
+            units: UnitSystem::Imperial,
+            header: false,
+            ..Default::default()
+        };
+        let buffer = buffer_from_records(records);
+        let table = ExportTable::new(
+            &buffer,
+            &DynoConfig::default(),
+            &markers,
+            None,
+            None,
+            &options,
+        )
+        .unwrap();
+
+        assert!(table.header.is_empty());
+        assert_eq!(
+            table.columns,
+            [
+                "Time [s]",
+                "Speed [mph]",
+                "Torque [lb·ft]",
+                "Engine Temperature [°F]"
+            ]
+        );
+        assert_eq!(
+            table.rows.iter().map(|row| row[0]).collect::<Vec<_>>(),
+            [2.0, 2.25, 2.5, 2.75, 3.0]
+        );
+        // record 8 at 2 s: speed 12 km/h, torque 15 Nm and temp 18 °C.
+        let close = |value: f64, expected: f64| (value - expected).abs() < 1e-9;
+        assert!(close(table.rows[0][1], 12.0 * 0.621_371));
+        assert!(close(table.rows[0][2], 15.0 * 0.737_562));
+        assert!(close(table.rows[0][3], 64.4));
+        // only the marker of record 10 is in the window.
+        assert_eq!(table.markers, [(2.5, "gear shift, 2nd".to_owned())]);
+    }
+}
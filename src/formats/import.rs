@@ -0,0 +1,511 @@
+//! Import of third-party dyno and datalogger csv files (Dynojet-style exports, ECU loggers)
+//! into [`BufferData`], using an [`ImportProfile`] that maps the foreign columns and units.
+use std::path::Path;
+
+use super::{buffer_from_records, format_error, Record, CHANNELS, CHANNEL_SIZE};
+use dyno_core::{chrono::Utc, serde, BufferData, DynoErr, DynoResult};
+
+const FORMAT_NAME: &str = "Csv Import";
+const PREVIEW_ROWS: usize = 10;
+/// `HP = Nm * rpm / HP_TORQUE_CONSTANT`, mechanical horsepower.
+const HP_TORQUE_CONSTANT: f64 = 7120.91;
+
+const IDX_RPM_ENGINE: usize = 2;
+const IDX_TORQUE: usize = 3;
+const IDX_HORSEPOWER: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum Delimiter {
+    #[default]
+    Auto,
+    Comma,
+    Semicolon,
+    Tab,
+}
+
+impl Delimiter {
+    pub const ALL: [Self; 4] = [Self::Auto, Self::Comma, Self::Semicolon, Self::Tab];
+
+    fn detect(self, line: &str) -> u8 {
+        match self {
+            Self::Comma => b',',
+            Self::Semicolon => b';',
+            Self::Tab => b'\t',
+            Self::Auto => [b',', b';', b'\t']
+                .into_iter()
+                .max_by_key(|d| line.bytes().filter(|b| b == d).count())
+                .unwrap_or(b','),
+        }
+    }
+}
+
+impl std::fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "Auto",
+            Self::Comma => "Comma (,)",
+            Self::Semicolon => "Semicolon (;)",
+            Self::Tab => "Tab",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum TimeUnit {
+    #[default]
+    Seconds,
+    Milliseconds,
+    Microseconds,
+}
+
+impl TimeUnit {
+    pub const ALL: [Self; 3] = [Self::Seconds, Self::Milliseconds, Self::Microseconds];
+
+    #[inline]
+    pub fn to_millis(self, value: f64) -> f64 {
+        match self {
+            Self::Seconds => value * 1000.0,
+            Self::Milliseconds => value,
+            Self::Microseconds => value * 0.001,
+        }
+    }
+}
+
+impl std::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Seconds => "s",
+            Self::Milliseconds => "ms",
+            Self::Microseconds => "µs",
+        })
+    }
+}
+
+/// unit of a foreign column, converted to the unit of the [`CHANNELS`] on import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum ImportUnit {
+    /// same unit as the channel in [`CHANNELS`].
+    #[default]
+    Native,
+    MilesPerHour,
+    MetresPerSecond,
+    PoundFeet,
+    KiloWatt,
+    MetricHorsePower,
+    Miles,
+    Metres,
+    Fahrenheit,
+}
+
+impl ImportUnit {
+    /// the units that can be selected for channel at `idx` in [`CHANNELS`].
+    pub fn for_channel(idx: usize) -> &'static [Self] {
+        match CHANNELS[idx].name {
+            "speed" => &[Self::Native, Self::MilesPerHour, Self::MetresPerSecond],
+            "torque" => &[Self::Native, Self::PoundFeet],
+            "horsepower" => &[Self::Native, Self::KiloWatt, Self::MetricHorsePower],
+            "odo" => &[Self::Native, Self::Miles, Self::Metres],
+            "temp" => &[Self::Native, Self::Fahrenheit],
+            _ => &[Self::Native],
+        }
+    }
+
+    #[inline]
+    pub fn to_native(self, value: f64) -> f64 {
+        match self {
+            Self::Native => value,
+            Self::MilesPerHour | Self::Miles => value * 1.609_344,
+            Self::MetresPerSecond => value * 3.6,
+            Self::PoundFeet => value * 1.355_818,
+            Self::KiloWatt => value * 1.341_022,
+            Self::MetricHorsePower => value * 0.986_320,
+            Self::Metres => value * 0.001,
+            Self::Fahrenheit => (value - 32.0) * 5.0 / 9.0,
+        }
+    }
+
+    pub fn label(self, idx: usize) -> &'static str {
+        match self {
+            Self::Native => CHANNELS[idx].unit,
+            Self::MilesPerHour => "mph",
+            Self::MetresPerSecond => "m/s",
+            Self::PoundFeet => "lb·ft",
+            Self::KiloWatt => "kW",
+            Self::MetricHorsePower => "PS",
+            Self::Miles => "mi",
+            Self::Metres => "m",
+            Self::Fahrenheit => "°F",
+        }
+    }
+}
+
+/// mapping of a foreign column by its header name, with the unit of the values.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ColumnMap<U> {
+    pub column: Option<String>,
+    pub unit: U,
+}
+
+/// reusable mapping of a foreign csv layout, saved in `import_profiles.toml`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ImportProfile {
+    pub name: String,
+    pub delimiter: Delimiter,
+    /// lines before the header row, for files with a title block.
+    pub skip_rows: usize,
+    /// values written with decimal comma (`1,5`), only used with non comma delimiter.
+    pub decimal_comma: bool,
+    pub time: ColumnMap<TimeUnit>,
+    pub channels: [ColumnMap<ImportUnit>; CHANNEL_SIZE],
+}
+
+impl Default for ImportProfile {
+    fn default() -> Self {
+        Self {
+            name: "Custom".to_owned(),
+            delimiter: Delimiter::Auto,
+            skip_rows: 0,
+            decimal_comma: false,
+            time: ColumnMap::default(),
+            channels: Default::default(),
+        }
+    }
+}
+
+impl ImportProfile {
+    /// Dynojet-style export, imperial units with engine rpm.
+    pub fn dynojet() -> Self {
+        let mut profile = Self {
+            name: "Dynojet CSV".to_owned(),
+            ..Default::default()
+        };
+        profile.channels[0].unit = ImportUnit::MilesPerHour;
+        profile.channels[IDX_TORQUE].unit = ImportUnit::PoundFeet;
+        profile
+    }
+
+    /// ECU datalogger, metric units and commonly semicolon separated.
+    pub fn ecu_logger() -> Self {
+        Self {
+            name: "ECU Logger".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::dynojet(), Self::ecu_logger()]
+    }
+
+    /// fill the unmapped columns by matching the words of the header names with common
+    /// keywords, and pick the unit from the header when it is written in there (`Speed (mph)`).
+    /// The keywords match whole words, `hp` is not found in `Speed (MPH)`.
+    pub fn guess_columns(&mut self, headers: &[String]) {
+        let find = |keywords: &[&str], exclude: &[&str]| {
+            headers.iter().find(|h| {
+                let words = words(h);
+                keywords.iter().any(|k| has_words(&words, k))
+                    && !exclude.iter().any(|e| has_words(&words, e))
+            })
+        };
+        if self.time.column.is_none() {
+            if let Some(header) = find(&["time", "waktu", "sec", "seconds"], &["date"]) {
+                let words = words(header);
+                if has_words(&words, "ms") || has_words(&words, "msec") {
+                    self.time.unit = TimeUnit::Milliseconds;
+                }
+                self.time.column = Some(header.clone());
+            }
+        }
+
+        let keywords: [(&[&str], &[&str]); CHANNEL_SIZE] = [
+            (
+                &["speed", "mph", "km/h", "kph", "kecepatan"],
+                &["engine", "rpm"],
+            ),
+            (
+                &["roller", "drum", "roda", "wheel"],
+                &["mph", "km/h", "kph"],
+            ),
+            (
+                &["rpm", "engine speed"],
+                &["roller", "drum", "roda", "wheel"],
+            ),
+            (&["torque", "trq"], &[]),
+            (&["power", "hp", "kw"], &[]),
+            (&["odo", "odometer", "distance", "jarak"], &[]),
+            (&["temp", "temperature", "suhu", "ect", "coolant"], &[]),
+        ];
+        for (idx, (keywords, exclude)) in keywords.into_iter().enumerate() {
+            let map = &mut self.channels[idx];
+            if map.column.is_some() {
+                continue;
+            }
+            let Some(header) = find(keywords, exclude) else {
+                continue;
+            };
+            let words = words(header);
+            let unit = ImportUnit::for_channel(idx)
+                .iter()
+                .copied()
+                .find(|unit| *unit != ImportUnit::Native && has_words(&words, unit.label(idx)));
+            if let Some(unit) = unit {
+                map.unit = unit;
+            } else if has_words(&words, "ft") && idx == IDX_TORQUE {
+                map.unit = ImportUnit::PoundFeet;
+            }
+            map.column = Some(header.clone());
+        }
+    }
+}
+
+/// lowercase words of the header, `Speed (MPH)` is `["speed", "mph"]`.
+fn words(header: &str) -> Vec<String> {
+    header
+        .to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '/' | '·' | '°')))
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// the words of `keyword` are next to each other in `words`.
+fn has_words(words: &[String], keyword: &str) -> bool {
+    let keyword = self::words(keyword);
+    !keyword.is_empty()
+        && words
+            .windows(keyword.len())
+            .any(|w| w == keyword.as_slice())
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ImportProfiles {
+    pub profiles: Vec<ImportProfile>,
+}
+
+impl ImportProfiles {
+    pub const FILE_NAME: &'static str = "import_profiles.toml";
+}
+
+/// headers and the first rows of the file, shown in the column mapping dialog.
+#[derive(Debug, Clone, Default)]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+type CsvReader = csv::Reader<std::io::Cursor<String>>;
+
+/// csv reader after the skipped rows, with the (detected) delimiter.
+fn reader(path: &Path, profile: &ImportProfile) -> DynoResult<(CsvReader, u8)> {
+    let content = std::fs::read(path)?;
+    let content = String::from_utf8_lossy(&content);
+    let content = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .skip(profile.skip_rows)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let delimiter = profile
+        .delimiter
+        .detect(content.lines().next().unwrap_or_default());
+    let reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(std::io::Cursor::new(content));
+    Ok((reader, delimiter))
+}
+
+pub fn preview(path: &Path, profile: &ImportProfile) -> DynoResult<CsvPreview> {
+    let err_fn = |err| format_error(FORMAT_NAME, path, err);
+    let (mut reader, _) = reader(path, profile)?;
+    let headers = reader
+        .headers()
+        .map_err(err_fn)?
+        .iter()
+        .map(ToOwned::to_owned)
+        .collect();
+    let rows = reader
+        .records()
+        .take(PREVIEW_ROWS)
+        .map(|row| row.map(|r| r.iter().map(ToOwned::to_owned).collect()))
+        .collect::<Result<_, _>>()
+        .map_err(err_fn)?;
+    Ok(CsvPreview { headers, rows })
+}
+
+fn parse_value(value: &str, decimal_comma: bool) -> Option<f64> {
+    let value = value.trim();
+    match decimal_comma {
+        true => value.replace('.', "").replace(',', ".").parse().ok(),
+        false => value.parse().ok(),
+    }
+}
+
+pub fn import(path: &Path, profile: &ImportProfile) -> DynoResult<BufferData> {
+    let err_fn = |err| format_error(FORMAT_NAME, path, err);
+    let (mut reader, delimiter) = reader(path, profile)?;
+    let headers = reader.headers().map_err(err_fn)?.clone();
+    let column_idx = |name: &Option<String>| -> DynoResult<Option<usize>> {
+        match name {
+            Some(name) => headers
+                .iter()
+                .position(|h| h.eq_ignore_ascii_case(name.trim()))
+                .map(Some)
+                .ok_or_else(|| {
+                    DynoErr::validation_error(format!(
+                        "Column `{name}` from profile `{}` is not found in `{}`",
+                        profile.name,
+                        path.display()
+                    ))
+                }),
+            None => Ok(None),
+        }
+    };
+    let time_idx = column_idx(&profile.time.column)?
+        .ok_or_else(|| DynoErr::validation_error("Time column is not mapped in import profile"))?;
+    let mut channel_idx = [None; CHANNEL_SIZE];
+    for (idx, map) in profile.channels.iter().enumerate() {
+        channel_idx[idx] = column_idx(&map.column)?;
+    }
+    // decimal comma is ambiguous with the comma delimiter.
+    let decimal_comma = profile.decimal_comma && delimiter != b',';
+
+    let start_time = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(dyno_core::chrono::DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now())
+        .timestamp_millis();
+
+    let mut first_time = None;
+    let mut records = Vec::new();
+    for row in reader.records() {
+        let row = row.map_err(err_fn)?;
+        // skip unit rows or empty lines after the header.
+        let Some(time) = row
+            .get(time_idx)
+            .and_then(|v| parse_value(v, decimal_comma))
+        else {
+            continue;
+        };
+        let time = profile.time.unit.to_millis(time);
+        let first = *first_time.get_or_insert(time);
+        let mut record = Record {
+            time_stamp: start_time + (time - first).round() as i64,
+            ..Default::default()
+        };
+        for (idx, column) in channel_idx.iter().enumerate() {
+            if let Some(value) = column
+                .and_then(|c| row.get(c))
+                .and_then(|v| parse_value(v, decimal_comma))
+            {
+                record.values[idx] = profile.channels[idx].unit.to_native(value);
+            }
+        }
+        records.push(record);
+    }
+    if records.is_empty() {
+        return Err(DynoErr::validation_error(format!(
+            "No data rows found in `{}` with profile `{}`",
+            path.display(),
+            profile.name
+        )));
+    }
+
+    // power and torque can be derived from each other with the engine rpm.
+    let has_rpm = channel_idx[IDX_RPM_ENGINE].is_some();
+    match (channel_idx[IDX_TORQUE], channel_idx[IDX_HORSEPOWER]) {
+        (Some(_), None) if has_rpm => records.iter_mut().for_each(|r| {
+            r.values[IDX_HORSEPOWER] =
+                r.values[IDX_TORQUE] * r.values[IDX_RPM_ENGINE] / HP_TORQUE_CONSTANT
+        }),
+        (None, Some(_)) if has_rpm => records.iter_mut().for_each(|r| {
+            if r.values[IDX_RPM_ENGINE] > 0.0 {
+                r.values[IDX_TORQUE] =
+                    r.values[IDX_HORSEPOWER] * HP_TORQUE_CONSTANT / r.values[IDX_RPM_ENGINE]
+            }
+        }),
+        _ => {}
+    }
+
+    Ok(buffer_from_records(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{records, test_path};
+
+    fn headers(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn dynojet_csv_in_native_units() {
+        let path = test_path("dynojet.csv");
+        std::fs::write(
+            &path,
+            "Run 1\nTime (sec),Engine Speed (RPM),Speed (MPH),Torque (ft-lbs),Correction\n\
+             0.0,3000,10.0,50.0,1.0\n0.5,3500,12.0,55.0,1.0\n",
+        )
+        .unwrap();
+        // the units come from the headers, not from a preset profile.
+        let mut profile = ImportProfile {
+            skip_rows: 1,
+            ..Default::default()
+        };
+        let preview = preview(&path, &profile).unwrap();
+        profile.guess_columns(&preview.headers);
+        let buffer = import(&path, &profile);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(preview.rows.len(), 2);
+        assert_eq!(profile.time.column.as_deref(), Some("Time (sec)"));
+        assert_eq!(profile.channels[0].unit, ImportUnit::MilesPerHour);
+        assert_eq!(profile.channels[IDX_TORQUE].unit, ImportUnit::PoundFeet);
+        assert_eq!(profile.channels[IDX_HORSEPOWER].column, None);
+
+        let records = records(&buffer.unwrap());
+        assert_eq!(records.len(), 2);
+        let [first, second] = [records[0], records[1]];
+        let close = |value: f64, expected: f64| (value - expected).abs() < 1e-9;
+        assert_eq!(second.time_stamp - first.time_stamp, 500);
+        assert!(close(second.values[0], 12.0 * 1.609_344));
+        assert!(close(second.values[IDX_RPM_ENGINE], 3500.0));
+        assert!(close(second.values[IDX_TORQUE], 55.0 * 1.355_818));
+        // the missing power is derived from the torque in Nm.
+        let hp = 55.0 * 1.355_818 * 3500.0 / 7120.91;
+        assert!(close(second.values[IDX_HORSEPOWER], hp));
+    }
+
+    #[test]
+    fn guess_columns_on_whole_words() {
+        let mut profile = ImportProfile::default();
+        profile.guess_columns(&headers(&[
+            "Date Time",
+            "Time (ms)",
+            "Engine Speed",
+            "Wheel RPM",
+            "Shp",
+            "Torque (Nm)",
+        ]));
+        assert_eq!(profile.time.column.as_deref(), Some("Time (ms)"));
+        assert_eq!(profile.time.unit, TimeUnit::Milliseconds);
+        // `Engine Speed` is the engine rpm, not the vehicle speed.
+        assert_eq!(profile.channels[0].column, None);
+        assert_eq!(profile.channels[1].column.as_deref(), Some("Wheel RPM"));
+        assert_eq!(
+            profile.channels[IDX_RPM_ENGINE].column.as_deref(),
+            Some("Engine Speed")
+        );
+        assert_eq!(profile.channels[IDX_TORQUE].unit, ImportUnit::Native);
+        // `hp` is not a word of `Shp`.
+        assert_eq!(profile.channels[IDX_HORSEPOWER].column, None);
+    }
+}
@@ -0,0 +1,123 @@
+//! JSON Lines, first line is header describing the channels and their units with the event
+//! markers of the run, every next line is one record object keyed by the channel name.
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    format_error,
+    markers::{EventMarker, Markers},
+    Channel, Record, CHANNELS, TIME_CHANNEL,
+};
+use dyno_core::{serde::Deserialize as _, DynoResult};
+
+const FORMAT_NAME: &str = "dynotest-jsonl";
+const FORMAT_VERSION: u32 = 1;
+
+fn header(markers: &Markers) -> Value {
+    let channels = std::iter::once(&TIME_CHANNEL)
+        .chain(CHANNELS.iter())
+        .map(|Channel { name, unit, desc }| json!({ "name": name, "unit": unit, "desc": desc }))
+        .collect::<Vec<_>>();
+    json!({
+        "format": FORMAT_NAME,
+        "version": FORMAT_VERSION,
+        "channels": channels,
+        "markers": markers.iter().collect::<Vec<_>>(),
+    })
+}
+
+pub fn save(records: &[Record], markers: &Markers, path: &Path) -> DynoResult<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut write_line = |value: &Value| -> DynoResult<()> {
+        serde_json::to_writer(&mut writer, value)
+            .map_err(|err| format_error("JSON Lines", path, err))?;
+        writer.write_all(b"\n").map_err(From::from)
+    };
+
+    write_line(&header(markers))?;
+    for record in records {
+        let mut line = Map::with_capacity(CHANNELS.len() + 1);
+        line.insert(TIME_CHANNEL.name.to_owned(), record.time_stamp.into());
+        for (channel, value) in CHANNELS.iter().zip(record.values) {
+            line.insert(channel.name.to_owned(), value.into());
+        }
+        write_line(&Value::Object(line))?;
+    }
+    writer.flush().map_err(From::from)
+}
+
+pub fn open(path: &Path) -> DynoResult<(Vec<Record>, Markers)> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut records = vec![];
+    let mut markers = Markers::default();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Object(object)) => object,
+            Ok(_) => {
+                return Err(format_error(
+                    "JSON Lines",
+                    path,
+                    "line is not a JSON object",
+                ))
+            }
+            Err(err) => return Err(format_error("JSON Lines", path, err)),
+        };
+        // header line, only the markers to read
+        if object.contains_key("channels") {
+            if let Some(saved) = object.get("markers") {
+                markers = Vec::<EventMarker>::deserialize(saved)
+                    .map_err(|err| format_error("JSON Lines", path, err))?
+                    .into_iter()
+                    .collect();
+            }
+            continue;
+        }
+        let mut record = Record {
+            time_stamp: object
+                .get(TIME_CHANNEL.name)
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        for (key, value) in object {
+            if let (Some(idx), Some(value)) = (Channel::position(&key), value.as_f64()) {
+                record.values[idx] = value;
+            }
+        }
+        records.push(record);
+    }
+    Ok((records, markers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{sample_markers, sample_records, test_path};
+
+    #[test]
+    fn header_line_with_markers() {
+        let path = test_path("run.jsonl");
+        let records = sample_records();
+        let markers = sample_markers(&records);
+        save(&records, &markers, &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let opened = open(&path);
+        std::fs::remove_file(&path).ok();
+
+        let header = serde_json::from_str::<Value>(text.lines().next().unwrap()).unwrap();
+        assert_eq!(header["format"], FORMAT_NAME);
+        assert_eq!(header["channels"][0]["name"], TIME_CHANNEL.name);
+        assert_eq!(header["markers"][1]["text"], "gear shift, 2nd");
+        assert_eq!(text.lines().count(), records.len() + 1);
+        assert_eq!(opened.unwrap(), (records, markers));
+    }
+}
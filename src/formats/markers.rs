@@ -0,0 +1,165 @@
+//! Event markers of a run, like "gear shift" or "knock heard". Every saved file type holds the
+//! markers in the file (see [`super::save_to_path`]), except the `.dyno` binary: it is written by
+//! `dyno_core` without a place for them, so it is saved next to a `<file>.markers.toml` sidecar
+//! and a `.dyno` copied or downloaded without its sidecar has no markers. The markers are sent
+//! with the run to the server, and written in the export header (csv) or in the `Markers` sheet
+//! (excel).
+use std::path::{Path, PathBuf};
+
+use dyno_core::{serde, toml, DynoResult};
+
+use super::format_error;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct EventMarker {
+    /// in milliseconds unix timestamp, like the records of the run.
+    pub time_stamp: i64,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+pub struct Markers {
+    markers: Vec<EventMarker>,
+}
+
+impl Markers {
+    pub const EXTENSION: &'static str = "markers.toml";
+
+    /// `<file>.markers.toml` for the run file in `path`.
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(Self::EXTENSION);
+        path.with_file_name(name)
+    }
+
+    /// add the marker, the markers are kept ordered by time.
+    pub fn push(&mut self, time_stamp: i64, text: impl Into<String>) {
+        let idx = self.markers.partition_point(|m| m.time_stamp <= time_stamp);
+        self.markers.insert(
+            idx,
+            EventMarker {
+                time_stamp,
+                text: text.into(),
+            },
+        );
+    }
+
+    #[inline]
+    pub fn remove(&mut self, idx: usize) -> EventMarker {
+        self.markers.remove(idx)
+    }
+
+    /// order the markers by time again, after the time of a marker is edited.
+    #[inline]
+    pub fn sort(&mut self) {
+        self.markers.sort_by_key(|m| m.time_stamp);
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.markers.clear();
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &EventMarker> {
+        self.markers.iter()
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut EventMarker> {
+        self.markers.iter_mut()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.markers.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.markers.is_empty()
+    }
+
+    pub fn save(&self, path: &Path) -> DynoResult<()> {
+        let data = toml::to_string(self).map_err(|err| format_error("markers", path, err))?;
+        std::fs::write(path, data).map_err(|err| format_error("markers", path, err))
+    }
+
+    pub fn open(path: &Path) -> DynoResult<Self> {
+        let data =
+            std::fs::read_to_string(path).map_err(|err| format_error("markers", path, err))?;
+        toml::from_str(&data).map_err(|err| format_error("markers", path, err))
+    }
+
+    /// save the markers next to the run file in `path`, an old sidecar is removed if the run
+    /// has no markers.
+    pub fn save_sidecar(&self, path: &Path) -> DynoResult<()> {
+        let sidecar = Self::sidecar_path(path);
+        if !self.is_empty() {
+            return self.save(&sidecar);
+        }
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar).map_err(|err| format_error("markers", path, err))?;
+        }
+        Ok(())
+    }
+
+    /// markers saved next to the run file in `path`, empty if the run has no markers.
+    pub fn open_sidecar(path: &Path) -> Self {
+        let sidecar = Self::sidecar_path(path);
+        if !sidecar.exists() {
+            return Self::default();
+        }
+        Self::open(&sidecar).unwrap_or_else(|err| {
+            dyno_core::log::warn!("{err}");
+            Self::default()
+        })
+    }
+}
+
+impl FromIterator<EventMarker> for Markers {
+    fn from_iter<I: IntoIterator<Item = EventMarker>>(iter: I) -> Self {
+        let mut markers = Self {
+            markers: iter.into_iter().collect(),
+        };
+        markers.sort();
+        markers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordered_markers_sidecar() {
+        let dir = std::env::temp_dir().join(format!("dyno-markers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = dir.join("run.csv");
+
+        let mut markers = Markers::default();
+        markers.push(2_000, "throttle closed");
+        markers.push(1_000, "gear shift");
+        markers.push(1_500, "");
+        assert_eq!(
+            markers.iter().map(|m| m.time_stamp).collect::<Vec<_>>(),
+            [1_000, 1_500, 2_000]
+        );
+        assert_eq!(
+            Markers::sidecar_path(&run),
+            dir.join("run.csv.markers.toml")
+        );
+        markers.save_sidecar(&run).unwrap();
+        assert_eq!(Markers::open_sidecar(&run), markers);
+
+        // run without markers removes the old sidecar.
+        Markers::default().save_sidecar(&run).unwrap();
+        assert!(!Markers::sidecar_path(&run).exists());
+        assert!(Markers::open_sidecar(&run).is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
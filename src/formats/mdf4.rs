@@ -0,0 +1,569 @@
+//! ASAM MDF version 4.10, written as one sorted data group with one channel group,
+//! master channel `time_stamp` in seconds and every channel as 64-bit float. The event
+//! markers are written as marker events (`##EV`) with the text as the event name.
+//!
+//! the reader only support sorted, unzipped data (`##DT` block), with the
+//! identity and linear conversion, which is what most of tools write by default.
+use std::{
+    collections::HashSet,
+    fs,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use super::{format_error, markers::Markers, Channel, Record, CHANNELS, TIME_CHANNEL};
+use dyno_core::{DynoErr, DynoResult};
+
+const FORMAT_NAME: &str = "MDF4";
+const ID_BLOCK_SIZE: u64 = 64;
+const HEADER_SIZE: usize = 24;
+
+const CN_TYPE_MASTER: u8 = 2;
+const CN_SYNC_TIME: u8 = 1;
+const DT_UNSIGNED_LE: u8 = 0;
+const DT_SIGNED_LE: u8 = 2;
+const DT_FLOAT_LE: u8 = 4;
+const CC_IDENTITY: u8 = 0;
+const CC_LINEAR: u8 = 1;
+const EV_TYPE_MARKER: u8 = 6;
+const EV_SYNC_TIME: u8 = 1;
+const EV_CAUSE_USER: u8 = 4;
+
+#[inline(always)]
+const fn align8(len: u64) -> u64 {
+    (len + 7) & !7
+}
+
+struct Block {
+    id: [u8; 4],
+    links: Vec<Option<usize>>,
+    data: Vec<u8>,
+}
+
+impl Block {
+    fn new(id: &[u8; 4], links: usize, data: Vec<u8>) -> Self {
+        Self {
+            id: *id,
+            links: vec![None; links],
+            data,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> u64 {
+        (HEADER_SIZE + self.links.len() * 8 + self.data.len()) as u64
+    }
+}
+
+#[derive(Default)]
+struct MdfWriter {
+    blocks: Vec<Block>,
+}
+
+impl MdfWriter {
+    fn push(&mut self, block: Block) -> usize {
+        self.blocks.push(block);
+        self.blocks.len() - 1
+    }
+
+    fn link(&mut self, block: usize, link: usize, target: usize) {
+        self.blocks[block].links[link] = Some(target);
+    }
+
+    fn text(&mut self, id: &[u8; 4], text: &str) -> usize {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        data.resize(align8(data.len() as u64) as usize, 0);
+        self.push(Block::new(id, 0, data))
+    }
+
+    fn finish(self, writer: impl Write) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+
+        let mut offsets = Vec::with_capacity(self.blocks.len());
+        let mut offset = ID_BLOCK_SIZE;
+        for block in &self.blocks {
+            offsets.push(offset);
+            offset += align8(block.len());
+        }
+
+        // ID block
+        writer.write_all(b"MDF     ")?;
+        writer.write_all(b"4.10    ")?;
+        writer.write_all(b"DynoTest")?;
+        writer.write_all(&[0u8; 4])?;
+        writer.write_all(&410u16.to_le_bytes())?;
+        writer.write_all(&[0u8; 30])?;
+        writer.write_all(&[0u8; 4])?;
+
+        for block in &self.blocks {
+            writer.write_all(&block.id)?;
+            writer.write_all(&[0u8; 4])?;
+            writer.write_all(&block.len().to_le_bytes())?;
+            writer.write_all(&(block.links.len() as u64).to_le_bytes())?;
+            for link in &block.links {
+                let link = link.map(|idx| offsets[idx]).unwrap_or_default();
+                writer.write_all(&link.to_le_bytes())?;
+            }
+            writer.write_all(&block.data)?;
+            let padding = align8(block.len()) - block.len();
+            writer.write_all(&[0u8; 8][..padding as usize])?;
+        }
+        writer.flush()
+    }
+}
+
+fn channel_block(cn_type: u8, sync_type: u8, byte_offset: u32) -> Block {
+    let mut data = Vec::with_capacity(72);
+    data.extend([cn_type, sync_type, DT_FLOAT_LE, 0]);
+    data.extend(byte_offset.to_le_bytes());
+    data.extend(64u32.to_le_bytes()); // bit count
+    data.extend(0u32.to_le_bytes()); // flags
+    data.extend(0u32.to_le_bytes()); // invalidation bit position
+    data.extend([0u8, 0]); // precision, reserved
+    data.extend(0u16.to_le_bytes()); // attachment count
+    data.extend([0u8; 6 * 8]); // value range and limits
+
+    // links: cn_next, composition, tx_name, si_source, cc_conversion, data, md_unit, md_comment
+    Block::new(b"##CN", 8, data)
+}
+
+/// marker event `offset_ms` milliseconds from the start of the recording.
+fn event_block(offset_ms: i64) -> Block {
+    let mut data = Vec::with_capacity(32);
+    // type, sync type, range type (point), cause, flags
+    data.extend([EV_TYPE_MARKER, EV_SYNC_TIME, 0, EV_CAUSE_USER, 0]);
+    data.extend([0u8; 3]); // reserved
+    data.extend(0u32.to_le_bytes()); // scope count
+    data.extend(0u16.to_le_bytes()); // attachment count
+    data.extend(0u16.to_le_bytes()); // creator index
+    data.extend(offset_ms.to_le_bytes()); // sync base value
+    data.extend(1e-3f64.to_le_bytes()); // sync factor
+
+    // links: ev_next, ev_parent, ev_range, tx_name, md_comment
+    Block::new(b"##EV", 5, data)
+}
+
+pub fn save(records: &[Record], markers: &Markers, path: &Path) -> DynoResult<()> {
+    let start_ms = records.first().map(|r| r.time_stamp).unwrap_or_default();
+    let start_ns = (start_ms.max(0) as u64).saturating_mul(1_000_000);
+    let mut mdf = MdfWriter::default();
+
+    // HD: links dg_first, fh_first, ch_first, at_first, ev_first, md_comment
+    let mut hd_data = Vec::with_capacity(32);
+    hd_data.extend(start_ns.to_le_bytes());
+    hd_data.extend([0u8; 8]); // tz offset, dst offset, time flags, time class, flags, reserved
+    hd_data.extend([0u8; 16]); // start angle, start distance
+    let hd = mdf.push(Block::new(b"##HD", 6, hd_data));
+
+    // FH: links fh_next, md_comment
+    let mut fh_data = Vec::with_capacity(16);
+    fh_data.extend(start_ns.to_le_bytes());
+    fh_data.extend([0u8; 8]);
+    let fh = mdf.push(Block::new(b"##FH", 2, fh_data));
+    let fh_comment = mdf.text(
+        b"##MD",
+        &format!(
+            r#"<FHcomment xmlns="http://www.asam.net/mdf/v4"><TX>created by {app}</TX><tool_id>{name}</tool_id><tool_vendor>Politeknik Negeri Jember</tool_vendor><tool_version>{version}</tool_version></FHcomment>"#,
+            app = crate::PACKAGE_INFO.app_name,
+            name = crate::PACKAGE_INFO.name,
+            version = crate::PACKAGE_INFO.version,
+        ),
+    );
+    mdf.link(fh, 1, fh_comment);
+    mdf.link(hd, 1, fh);
+
+    // DG: links dg_next, cg_first, data, md_comment
+    let dg = mdf.push(Block::new(b"##DG", 4, vec![0u8; 8]));
+    mdf.link(hd, 0, dg);
+
+    // CG: links cg_next, cn_first, acq_name, acq_source, sr_first, md_comment
+    let record_size = ((CHANNELS.len() + 1) * 8) as u32;
+    let mut cg_data = Vec::with_capacity(32);
+    cg_data.extend(0u64.to_le_bytes()); // record id
+    cg_data.extend((records.len() as u64).to_le_bytes()); // cycle count
+    cg_data.extend([0u8; 8]); // flags, path separator, reserved
+    cg_data.extend(record_size.to_le_bytes());
+    cg_data.extend(0u32.to_le_bytes()); // invalidation bytes
+    let cg = mdf.push(Block::new(b"##CG", 6, cg_data));
+    mdf.link(dg, 1, cg);
+
+    let channels = std::iter::once(&TIME_CHANNEL).chain(CHANNELS.iter());
+    let mut prev_cn: Option<usize> = None;
+    for (idx, Channel { name, unit, desc }) in channels.enumerate() {
+        let cn = match idx {
+            0 => mdf.push(channel_block(CN_TYPE_MASTER, CN_SYNC_TIME, 0)),
+            idx => mdf.push(channel_block(0, 0, (idx * 8) as u32)),
+        };
+        let (name, unit) = match idx {
+            // master channel stored as seconds from the start of the recording
+            0 => ("time", "s"),
+            _ => (*name, *unit),
+        };
+        let tx_name = mdf.text(b"##TX", name);
+        let tx_unit = mdf.text(b"##TX", unit);
+        let tx_desc = mdf.text(b"##TX", desc);
+        mdf.link(cn, 2, tx_name);
+        mdf.link(cn, 6, tx_unit);
+        mdf.link(cn, 7, tx_desc);
+        match prev_cn {
+            Some(prev) => mdf.link(prev, 0, cn),
+            None => mdf.link(cg, 1, cn),
+        }
+        prev_cn = Some(cn);
+    }
+
+    let mut dt_data = Vec::with_capacity(records.len() * record_size as usize);
+    for record in records {
+        let time = (record.time_stamp - start_ms) as f64 * 1e-3;
+        dt_data.extend(time.to_le_bytes());
+        for value in record.values {
+            dt_data.extend(value.to_le_bytes());
+        }
+    }
+    let dt = mdf.push(Block::new(b"##DT", 0, dt_data));
+    mdf.link(dg, 2, dt);
+
+    let mut prev_ev: Option<usize> = None;
+    for marker in markers.iter() {
+        let ev = mdf.push(event_block(marker.time_stamp - start_ms));
+        let tx_name = mdf.text(b"##TX", &marker.text);
+        mdf.link(ev, 3, tx_name);
+        match prev_ev {
+            Some(prev) => mdf.link(prev, 0, ev),
+            None => mdf.link(hd, 4, ev),
+        }
+        prev_ev = Some(ev);
+    }
+
+    let file = fs::File::create(path)?;
+    mdf.finish(file).map_err(From::from)
+}
+
+struct BlockRef<'a> {
+    links: Vec<u64>,
+    data: &'a [u8],
+}
+
+struct MdfReader<'a> {
+    buf: &'a [u8],
+    path: &'a Path,
+}
+
+impl<'a> MdfReader<'a> {
+    #[inline]
+    fn err(&self, msg: impl std::fmt::Display) -> DynoErr {
+        format_error(FORMAT_NAME, self.path, msg)
+    }
+
+    fn slice(&self, offset: u64, len: u64) -> DynoResult<&'a [u8]> {
+        let start = offset as usize;
+        let end = start.saturating_add(len as usize);
+        self.buf
+            .get(start..end)
+            .ok_or_else(|| self.err(format!("block at {offset:#x} is out of file range")))
+    }
+
+    fn block(&self, offset: u64, id: &[u8; 4]) -> DynoResult<BlockRef<'a>> {
+        let header = self.slice(offset, HEADER_SIZE as u64)?;
+        if &header[..4] != id {
+            return Err(self.err(format!(
+                "expected block `{}` at {offset:#x}, found `{}`",
+                String::from_utf8_lossy(id),
+                String::from_utf8_lossy(&header[..4])
+            )));
+        }
+        let len = le_u64(header, 8);
+        let link_count = le_u64(header, 16);
+        let block = self.slice(offset, len)?;
+        let links_end = usize::try_from(link_count)
+            .ok()
+            .and_then(|count| count.checked_mul(8))
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .filter(|end| *end <= block.len());
+        let Some(links_end) = links_end else {
+            return Err(self.err(format!("block at {offset:#x} has invalid link count")));
+        };
+        let links = (0..link_count as usize)
+            .map(|i| le_u64(block, HEADER_SIZE + i * 8))
+            .collect::<Vec<_>>();
+        let data = &block[links_end..];
+
+        // minimum (links, data bytes) needed by the reader for every block
+        let (min_links, min_data) = match id {
+            b"##HD" => (1, 8),
+            b"##DG" => (3, 1),
+            b"##CG" => (2, 32),
+            b"##CN" => (5, 12),
+            b"##CC" => (0, 24),
+            b"##EV" => (4, 32),
+            _ => (0, 0),
+        };
+        if links.len() < min_links || data.len() < min_data {
+            return Err(self.err(format!(
+                "block `{}` at {offset:#x} is too short",
+                String::from_utf8_lossy(id)
+            )));
+        }
+        Ok(BlockRef { links, data })
+    }
+
+    fn text(&self, offset: u64) -> DynoResult<String> {
+        if offset == 0 {
+            return Ok(String::new());
+        }
+        let header = self.slice(offset, 4)?;
+        let block = match header {
+            b"##TX" => self.block(offset, b"##TX")?,
+            _ => self.block(offset, b"##MD")?,
+        };
+        let end = block
+            .data
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(block.data.len());
+        Ok(String::from_utf8_lossy(&block.data[..end]).into_owned())
+    }
+
+    /// return the linear conversion `(offset, factor)` of the channel
+    fn conversion(&self, offset: u64) -> DynoResult<(f64, f64)> {
+        if offset == 0 {
+            return Ok((0.0, 1.0));
+        }
+        let block = self.block(offset, b"##CC")?;
+        let val_count = le_u16(block.data, 6) as usize;
+        match block.data[0] {
+            CC_IDENTITY => Ok((0.0, 1.0)),
+            CC_LINEAR if val_count >= 2 && block.data.len() >= 24 + 16 => {
+                Ok((le_f64(block.data, 24), le_f64(block.data, 32)))
+            }
+            cc_type => Err(self.err(format!("conversion type {cc_type} is not supported"))),
+        }
+    }
+}
+
+struct ChannelReader {
+    target: Option<usize>,
+    master: bool,
+    data_type: u8,
+    byte_offset: usize,
+    bit_count: u32,
+    conversion: (f64, f64),
+}
+
+impl ChannelReader {
+    fn read(&self, record: &[u8]) -> Option<f64> {
+        let off = self.byte_offset;
+        let raw = match (self.data_type, self.bit_count) {
+            (DT_FLOAT_LE, 64) => le_f64(record.get(off..off + 8)?, 0),
+            (DT_FLOAT_LE, 32) => {
+                f32::from_le_bytes(record.get(off..off + 4)?.try_into().ok()?) as f64
+            }
+            (DT_UNSIGNED_LE, 8) => *record.get(off)? as f64,
+            (DT_UNSIGNED_LE, 16) => le_u16(record.get(off..off + 2)?, 0) as f64,
+            (DT_UNSIGNED_LE, 32) => {
+                u32::from_le_bytes(record.get(off..off + 4)?.try_into().ok()?) as f64
+            }
+            (DT_UNSIGNED_LE, 64) => le_u64(record.get(off..off + 8)?, 0) as f64,
+            (DT_SIGNED_LE, 8) => *record.get(off)? as i8 as f64,
+            (DT_SIGNED_LE, 16) => {
+                i16::from_le_bytes(record.get(off..off + 2)?.try_into().ok()?) as f64
+            }
+            (DT_SIGNED_LE, 32) => {
+                i32::from_le_bytes(record.get(off..off + 4)?.try_into().ok()?) as f64
+            }
+            (DT_SIGNED_LE, 64) => {
+                i64::from_le_bytes(record.get(off..off + 8)?.try_into().ok()?) as f64
+            }
+            _ => return None,
+        };
+        let (offset, factor) = self.conversion;
+        Some(offset + factor * raw)
+    }
+}
+
+pub fn open(path: &Path) -> DynoResult<(Vec<Record>, Markers)> {
+    let buf = fs::read(path)?;
+    let reader = MdfReader { buf: &buf, path };
+
+    let id = reader.slice(0, ID_BLOCK_SIZE)?;
+    if &id[..3] != b"MDF" || le_u16(id, 28) < 400 {
+        return Err(reader.err("file is not MDF version 4"));
+    }
+
+    let hd = reader.block(ID_BLOCK_SIZE, b"##HD")?;
+    let start_ms = (le_u64(hd.data, 0) / 1_000_000) as i64;
+
+    let dg = reader.block(hd.links[0], b"##DG")?;
+    let rec_id_size = *dg.data.first().unwrap_or(&0) as usize;
+    let cg = reader.block(dg.links[1], b"##CG")?;
+    if cg.links[0] != 0 {
+        dyno_core::log::warn!("MDF4 file is unsorted, only the first channel group is read");
+    }
+    let cycle_count = le_u64(cg.data, 8) as usize;
+    let data_bytes = le_u32(cg.data, 24) as usize;
+    let inval_bytes = le_u32(cg.data, 28) as usize;
+
+    let mut channels = vec![];
+    let mut visited = HashSet::new();
+    let mut cn_offset = cg.links[1];
+    while cn_offset != 0 {
+        if !visited.insert(cn_offset) {
+            return Err(reader.err(format!(
+                "channel at {cn_offset:#x} links back into the list"
+            )));
+        }
+        let cn = reader.block(cn_offset, b"##CN")?;
+        let name = reader.text(cn.links[2])?;
+        let master = cn.data[0] == CN_TYPE_MASTER || cn.data[0] == 3;
+        channels.push(ChannelReader {
+            target: Channel::position(&name),
+            master,
+            data_type: cn.data[2],
+            byte_offset: le_u32(cn.data, 4) as usize,
+            bit_count: le_u32(cn.data, 8),
+            conversion: reader.conversion(cn.links[4])?,
+        });
+        cn_offset = cn.links[0];
+    }
+    if !channels.iter().any(|ch| ch.target.is_some()) {
+        return Err(reader.err(format!(
+            "no known channel found, expected one of [{}]",
+            CHANNELS.map(|ch| ch.name).join(", ")
+        )));
+    }
+
+    let data = match reader.slice(dg.links[2], 4)? {
+        b"##DT" => reader.block(dg.links[2], b"##DT")?.data,
+        other => {
+            return Err(reader.err(format!(
+                "data block `{}` is not supported, only `##DT`",
+                String::from_utf8_lossy(other)
+            )))
+        }
+    };
+
+    let record_size = rec_id_size + data_bytes + inval_bytes;
+    if record_size == 0 {
+        return Err(reader.err("channel group has empty record size"));
+    }
+    let records = data
+        .chunks_exact(record_size)
+        .take(cycle_count)
+        .map(|raw| {
+            let raw = &raw[rec_id_size..];
+            let mut record = Record {
+                time_stamp: start_ms,
+                ..Default::default()
+            };
+            for channel in &channels {
+                let Some(value) = channel.read(raw) else {
+                    continue;
+                };
+                match (channel.master, channel.target) {
+                    (true, _) => record.time_stamp = start_ms + (value * 1e3).round() as i64,
+                    (false, Some(idx)) => record.values[idx] = value,
+                    (false, None) => {}
+                }
+            }
+            record
+        })
+        .collect();
+
+    let mut markers = Markers::default();
+    let mut ev_offset = hd.links.get(4).copied().unwrap_or_default();
+    while ev_offset != 0 {
+        if !visited.insert(ev_offset) {
+            return Err(reader.err(format!("event at {ev_offset:#x} links back into the list")));
+        }
+        let ev = reader.block(ev_offset, b"##EV")?;
+        if ev.data[1] == EV_SYNC_TIME {
+            let secs = le_i64(ev.data, 16) as f64 * le_f64(ev.data, 24);
+            let text = reader.text(ev.links[3])?;
+            markers.push(start_ms + (secs * 1e3).round() as i64, text);
+        }
+        ev_offset = ev.links[0];
+    }
+    Ok((records, markers))
+}
+
+#[inline(always)]
+fn le_u16(buf: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([buf[off], buf[off + 1]])
+}
+
+#[inline(always)]
+fn le_u32(buf: &[u8], off: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&buf[off..off + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+#[inline(always)]
+fn le_u64(buf: &[u8], off: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&buf[off..off + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[inline(always)]
+fn le_i64(buf: &[u8], off: usize) -> i64 {
+    le_u64(buf, off) as i64
+}
+
+#[inline(always)]
+fn le_f64(buf: &[u8], off: usize) -> f64 {
+    f64::from_bits(le_u64(buf, off))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{sample_markers, sample_records, test_path};
+
+    fn block_offsets(bytes: &[u8], id: &[u8; 4]) -> Vec<usize> {
+        (0..bytes.len())
+            .step_by(8)
+            .filter(|&off| bytes[off..].starts_with(id))
+            .collect()
+    }
+
+    #[test]
+    fn markers_as_events() {
+        let path = test_path("run.mf4");
+        let records = sample_records();
+        let markers = sample_markers(&records);
+        save(&records, &markers, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        let opened = open(&path);
+        std::fs::remove_file(&path).ok();
+
+        let events = block_offsets(&bytes, b"##EV");
+        assert_eq!(events.len(), markers.len());
+        let first = &bytes[events[0] + HEADER_SIZE + 5 * 8..];
+        assert_eq!(first[0], EV_TYPE_MARKER);
+        assert_eq!(le_i64(first, 16), -10);
+        assert_eq!(opened.unwrap(), (records, markers));
+    }
+
+    #[test]
+    fn cyclic_links() {
+        let path = test_path("cyclic.mf4");
+        let records = sample_records();
+        save(&records, &sample_markers(&records), &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+
+        // point the next link of the first channel, then of the first event, back to itself.
+        let mut results = vec![];
+        for (id, next_link) in [(b"##CN", 24), (b"##EV", 24)] {
+            let block = block_offsets(&bytes, id)[0];
+            let mut cyclic = bytes.clone();
+            cyclic[block + next_link..block + next_link + 8]
+                .copy_from_slice(&(block as u64).to_le_bytes());
+            fs::write(&path, &cyclic).unwrap();
+            results.push(open(&path));
+        }
+        std::fs::remove_file(&path).ok();
+        assert!(results.iter().all(Result::is_err));
+    }
+}
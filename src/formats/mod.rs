@@ -0,0 +1,288 @@
+//! Exchange formats for dynotest records, used by `DynoControl::on_save` and
+//! `DynoControl::on_open` next to the native binaries, csv and excel format.
+mod csv_file;
+mod excel_file;
+pub mod export;
+pub mod import;
+mod jsonl;
+pub mod markers;
+mod mdf4;
+mod parquet_file;
+pub mod raw;
+pub mod summary;
+
+use std::path::Path;
+
+use crate::state::DynoFileType;
+use dyno_core::{
+    chrono::{DateTime, NaiveDateTime},
+    convertions::prelude::*,
+    BufferData, CompresedSaver as _, Data, DynoErr, DynoResult,
+};
+use markers::Markers;
+
+/// description of a single channel (column) from [`BufferData`] written to the exchange formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Channel {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub desc: &'static str,
+}
+
+/// master channel of every record, in milliseconds unix timestamp.
+pub const TIME_CHANNEL: Channel = Channel {
+    name: "time_stamp",
+    unit: "ms",
+    desc: "Time Stamp",
+};
+
+pub const CHANNEL_SIZE: usize = 7;
+pub const CHANNELS: [Channel; CHANNEL_SIZE] = [
+    Channel {
+        name: "speed",
+        unit: "km/h",
+        desc: "Speed",
+    },
+    Channel {
+        name: "rpm_roda",
+        unit: "rpm",
+        desc: "Rpm Tire (Roda)",
+    },
+    Channel {
+        name: "rpm_engine",
+        unit: "rpm",
+        desc: "Rpm Engine (Mesin)",
+    },
+    Channel {
+        name: "torque",
+        unit: "Nm",
+        desc: "Torque",
+    },
+    Channel {
+        name: "horsepower",
+        unit: "HP",
+        desc: "HorsePower",
+    },
+    Channel {
+        name: "odo",
+        unit: "km",
+        desc: "ODO (Jarak Tempuh)",
+    },
+    Channel {
+        name: "temp",
+        unit: "°C",
+        desc: "Engine Temperature",
+    },
+];
+
+impl Channel {
+    /// find the index of channel in [`CHANNELS`] by the channel name, case insensitive.
+    pub fn position(name: &str) -> Option<usize> {
+        CHANNELS
+            .iter()
+            .position(|ch| ch.name.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// one row of [`BufferData`], ordered like [`CHANNELS`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Record {
+    pub time_stamp: i64,
+    pub values: [f64; CHANNEL_SIZE],
+}
+
+impl Record {
+    pub fn from_data(data: &Data) -> Self {
+        Self {
+            time_stamp: data.time_stamp.and_utc().timestamp_millis(),
+            values: [
+                data.speed.value(),
+                data.rpm_roda.value(),
+                data.rpm_engine.value(),
+                data.torque.value(),
+                data.horsepower.value(),
+                data.odo.value(),
+                data.temp.value(),
+            ],
+        }
+    }
+
+    pub fn into_data(self) -> Data {
+        let [speed, rpm_roda, rpm_engine, torque, horsepower, odo, temp] = self.values;
+        Data {
+            speed: KilometresPerHour::new(speed),
+            rpm_roda: RotationPerMinute::new(rpm_roda),
+            rpm_engine: RotationPerMinute::new(rpm_engine),
+            torque: NewtonMeter::new(torque),
+            horsepower: HorsePower::new(horsepower),
+            odo: KiloMetres::new(odo),
+            temp: Celcius::new(temp),
+            time_stamp: datetime_from_millis(self.time_stamp),
+            ..Default::default()
+        }
+    }
+}
+
+/// date time of the milliseconds unix time stamp `ms`, the unix epoch if out of range.
+pub fn datetime_from_millis(ms: i64) -> NaiveDateTime {
+    DateTime::from_timestamp_millis(ms)
+        .map(|time| time.naive_utc())
+        .unwrap_or_default()
+}
+
+/// collect every recorded row in `buffer` into [`Record`]s.
+pub fn records(buffer: &BufferData) -> Vec<Record> {
+    itertools::izip!(
+        buffer.time_stamp.iter(),
+        buffer.speed.iter(),
+        buffer.rpm_roda.iter(),
+        buffer.rpm_engine.iter(),
+        buffer.torque.iter(),
+        buffer.horsepower.iter(),
+        buffer.odo.iter(),
+        buffer.temp.iter(),
+    )
+    .map(
+        |(time_stamp, speed, rpm_roda, rpm_engine, torque, horsepower, odo, temp)| Record {
+            time_stamp: *time_stamp,
+            values: [
+                speed.value(),
+                rpm_roda.value(),
+                rpm_engine.value(),
+                torque.value(),
+                horsepower.value(),
+                odo.value(),
+                temp.value(),
+            ],
+        },
+    )
+    .collect()
+}
+
+/// build new [`BufferData`] from [`Record`]s, in the same way the buffer processing live data.
+pub fn buffer_from_records(records: impl IntoIterator<Item = Record>) -> BufferData {
+    let mut buffer = BufferData::new();
+    for record in records {
+        buffer.data = record.into_data();
+        buffer.process_data();
+    }
+    buffer
+}
+
+/// [`buffer_from_records`] with the `records` changed from `buffer`, the last sample keeps
+/// the values of `buffer` that are not in [`CHANNELS`].
+pub fn rebuild_buffer(buffer: &BufferData, records: Vec<Record>) -> BufferData {
+    let last = records.last().copied();
+    let mut rebuilt = buffer_from_records(records);
+    if let Some(last) = last {
+        let Data {
+            speed,
+            rpm_roda,
+            rpm_engine,
+            torque,
+            horsepower,
+            odo,
+            temp,
+            time_stamp,
+            ..
+        } = last.into_data();
+        rebuilt.data = Data {
+            speed,
+            rpm_roda,
+            rpm_engine,
+            torque,
+            horsepower,
+            odo,
+            temp,
+            time_stamp,
+            ..buffer.last().clone()
+        };
+    }
+    rebuilt
+}
+
+/// save the buffer with the event markers, every file type holds the markers in the file
+/// except the `.dyno` binary, saved with the markers sidecar (see [`markers`]).
+pub fn save_to_path(
+    tp: DynoFileType,
+    buffer: &BufferData,
+    markers: &Markers,
+    path: &Path,
+) -> DynoResult<()> {
+    match tp {
+        DynoFileType::Dyno => {
+            buffer.compress_to_path(path)?;
+            markers.save_sidecar(path)
+        }
+        DynoFileType::Csv => {
+            buffer.save_csv_from_path(path)?;
+            csv_file::append_markers(markers, path)
+        }
+        DynoFileType::Excel => {
+            buffer.save_excel_from_path(path)?;
+            excel_file::add_markers(markers, path)
+        }
+        DynoFileType::Json => jsonl::save(&records(buffer), markers, path),
+        DynoFileType::Parquet => parquet_file::save(&records(buffer), markers, path),
+        DynoFileType::Mdf => mdf4::save(&records(buffer), markers, path),
+    }
+}
+
+#[inline]
+pub fn open_from_path(tp: DynoFileType, path: &Path) -> DynoResult<BufferData> {
+    open_with_markers(tp, path).map(|(buffer, _)| buffer)
+}
+
+/// open the buffer with the event markers saved by [`save_to_path`].
+pub fn open_with_markers(tp: DynoFileType, path: &Path) -> DynoResult<(BufferData, Markers)> {
+    let (records, markers) = match tp {
+        DynoFileType::Dyno => {
+            let buffer = BufferData::decompress_from_path(path)?;
+            return Ok((buffer, Markers::open_sidecar(path)));
+        }
+        DynoFileType::Csv => return csv_file::open(path),
+        DynoFileType::Excel => return excel_file::open(path),
+        DynoFileType::Json => jsonl::open(path)?,
+        DynoFileType::Parquet => parquet_file::open(path)?,
+        DynoFileType::Mdf => mdf4::open(path)?,
+    };
+    Ok((buffer_from_records(records), markers))
+}
+
+#[inline]
+fn format_error(format: &str, path: &Path, err: impl std::fmt::Display) -> DynoErr {
+    DynoErr::input_output_error(format!(
+        "Failed to process {format} file `{}` - {err}",
+        path.display()
+    ))
+}
+
+/// records of a run with a distinct value in every channel, for the format tests.
+#[cfg(test)]
+pub(crate) fn sample_records() -> Vec<Record> {
+    (0..50)
+        .map(|i| Record {
+            time_stamp: 1_686_000_000_000 + i * 250,
+            values: std::array::from_fn(|ch| i as f64 * 1.5 + ch as f64),
+        })
+        .collect()
+}
+
+/// markers of [`sample_records`], one before, one in and one after the records.
+#[cfg(test)]
+pub(crate) fn sample_markers(records: &[Record]) -> Markers {
+    let mut markers = Markers::default();
+    markers.push(records[0].time_stamp - 10, "start");
+    markers.push(records[10].time_stamp, "gear shift, 2nd");
+    markers.push(
+        records[records.len() - 1].time_stamp + 500,
+        "<throttle> \"closed\" & off",
+    );
+    markers
+}
+
+/// unique path in the temp dir for the test file `name`.
+#[cfg(test)]
+pub(crate) fn test_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("dynotest-{}-{name}", std::process::id()))
+}
@@ -0,0 +1,177 @@
+//! Apache Parquet, single row group with `time_stamp` as INT64 millis timestamp
+//! and every channel as DOUBLE column, the units and the event markers (json in the
+//! `markers` key) are stored in the key value metadata.
+use std::{fs::File, path::Path, sync::Arc};
+
+use parquet::{
+    data_type::{DoubleType, Int64Type},
+    file::{
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+        writer::SerializedFileWriter,
+    },
+    format::KeyValue,
+    record::Field,
+    schema::parser::parse_message_type,
+};
+
+use super::{
+    format_error,
+    markers::{EventMarker, Markers},
+    Channel, Record, CHANNELS, TIME_CHANNEL,
+};
+use dyno_core::DynoResult;
+
+const FORMAT_NAME: &str = "Parquet";
+const MARKERS_KEY: &str = "markers";
+
+fn message_type() -> String {
+    let columns = CHANNELS
+        .iter()
+        .map(|ch| format!("REQUIRED DOUBLE {};", ch.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "message dynotest {{\nREQUIRED INT64 {} (TIMESTAMP(MILLIS,true));\n{columns}\n}}",
+        TIME_CHANNEL.name
+    )
+}
+
+fn units_metadata() -> Vec<KeyValue> {
+    std::iter::once(&TIME_CHANNEL)
+        .chain(CHANNELS.iter())
+        .map(|ch| KeyValue::new(format!("unit.{}", ch.name), ch.unit.to_owned()))
+        .collect()
+}
+
+pub fn save(records: &[Record], markers: &Markers, path: &Path) -> DynoResult<()> {
+    let err_fn = |err| format_error(FORMAT_NAME, path, err);
+
+    let mut metadata = units_metadata();
+    if !markers.is_empty() {
+        let markers = serde_json::to_string(&markers.iter().collect::<Vec<_>>())
+            .map_err(|err| format_error(FORMAT_NAME, path, err))?;
+        metadata.push(KeyValue::new(MARKERS_KEY.to_owned(), markers));
+    }
+
+    let schema = Arc::new(parse_message_type(&message_type()).map_err(err_fn)?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_created_by(format!(
+                "{} {}",
+                crate::PACKAGE_INFO.name,
+                crate::PACKAGE_INFO.version
+            ))
+            .set_key_value_metadata(Some(metadata))
+            .build(),
+    );
+    let mut writer =
+        SerializedFileWriter::new(File::create(path)?, schema, props).map_err(err_fn)?;
+
+    let mut row_group = writer.next_row_group().map_err(err_fn)?;
+    let mut column_idx = 0usize;
+    while let Some(mut column) = row_group.next_column().map_err(err_fn)? {
+        match column_idx {
+            0 => {
+                let values = records.iter().map(|r| r.time_stamp).collect::<Vec<_>>();
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&values, None, None)
+                    .map_err(err_fn)?;
+            }
+            idx => {
+                let values = records
+                    .iter()
+                    .map(|r| r.values[idx - 1])
+                    .collect::<Vec<_>>();
+                column
+                    .typed::<DoubleType>()
+                    .write_batch(&values, None, None)
+                    .map_err(err_fn)?;
+            }
+        }
+        column.close().map_err(err_fn)?;
+        column_idx += 1;
+    }
+    row_group.close().map_err(err_fn)?;
+    writer.close().map(|_| ()).map_err(err_fn)
+}
+
+pub fn open(path: &Path) -> DynoResult<(Vec<Record>, Markers)> {
+    let err_fn = |err| format_error(FORMAT_NAME, path, err);
+
+    let reader = SerializedFileReader::new(File::open(path)?).map_err(err_fn)?;
+    let metadata = reader.metadata().file_metadata().key_value_metadata();
+    let markers = match metadata
+        .into_iter()
+        .flatten()
+        .find(|kv| kv.key == MARKERS_KEY)
+    {
+        Some(KeyValue {
+            value: Some(value), ..
+        }) => serde_json::from_str::<Vec<EventMarker>>(value)
+            .map_err(|err| format_error(FORMAT_NAME, path, err))?
+            .into_iter()
+            .collect(),
+        _ => Markers::default(),
+    };
+
+    let rows = reader.get_row_iter(None).map_err(err_fn)?;
+    let records = rows
+        .map(|row| {
+            let row = row.map_err(err_fn)?;
+            let mut record = Record::default();
+            for (name, field) in row.get_column_iter() {
+                let value = match field {
+                    Field::TimestampMillis(v) => *v as f64,
+                    Field::Long(v) => *v as f64,
+                    Field::TimestampMicros(v) => *v as f64 * 1e-3,
+                    Field::Int(v) => *v as f64,
+                    Field::Float(v) => *v as f64,
+                    Field::Double(v) => *v,
+                    _ => continue,
+                };
+                if name == TIME_CHANNEL.name {
+                    record.time_stamp = value as i64;
+                } else if let Some(idx) = Channel::position(name) {
+                    record.values[idx] = value;
+                }
+            }
+            Ok(record)
+        })
+        .collect::<DynoResult<_>>()?;
+    Ok((records, markers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{sample_markers, sample_records, test_path};
+
+    fn metadata_value(path: &Path, key: &str) -> Option<String> {
+        let reader = SerializedFileReader::new(File::open(path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata().key_value_metadata()?;
+        metadata.iter().find(|kv| kv.key == key)?.value.clone()
+    }
+
+    #[test]
+    fn units_and_markers_in_metadata() {
+        let path = test_path("run.parquet");
+        let records = sample_records();
+        let markers = sample_markers(&records);
+        save(&records, &markers, &path).unwrap();
+        let torque_unit = metadata_value(&path, "unit.torque");
+        let saved_markers = metadata_value(&path, MARKERS_KEY);
+        let opened = open(&path);
+
+        // a run without markers has no markers key.
+        save(&records, &Markers::default(), &path).unwrap();
+        let no_markers = metadata_value(&path, MARKERS_KEY);
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(torque_unit.as_deref(), Some(CHANNELS[3].unit));
+        assert!(saved_markers.unwrap().contains("gear shift, 2nd"));
+        assert_eq!(no_markers, None);
+        assert_eq!(opened.unwrap(), (records, markers));
+    }
+}
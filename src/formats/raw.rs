@@ -0,0 +1,227 @@
+//! Raw serial frames of a run, saved next to the run file as `<file>.raw`, so the run can be
+//! processed again with a different `DynoConfig` and saved as a revision `<name>.rev<N>.<ext>`.
+//! Every frame is written as received from the serial port, with the time stamp of the sample
+//! the frame is processed to, so a replay keeps the time axis of the run.
+//! The `DynoConfig` of a saved run is kept in `<file>.config.toml`, to upload the run later with the
+//! config it is recorded with.
+use std::path::{Path, PathBuf};
+
+use dyno_core::{
+    chrono::NaiveDateTime, serde, BufferData, Data, DynoConfig, DynoResult, SerialData,
+};
+
+use super::format_error;
+
+/// milliseconds unix time stamp of the sample, and the frame.
+type Frame = (i64, Vec<u8>);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawFrames {
+    frames: Vec<Frame>,
+}
+
+impl RawFrames {
+    pub const EXTENSION: &'static str = "raw";
+    /// start of every raw file.
+    const MAGIC: &'static [u8] = b"DYNORAW1";
+
+    /// `<file>.raw` for the run file in `path`.
+    pub fn sidecar_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(Self::EXTENSION);
+        path.with_file_name(name)
+    }
+
+    #[inline]
+    pub fn push(&mut self, time_stamp: i64, frame: Vec<u8>) {
+        self.frames.push((time_stamp, frame));
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// process the frames again with `config`, in the same way as the live data,
+    /// every sample keeps the time stamp of its frame.
+    pub fn replay(&self, config: &DynoConfig) -> BufferData {
+        let mut config = config.clone();
+        let mut live = BufferData::new();
+        let mut buffer = BufferData::new();
+        for (time, frame) in &self.frames {
+            let Some(data) = SerialData::from_bytes(frame) else {
+                continue;
+            };
+            live.push_from_serial(&mut config, data);
+            buffer.data = Data {
+                time_stamp: super::datetime_from_millis(*time),
+                ..live.last().clone()
+            };
+            buffer.process_data();
+        }
+        buffer
+    }
+
+    pub fn save(&self, path: &Path) -> DynoResult<()> {
+        let size = self.frames.iter().map(|(_, f)| f.len() + 12).sum::<usize>();
+        let mut data = Vec::with_capacity(Self::MAGIC.len() + size);
+        data.extend_from_slice(Self::MAGIC);
+        for (time, frame) in &self.frames {
+            data.extend_from_slice(&time.to_le_bytes());
+            data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            data.extend_from_slice(frame);
+        }
+        std::fs::write(path, data).map_err(|err| format_error("raw", path, err))
+    }
+
+    pub fn open(path: &Path) -> DynoResult<Self> {
+        let data = std::fs::read(path).map_err(|err| format_error("raw", path, err))?;
+        let Some(mut rest) = data.strip_prefix(Self::MAGIC) else {
+            return Err(format_error("raw", path, "not a raw frames file"));
+        };
+        let mut frames = vec![];
+        while !rest.is_empty() {
+            let Some((frame, tail)) = Self::read_frame(rest) else {
+                return Err(format_error("raw", path, "truncated frame"));
+            };
+            frames.push(frame);
+            rest = tail;
+        }
+        Ok(Self { frames })
+    }
+
+    /// `[time stamp: i64 LE][length: u32 LE][frame]`
+    fn read_frame(data: &[u8]) -> Option<(Frame, &[u8])> {
+        let time = i64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+        let len = u32::from_le_bytes(data.get(8..12)?.try_into().ok()?) as usize;
+        let frame = data.get(12..12 + len)?;
+        Some(((time, frame.to_vec()), &data[12 + len..]))
+    }
+
+    /// raw frames saved next to the run file in `path`, empty if the run has no raw frames.
+    pub fn open_sidecar(path: &Path) -> Self {
+        let sidecar = Self::sidecar_path(path);
+        if !sidecar.exists() {
+            return Self::default();
+        }
+        Self::open(&sidecar).unwrap_or_else(|err| {
+            dyno_core::log::warn!("{err}");
+            Self::default()
+        })
+    }
+}
+
+/// how a revision is made, saved next to the revision as `<file>.toml`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RevisionInfo {
+    /// run file the revision is processed from.
+    pub source: PathBuf,
+    pub time: NaiveDateTime,
+    pub config: DynoConfig,
+}
+
+impl RevisionInfo {
+    pub fn info_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".toml");
+        path.with_file_name(name)
+    }
+}
+
+/// `<file>.config.toml` for the run file in `path`.
+pub fn config_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".config.toml");
+    path.with_file_name(name)
+}
+
+/// save the `DynoConfig` the run in `path` is recorded with.
+pub fn save_config(path: &Path, config: &DynoConfig) -> DynoResult<()> {
+    let sidecar = config_path(path);
+    let data =
+        dyno_core::toml::to_string(config).map_err(|err| format_error("config", path, err))?;
+    std::fs::write(&sidecar, data).map_err(|err| format_error("config", &sidecar, err))
+}
+
+/// `DynoConfig` of the run in `path`, from `<file>.config.toml` or the info of a revision,
+/// `None` if the config is unknown (runs saved before the config is kept).
+pub fn open_config(path: &Path) -> Option<DynoConfig> {
+    let read = |sidecar: PathBuf| std::fs::read_to_string(sidecar).ok();
+    if let Some(data) = read(config_path(path)) {
+        return dyno_core::toml::from_str(&data)
+            .map_err(|err| dyno_core::log::warn!("{}", format_error("config", path, err)))
+            .ok();
+    }
+    read(RevisionInfo::info_path(path))
+        .and_then(|data| dyno_core::toml::from_str::<RevisionInfo>(&data).ok())
+        .map(|info| info.config)
+}
+
+/// next free revision path of the run file in `path`, `run.dyno` and `run.rev1.dyno`
+/// both give `run.rev2.dyno` if `run.rev1.dyno` exists.
+pub fn revision_path(path: &Path) -> PathBuf {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let base = match stem.rsplit_once(".rev") {
+        Some((base, rev)) if rev.parse::<u32>().is_ok() => base,
+        _ => stem.as_str(),
+    };
+    (1..)
+        .map(|n| path.with_file_name(format!("{base}.rev{n}.{ext}")))
+        .find(|p| !p.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_frames_and_revision() {
+        let dir = std::env::temp_dir().join(format!("dyno-raw-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let run = dir.join("run.dyno");
+
+        let mut frames = RawFrames::default();
+        frames.push(1_686_000_000_000, vec![1, 2, SerialData::DELIM]);
+        frames.push(1_686_000_000_250, vec![4, 5]);
+        let sidecar = RawFrames::sidecar_path(&run);
+        assert_eq!(sidecar, dir.join("run.dyno.raw"));
+        frames.save(&sidecar).unwrap();
+        assert_eq!(RawFrames::open_sidecar(&run), frames);
+
+        std::fs::write(&sidecar, [1, 2, 3, SerialData::DELIM]).unwrap();
+        assert!(RawFrames::open(&sidecar).is_err());
+        assert!(RawFrames::open_sidecar(&run).is_empty());
+
+        assert!(open_config(&run).is_none());
+        save_config(&run, &DynoConfig::default()).unwrap();
+        assert!(open_config(&run).is_some());
+
+        assert_eq!(revision_path(&run), dir.join("run.rev1.dyno"));
+        std::fs::write(dir.join("run.rev1.dyno"), b"").unwrap();
+        assert_eq!(
+            revision_path(&dir.join("run.rev1.dyno")),
+            dir.join("run.rev2.dyno")
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,129 @@
+//! Summary of a run (peak power and torque), used by the headless command line and telemetry.
+use super::{records, Record};
+use crate::tr;
+use dyno_core::{serde, BufferData};
+
+const IDX_SPEED: usize = 0;
+const IDX_RPM_ENGINE: usize = 2;
+const IDX_TORQUE: usize = 3;
+const IDX_HORSEPOWER: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct RunSummary {
+    pub samples: usize,
+    /// duration in seconds.
+    pub duration: f64,
+    /// maximum speed in km/h.
+    pub max_speed: f64,
+    pub max_rpm: f64,
+    /// peak horsepower (HP) and the engine rpm where it happens.
+    pub peak_hp: f64,
+    pub peak_hp_rpm: f64,
+    /// peak torque (Nm) and the engine rpm where it happens.
+    pub peak_torque: f64,
+    pub peak_torque_rpm: f64,
+}
+
+impl RunSummary {
+    pub fn from_records(records: &[Record]) -> Self {
+        let start = records.first().map(|r| r.time_stamp).unwrap_or_default();
+        records.iter().fold(Self::default(), |mut summary, record| {
+            summary.push(record, start);
+            summary
+        })
+    }
+
+    /// update the summary with the next `record`, `start` is the time stamp of the first record.
+    pub fn push(&mut self, record: &Record, start: i64) {
+        let rpm = record.values[IDX_RPM_ENGINE];
+        self.samples += 1;
+        self.duration = (record.time_stamp - start) as f64 / 1000.0;
+        self.max_speed = self.max_speed.max(record.values[IDX_SPEED]);
+        self.max_rpm = self.max_rpm.max(rpm);
+        if self.samples == 1 || record.values[IDX_HORSEPOWER] > self.peak_hp {
+            self.peak_hp = record.values[IDX_HORSEPOWER];
+            self.peak_hp_rpm = rpm;
+        }
+        if self.samples == 1 || record.values[IDX_TORQUE] > self.peak_torque {
+            self.peak_torque = record.values[IDX_TORQUE];
+            self.peak_torque_rpm = rpm;
+        }
+    }
+
+    #[inline]
+    pub fn from_buffer(buffer: &BufferData) -> Self {
+        Self::from_records(&records(buffer))
+    }
+}
+
+impl std::fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = [
+            (tr!("Samples"), self.samples.to_string()),
+            (tr!("Duration"), format!("{:.3} s", self.duration)),
+            (tr!("Max Speed"), format!("{:.2} km/h", self.max_speed)),
+            (tr!("Max RPM"), format!("{:.0} rpm", self.max_rpm)),
+            (
+                tr!("Peak Power"),
+                format!("{:.2} HP @ {:.0} rpm", self.peak_hp, self.peak_hp_rpm),
+            ),
+            (
+                tr!("Peak Torque"),
+                format!(
+                    "{:.2} Nm @ {:.0} rpm",
+                    self.peak_torque, self.peak_torque_rpm
+                ),
+            ),
+        ];
+        // the labels are aligned, the width depends on the language.
+        let width = rows.iter().map(|(label, _)| label.chars().count()).max();
+        for (idx, (label, value)) in rows.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("\n")?;
+            }
+            write!(
+                f,
+                "{label:<width$} : {value}",
+                width = width.unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_values() {
+        // torque falls after 4000 rpm, the power peaks at 4500 rpm.
+        let records = (0..=10)
+            .map(|i| {
+                let rpm = 2000.0 + i as f64 * 500.0;
+                let torque = 100.0 - (rpm - 4000.0).max(0.0) * 0.02;
+                let mut values = [0.0; 7];
+                values[IDX_SPEED] = i as f64 * 10.0;
+                values[IDX_RPM_ENGINE] = rpm;
+                values[IDX_TORQUE] = torque;
+                values[IDX_HORSEPOWER] = torque * rpm / 7120.91;
+                Record {
+                    time_stamp: 1_000 + i * 250,
+                    values,
+                }
+            })
+            .collect::<Vec<_>>();
+        let summary = RunSummary::from_records(&records);
+        let close = |value: f64, expected: f64| (value - expected).abs() < 1e-9;
+        assert_eq!(summary.samples, 11);
+        assert!(close(summary.duration, 2.5));
+        assert!(close(summary.max_speed, 100.0));
+        assert!(close(summary.max_rpm, 7000.0));
+        assert!(close(summary.peak_torque, 100.0));
+        assert!(close(summary.peak_torque_rpm, 2000.0));
+        assert!(close(summary.peak_hp, 90.0 * 4500.0 / 7120.91));
+        assert!(close(summary.peak_hp_rpm, 4500.0));
+        assert_eq!(RunSummary::from_records(&[]), RunSummary::default());
+    }
+}
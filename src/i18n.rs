@@ -0,0 +1,231 @@
+//! Translation of the UI text. The text in the code is the English message, and is the key of
+//! the message catalog of the other languages in `assets/i18n/<code>.toml`. A message without
+//! translation is shown in English.
+//!
+//! Use [`crate::tr!`] for the text, with the named arguments for the `{name}` placeholders:
+//! `tr!("Preset `{name}` saved", name = name)`.
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use dyno_core::{lazy_static, serde, toml};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum Language {
+    #[default]
+    English,
+    Indonesian,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Indonesian];
+
+    /// code of the language, also the name of the catalog file.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Indonesian => "id",
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Indonesian,
+            _ => Self::English,
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the name of the language in the language itself.
+        f.write_str(match self {
+            Self::English => "English",
+            Self::Indonesian => "Bahasa Indonesia",
+        })
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+const CATALOG_ID: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/i18n/id.toml"));
+
+fn parse_catalog(language: Language, data: &str) -> HashMap<String, String> {
+    toml::from_str(data).unwrap_or_else(|err| {
+        dyno_core::log::error!(
+            "Failed to read `{}` message catalog - {err}",
+            language.code()
+        );
+        HashMap::new()
+    })
+}
+
+lazy_static::lazy_static! {
+    static ref INDONESIAN: HashMap<String, String> = parse_catalog(Language::Indonesian, CATALOG_ID);
+}
+
+#[inline]
+pub fn language() -> Language {
+    Language::from_u8(LANGUAGE.load(Ordering::Relaxed))
+}
+
+#[inline]
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+/// `text` in the current language.
+pub fn translate(text: &'static str) -> &'static str {
+    let catalog = match language() {
+        Language::English => return text,
+        Language::Indonesian => &*INDONESIAN,
+    };
+    match catalog.get(text) {
+        Some(translated) => translated.as_str(),
+        None => {
+            dyno_core::log::debug!("Missing `{}` translation of `{text}`", language().code());
+            text
+        }
+    }
+}
+
+/// replace the `{name}` placeholders of `text` with the `args`.
+pub fn translate_args(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text.to_owned(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+/// text in the current language, see [`crate::i18n`].
+#[macro_export]
+macro_rules! tr {
+    ($text:literal $(,)?) => {
+        $crate::i18n::translate($text)
+    };
+    ($text:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate_args(
+            $crate::i18n::translate($text),
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names = text
+            .split('{')
+            .skip(1)
+            .filter_map(|s| s.split_once('}').map(|(name, _)| name))
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// string literal at the start of `src`, with the escaped quote unescaped.
+    fn literal(src: &str) -> Option<String> {
+        let src = src.trim_start().strip_prefix('"')?;
+        let mut out = String::new();
+        let mut chars = src.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(out),
+                '\\' => out.push(chars.next()?),
+                c => out.push(c),
+            }
+        }
+        None
+    }
+
+    /// arguments of the macro call at the start of `src`, split on the top level commas.
+    fn arguments(src: &str) -> Vec<&str> {
+        let (mut args, mut depth, mut start, mut in_str, mut escaped) =
+            (vec![], 1, 0, false, false);
+        for (idx, c) in src.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_str => escaped = true,
+                '"' => in_str = !in_str,
+                _ if in_str => {}
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 1 => {
+                    args.push(&src[start..idx]);
+                    start = idx + 1;
+                }
+                _ => {}
+            }
+            if depth == 0 {
+                args.push(&src[start..idx]);
+                break;
+            }
+        }
+        args
+    }
+
+    /// messages of `tr!` and the name and description of `row_label_value!` in `src`.
+    fn messages(src: &str, out: &mut Vec<String>) {
+        let calls = |name: &'static str| {
+            src.match_indices(name)
+                .filter(|(idx, _)| {
+                    // skip the text in string, and the other macros like `include_str!(`.
+                    !src[..*idx].ends_with(|c: char| c == '"' || c == '_' || c.is_alphanumeric())
+                })
+                .map(move |(idx, _)| arguments(&src[idx + name.len()..]))
+        };
+        for args in calls("tr!(") {
+            out.extend(args.first().and_then(|text| literal(text)));
+        }
+        for args in calls("row_label_value!(") {
+            let texts = args
+                .iter()
+                .filter_map(|arg| literal(arg))
+                .collect::<Vec<_>>();
+            out.extend(texts.into_iter().rev().take(2));
+        }
+    }
+
+    fn visit(dir: &std::path::Path, out: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                visit(&path, out);
+            } else if path.extension().map_or(false, |e| e == "rs") {
+                messages(&std::fs::read_to_string(&path).unwrap(), out);
+            }
+        }
+    }
+
+    #[test]
+    fn indonesian_catalog() {
+        let catalog = toml::from_str::<HashMap<String, String>>(CATALOG_ID).unwrap();
+        for (text, translated) in &catalog {
+            assert_eq!(placeholders(text), placeholders(translated), "{text}");
+        }
+
+        let mut texts = vec![];
+        visit(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut texts,
+        );
+        let missing = texts
+            .iter()
+            .filter(|t| !catalog.contains_key(t.as_str()))
+            .collect::<Vec<_>>();
+        assert!(missing.is_empty(), "missing translation {missing:#?}");
+
+        set_language(Language::Indonesian);
+        assert_eq!(translate("Quit"), "Keluar");
+        assert_eq!(
+            tr!("Preset `{name}` saved", name = "Chassis B"),
+            "Preset `Chassis B` disimpan"
+        );
+        set_language(Language::English);
+        assert_eq!(translate("Quit"), "Quit");
+    }
+}
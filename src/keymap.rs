@@ -0,0 +1,275 @@
+//! Keyboard shortcuts of the main window. Every [`Action`] has a default [`Shortcut`], big keys
+//! (function keys and space) are used where possible so the shortcuts can be pressed with gloves.
+//! The keymap is saved in [`crate::config::ApplicationConfig`], an action missing in the saved
+//! keymap uses its default shortcut.
+use dyno_core::serde;
+use eframe::egui::{Context, Key, Modifiers};
+
+use crate::tr;
+
+/// action of a shortcut, handled by `DynoControl::on_action`. An action is only added here
+/// together with its handler, a shortcut of an action that does nothing is never listed in the
+/// keymap settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub enum Action {
+    StartRecording,
+    StopRecording,
+    ResetRecording,
+    Open,
+    Save,
+    ToggleLeftPanel,
+    ToggleBottomPanel,
+    MarkEvent,
+    NextPlotPanel,
+    OpenSettings,
+}
+
+impl Action {
+    /// every action, in the order of the keymap settings.
+    pub const ALL: [Self; 10] = [
+        Self::StartRecording,
+        Self::StopRecording,
+        Self::ResetRecording,
+        Self::Open,
+        Self::Save,
+        Self::ToggleLeftPanel,
+        Self::ToggleBottomPanel,
+        Self::MarkEvent,
+        Self::NextPlotPanel,
+        Self::OpenSettings,
+    ];
+
+    pub fn default_shortcut(self) -> Shortcut {
+        match self {
+            Self::StartRecording => Shortcut::new(Key::F5),
+            Self::StopRecording => Shortcut::new(Key::F6),
+            Self::ResetRecording => Shortcut::new(Key::F8),
+            Self::Open => Shortcut::ctrl(Key::O),
+            Self::Save => Shortcut {
+                shift: true,
+                ..Shortcut::ctrl(Key::S)
+            },
+            Self::ToggleLeftPanel => Shortcut::new(Key::F2),
+            Self::ToggleBottomPanel => Shortcut::new(Key::F3),
+            Self::MarkEvent => Shortcut::new(Key::Space),
+            Self::NextPlotPanel => Shortcut::new(Key::F4),
+            Self::OpenSettings => Shortcut::new(Key::F9),
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::StartRecording => tr!("start the serial service and record the data"),
+            Self::StopRecording => tr!("stop (pause) the recording"),
+            Self::ResetRecording => tr!("stop the recording and clear the recorded data"),
+            Self::Open => tr!("open a binaries file"),
+            Self::Save => tr!("save the data to a binaries file"),
+            Self::ToggleLeftPanel => tr!("show or hide the gauges panel"),
+            Self::ToggleBottomPanel => tr!("show or hide the status panel"),
+            Self::MarkEvent => tr!("mark an event in the current run"),
+            Self::NextPlotPanel => tr!("switch to the next plot panel"),
+            Self::OpenSettings => tr!("open or close the settings window"),
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::StartRecording => tr!("Start Recording"),
+            Self::StopRecording => tr!("Stop Recording"),
+            Self::ResetRecording => tr!("Reset Recording"),
+            Self::Open => tr!("Open File"),
+            Self::Save => tr!("Save File"),
+            Self::ToggleLeftPanel => tr!("Toggle Left Panel"),
+            Self::ToggleBottomPanel => tr!("Toggle Bottom Panel"),
+            Self::MarkEvent => tr!("Mark Event"),
+            Self::NextPlotPanel => tr!("Next Plot Panel"),
+            Self::OpenSettings => tr!("Settings"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct Shortcut {
+    pub key: Key,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl Shortcut {
+    pub const fn new(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(key: Key) -> Self {
+        Self {
+            ctrl: true,
+            ..Self::new(key)
+        }
+    }
+
+    pub fn from_modifiers(key: Key, modifiers: Modifiers) -> Self {
+        Self {
+            key,
+            ctrl: modifiers.ctrl || modifiers.command,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+        }
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl,
+            shift: self.shift,
+            alt: self.alt,
+            ..Default::default()
+        }
+    }
+
+    /// `true` if the shortcut is typed as a text, without Ctrl or Alt.
+    #[inline]
+    pub const fn is_text(&self) -> bool {
+        !self.ctrl && !self.alt
+    }
+}
+
+impl std::fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// shortcut of an action, `None` if the action has no shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct KeyBinding {
+    pub action: Action,
+    pub shortcut: Option<Shortcut>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct KeyMap {
+    bindings: Vec<KeyBinding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| KeyBinding {
+                    action,
+                    shortcut: Some(action.default_shortcut()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    pub fn get(&self, action: Action) -> Option<Shortcut> {
+        match self.bindings.iter().find(|b| b.action == action) {
+            Some(binding) => binding.shortcut,
+            None => Some(action.default_shortcut()),
+        }
+    }
+
+    pub fn set(&mut self, action: Action, shortcut: Option<Shortcut>) {
+        match self.bindings.iter_mut().find(|b| b.action == action) {
+            Some(binding) => binding.shortcut = shortcut,
+            None => self.bindings.push(KeyBinding { action, shortcut }),
+        }
+    }
+
+    /// other action that already uses `shortcut`.
+    pub fn conflict(&self, action: Action, shortcut: Shortcut) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|other| *other != action && self.get(*other) == Some(shortcut))
+    }
+
+    /// actions that share the shortcut with another action, like in an edited settings file.
+    pub fn conflicts(&self) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|action| {
+                self.get(*action)
+                    .map_or(false, |s| self.conflict(*action, s).is_some())
+            })
+            .collect()
+    }
+
+    /// actions of the shortcuts pressed in this frame, the key events are consumed.
+    /// Shortcuts without Ctrl or Alt are skipped while a text is edited.
+    pub fn pressed(&self, ctx: &Context) -> Vec<Action> {
+        let typing = ctx.wants_keyboard_input();
+        ctx.input_mut(|i| {
+            Action::ALL
+                .into_iter()
+                .filter(|action| match self.get(*action) {
+                    Some(s) if !(typing && s.is_text()) => i.consume_key(s.modifiers(), s.key),
+                    _ => false,
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dyno_core::toml;
+
+    #[test]
+    fn bindings_and_conflicts() {
+        let mut keymap = KeyMap::default();
+        assert!(keymap.conflicts().is_empty());
+        assert_eq!(
+            keymap.get(Action::Save).unwrap().to_string(),
+            "Ctrl+Shift+S"
+        );
+
+        let f5 = Action::StartRecording.default_shortcut();
+        assert_eq!(
+            keymap.conflict(Action::MarkEvent, f5),
+            Some(Action::StartRecording)
+        );
+        keymap.set(Action::MarkEvent, Some(f5));
+        assert_eq!(
+            keymap.conflicts(),
+            [Action::StartRecording, Action::MarkEvent]
+        );
+        keymap.set(Action::StartRecording, None);
+        assert!(keymap.conflicts().is_empty());
+
+        // unbound action is kept, missing action uses the default shortcut.
+        let saved = toml::to_string(&keymap).unwrap();
+        let mut loaded = toml::from_str::<KeyMap>(&saved).unwrap();
+        assert_eq!(loaded, keymap);
+        loaded.bindings.retain(|b| b.action != Action::Open);
+        assert_eq!(loaded.get(Action::Open), Some(Shortcut::ctrl(Key::O)));
+        assert_eq!(loaded.get(Action::StartRecording), None);
+    }
+}
@@ -1,9 +1,15 @@
 mod constant;
 
+pub mod calculation;
+pub mod cli;
 pub mod config;
 pub mod control;
+pub mod formats;
+pub mod i18n;
+pub mod keymap;
 pub mod paths;
 pub mod service;
+pub mod settings;
 pub mod state;
 pub mod widgets;
 pub mod windows;
@@ -63,13 +69,33 @@ pub fn init_logger(file: impl AsRef<std::path::Path>) -> DynoResult<()> {
 pub enum AsyncMsg {
     OnSavedBuffer(()),
     OnCheckHealthApi(dyno_core::reqwest::StatusCode),
-    OnSerialData(dyno_core::SerialData),
+    /// parsed serial data, and the raw frame for [`formats::raw::RawFrames`].
+    OnSerialData(dyno_core::SerialData, Vec<u8>),
     OnMessage(String),
     OnError(DynoErr),
     OnApiLogin,
     OnApiRegister,
-    OnApiLoadDyno(Vec<dyno_core::dynotests::DynoTest>),
+    OnApiLoadDyno(service::DynoPage),
     OnOpenBuffer(Box<dyno_core::BufferData>),
+    /// run file opened to the buffer, with its raw frames (empty if not available).
+    OnOpenRaw(std::path::PathBuf, Box<formats::raw::RawFrames>),
+    /// event markers of the run file opened to the buffer.
+    OnOpenMarkers(formats::markers::Markers),
+    OnOpenCompare(String, Box<dyno_core::BufferData>),
+    OnRecentFile(state::DynoFileType, std::path::PathBuf),
+    /// buffer is saved to the file, linked to the selected vehicle.
+    OnSavedFile(state::DynoFileType, std::path::PathBuf),
+    /// outbox entry id, and the id from server.
+    OnUploaded(String, i32),
+    OnUploadFailed(String, DynoErr),
+    /// server rejected the session, the request is sent again after login.
+    OnApiUnauthorized(service::ApiRequest),
+    OnSync(service::SyncEvent),
+    OnReview(service::ReviewEvent),
+    /// progress of chunked upload or download.
+    OnTransfer(service::TransferProgress),
+    /// settings file picked in File -> Import Settings.
+    OnImportSettings(std::path::PathBuf, settings::SettingsFile),
 }
 
 impl AsyncMsg {
@@ -78,15 +104,15 @@ impl AsyncMsg {
         Self::OnSavedBuffer(())
     }
     #[inline]
-    pub const fn serial_data(inner: dyno_core::SerialData) -> Self {
-        Self::OnSerialData(inner)
+    pub fn serial_data(inner: dyno_core::SerialData, frame: Vec<u8>) -> Self {
+        Self::OnSerialData(inner, frame)
     }
     #[inline]
     pub const fn check_health(inner: dyno_core::reqwest::StatusCode) -> Self {
         Self::OnCheckHealthApi(inner)
     }
     #[inline]
-    pub const fn on_load_dyno(data: Vec<dyno_core::dynotests::DynoTest>) -> Self {
+    pub const fn on_load_dyno(data: service::DynoPage) -> Self {
         Self::OnApiLoadDyno(data)
     }
     #[inline]
@@ -98,6 +124,22 @@ impl AsyncMsg {
         Self::OnOpenBuffer(Box::new(inner))
     }
     #[inline]
+    pub fn open_compare(name: impl ToString, inner: dyno_core::BufferData) -> Self {
+        Self::OnOpenCompare(name.to_string(), Box::new(inner))
+    }
+    #[inline]
+    pub fn recent_file(tp: state::DynoFileType, path: std::path::PathBuf) -> Self {
+        Self::OnRecentFile(tp, path)
+    }
+    #[inline]
+    pub const fn uploaded(id: String, server_id: i32) -> Self {
+        Self::OnUploaded(id, server_id)
+    }
+    #[inline]
+    pub fn upload_failed(id: String, err: impl Into<DynoErr>) -> Self {
+        Self::OnUploadFailed(id, err.into())
+    }
+    #[inline]
     pub fn message(inner: impl ToString) -> Self {
         Self::OnMessage(inner.to_string())
     }
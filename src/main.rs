@@ -3,10 +3,11 @@
 
 use dyno_core::{ignore_err, serde, tokio};
 use dynotest_app::{
-    control::DynoControl, init_logger, msg_dialog_err, state::DynoState, windows::WindowStack,
-    PanelId, APP_KEY, PACKAGE_INFO, TOAST_MSG,
+    cli::Command, control::DynoControl, init_logger, msg_dialog_err, paths::DynoPaths,
+    state::DynoState, windows::WindowStack, PanelId, APP_KEY, PACKAGE_INFO, TOAST_MSG,
 };
 use eframe::egui::*;
+use std::path::PathBuf;
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(crate = "serde")]
@@ -32,23 +33,26 @@ impl Default for Applications {
 
 impl Applications {
     #[allow(clippy::new_ret_no_self)]
-    pub fn run(control: DynoControl) {
-        let log_dir = control.paths.get_cache_dir_file("logs/log.log");
+    pub fn run(control: DynoControl, file: Option<PathBuf>) {
+        let log_dir = control.paths.get_cache_dir_file(DynoPaths::LOG_FILE);
         ignore_err!(init_logger(log_dir));
         let opt = control.app_config.app_options.main_window_opt();
         let app_creator: eframe::AppCreator = Box::new(|cc| {
-            Box::new(
-                cc.storage
-                    .and_then(|s| eframe::get_value::<Self>(s, APP_KEY).map(Self::init))
-                    .unwrap_or_else(|| {
-                        Self {
-                            window_stack: WindowStack::new(),
-                            control,
-                            ..Default::default()
-                        }
-                        .init()
-                    }),
-            )
+            let mut app = cc
+                .storage
+                .and_then(|s| eframe::get_value::<Self>(s, APP_KEY).map(Self::init))
+                .unwrap_or_else(|| {
+                    Self {
+                        window_stack: WindowStack::new(),
+                        control,
+                        ..Default::default()
+                    }
+                    .init()
+                });
+            if let Some(file) = file {
+                app.control.open_path(file, &mut app.state);
+            }
+            Box::new(app)
         });
 
         if let Err(err) = eframe::run_native(PACKAGE_INFO.app_name, opt, app_creator) {
@@ -112,6 +116,9 @@ impl eframe::App for Applications {
         }
         crate::TOAST_MSG.lock().show(ctx);
 
+        self.control
+            .key_events(ctx, &mut self.window_stack, &mut self.state);
+        self.control.handle_dropped_files(ctx, &mut self.state);
         self.control.handle_states(ctx);
         self.main_panels_draw(ctx);
 
@@ -150,6 +157,7 @@ impl eframe::App for Applications {
     }
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.control.save_active_config();
         eframe::set_value(storage, APP_KEY, self);
     }
 
@@ -173,6 +181,23 @@ fn main() {
         })
     });
 
+    // `dynotest_app path/to/run.dyno` open the file at startup,
+    // `dynotest_app --headless ..` run without the GUI window.
+    let file = match Command::parse(std::env::args_os().skip(1)) {
+        Ok(Command::Gui(file)) => file,
+        Ok(command) => {
+            if let Err(err) = command.run() {
+                eprintln!("ERROR: {err}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Err(err) => {
+            eprintln!("ERROR: {err}\n\n{}", dynotest_app::cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     let control = DynoControl::new();
-    Applications::run(control)
+    Applications::run(control, file)
 }
@@ -4,7 +4,12 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::widgets::DynoFileManager;
+use crate::{
+    formats::import::ImportProfiles,
+    service::{CalibrationLog, Outbox, Presets, VehicleDb},
+    tr,
+    widgets::DynoFileManager,
+};
 use dyno_core::{chrono::Local, paste::paste, serde, toml, CompresedSaver, DynoErr, DynoResult};
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -41,7 +46,7 @@ macro_rules! dyno_paths {
 // }
 
 #[allow(unused)]
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(crate = "serde")]
 pub struct DynoPaths {
     pub name: String,
@@ -67,6 +72,65 @@ dyno_paths!(
     ]
 );
 
+/// result of [`DynoPaths::migrate_from`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathsMigration {
+    /// files and folders moved to the new folders.
+    pub moved: usize,
+    /// not moved, a file with the same name exists in the new folder.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// `name` is `pattern`, a `*` in the pattern matches any text.
+fn is_entry(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => name == pattern,
+    }
+}
+
+/// rename, or copy and remove if the folders are on different drives.
+fn move_entry(from: &Path, to: &Path) -> std::io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            move_entry(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::remove_dir(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
+/// undo [`move_entry`] of `from` to `to`, also after it failed halfway, the entries that are
+/// still in `from` are kept.
+fn move_back(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !to.exists() {
+        return Ok(());
+    }
+    if !from.exists() {
+        return move_entry(to, from);
+    }
+    if to.is_dir() {
+        for entry in fs::read_dir(to)? {
+            let entry = entry?;
+            move_back(&from.join(entry.file_name()), &entry.path())?;
+        }
+        fs::remove_dir(to)
+    } else {
+        fs::remove_file(to)
+    }
+}
+
 #[inline]
 pub fn file_name_timestamp(extension: &str) -> String {
     format!("{}.{}", Local::now().format("%v_%s"), extension)
@@ -96,8 +160,9 @@ impl DynoPaths {
         }
     }
 
-    pub fn check_is_changed(&mut self, other: &Self) {
-        if crate::eq_structs!(self, other -> [
+    /// copy `other` if it's different, returns `true` if changed.
+    pub fn check_is_changed(&mut self, other: &Self) -> bool {
+        let changed = !crate::eq_structs!(self, other -> [
             name,
             project_path,
             cache_dir,
@@ -105,9 +170,11 @@ impl DynoPaths {
             data_dir,
             data_local_dir,
             preference_dir
-        ]) {
+        ]);
+        if changed {
             *self = other.clone();
         }
+        changed
     }
 
     #[inline(always)]
@@ -176,6 +243,144 @@ impl DynoPaths {
         r#"PREFERENCE PATH"#,
     ];
 
+    /// log file in the cache dir.
+    pub const LOG_FILE: &'static str = "logs/log.log";
+
+    /// files and folders of the application in the folders of [`Self::dirs`], only these are
+    /// moved by [`Self::migrate_from`], other files in the folder belong to the user.
+    const APP_ENTRIES: [&'static [&'static str]; 5] = [
+        &["logs"],
+        &[
+            "app_config.toml",
+            "config.toml",
+            ImportProfiles::FILE_NAME,
+            Presets::FOLDER,
+            // login sessions, `ServerProfile::session_file`
+            "session_*.json",
+        ],
+        &[
+            "Saved",
+            "Exported",
+            Outbox::FOLDER_NAME,
+            VehicleDb::FILE_NAME,
+            CalibrationLog::FILE_NAME,
+        ],
+        &[],
+        &[],
+    ];
+
+    /// folders of the application, `project_path` is only the relative name of the project.
+    fn dirs(&self) -> [(&'static str, &PathBuf); 5] {
+        [
+            (Self::PATHS_NAME[1], &self.cache_dir),
+            (Self::PATHS_NAME[2], &self.config_dir),
+            (Self::PATHS_NAME[3], &self.data_dir),
+            (Self::PATHS_NAME[4], &self.data_local_dir),
+            (Self::PATHS_NAME[5], &self.preference_dir),
+        ]
+    }
+
+    /// create the folders that don't exist, and check that every folder is writable.
+    pub fn validate(&self) -> DynoResult<()> {
+        for (name, dir) in self.dirs() {
+            if dir.as_os_str().is_empty() {
+                return Err(DynoErr::filesystem_error(format!("{name} is empty")));
+            }
+            if dir.exists() && !dir.is_dir() {
+                return Err(DynoErr::filesystem_error(format!(
+                    "{name} `{}` is not a folder",
+                    dir.display()
+                )));
+            }
+            fs::create_dir_all(dir).map_err(|err| {
+                DynoErr::filesystem_error(format!(
+                    "Failed to create {name} `{}` - {err}",
+                    dir.display()
+                ))
+            })?;
+            let probe = dir.join(".dyno_write_test");
+            fs::write(&probe, b"")
+                .and_then(|_| fs::remove_file(&probe))
+                .map_err(|err| {
+                    DynoErr::filesystem_error(format!(
+                        "{name} `{}` is not writable - {err}",
+                        dir.display()
+                    ))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// move the files of the application ([`Self::APP_ENTRIES`]) in the folders of `old` to the
+    /// changed folders of `self`, a file that already exists in the new folder is not
+    /// overwritten. Folders that are the same in `old` (config and preference dir on linux) are
+    /// moved once, to the first new folder. If a move fails, the moved files are moved back, so
+    /// the old folders can be kept in use.
+    pub fn migrate_from(&self, old: &Self) -> DynoResult<PathsMigration> {
+        let mut migration = PathsMigration::default();
+        let mut moves = Vec::new();
+        let mut done = Vec::new();
+        let old_dirs = old.dirs();
+        for ((name, new), (_, old)) in self.dirs().into_iter().zip(old_dirs) {
+            if new == old || done.contains(&old) || !old.is_dir() {
+                continue;
+            }
+            if new.starts_with(old) {
+                return Err(DynoErr::filesystem_error(format!(
+                    "{name} `{}` is inside the old folder `{}`, it can't be moved",
+                    new.display(),
+                    old.display()
+                )));
+            }
+            done.push(old);
+            let patterns = old_dirs
+                .iter()
+                .zip(Self::APP_ENTRIES)
+                .filter(|((_, dir), _)| *dir == old)
+                .flat_map(|(_, patterns)| patterns.iter())
+                .collect::<Vec<_>>();
+            fs::create_dir_all(new)?;
+            for entry in fs::read_dir(old)? {
+                let from = entry?.path();
+                let file_name = from.file_name().unwrap_or_default();
+                if !patterns
+                    .iter()
+                    .any(|pattern| is_entry(pattern, &file_name.to_string_lossy()))
+                {
+                    continue;
+                }
+                let to = new.join(file_name);
+                if to.exists() {
+                    migration.skipped.push(from);
+                    continue;
+                }
+                moves.push((from, to));
+            }
+        }
+
+        for (idx, (from, to)) in moves.iter().enumerate() {
+            if let Err(err) = move_entry(from, to) {
+                let mut msg = format!(
+                    "Failed to move `{}` to `{}` - {err}",
+                    from.display(),
+                    to.display()
+                );
+                for (from, to) in moves[..=idx].iter().rev() {
+                    if let Err(err) = move_back(from, to) {
+                        msg.push_str(&format!(
+                            "\n`{}` is left in `{}` - {err}",
+                            from.display(),
+                            to.display()
+                        ));
+                    }
+                }
+                return Err(DynoErr::filesystem_error(msg));
+            }
+            migration.moved += 1;
+        }
+        Ok(migration)
+    }
+
     #[inline]
     pub fn get_config<D>(&self, filename: &'_ str) -> DynoResult<D>
     where
@@ -241,9 +446,12 @@ impl DynoPaths {
     pub fn draw(&mut self, ui: &mut eframe::egui::Ui, edit: &mut bool) {
         use eframe::egui::{Grid, Link, TextEdit};
         ui.add_space(50.0);
-        ui.add(TextEdit::singleline(&mut self.name).hint_text("app dir name"));
+        ui.add_enabled(
+            *edit,
+            TextEdit::singleline(&mut self.name).hint_text(tr!("app dir name")),
+        );
         ui.add_space(20.0);
-        ui.checkbox(edit, "Edit Paths Config");
+        ui.checkbox(edit, tr!("Edit Paths Config"));
         ui.add_space(20.0);
         Grid::new("dyno_setting_paths")
             .num_columns(2)
@@ -251,12 +459,12 @@ impl DynoPaths {
             .show(ui, |ui| {
                 for (i, paths) in self.as_slice_mut().into_iter().enumerate() {
                     ui.label(Self::PATHS_NAME[i])
-                        .on_hover_text("Click on the path in the right to edit the paths");
+                        .on_hover_text(tr!("Click on the path in the right to edit the paths"));
                     let links = ui
                         .add_enabled(*edit, Link::new(paths.to_string_lossy()))
-                        .on_hover_text("Click to Edit");
+                        .on_hover_text(tr!("Click to Edit"));
                     if links.clicked() {
-                        if let Some(p) = DynoFileManager::pick_folder("Change Path", &paths) {
+                        if let Some(p) = DynoFileManager::pick_folder(tr!("Change Path"), &paths) {
                             *paths = p;
                         }
                     }
@@ -266,3 +474,59 @@ impl DynoPaths {
         ui.add_space(50.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_and_migrate() {
+        let root = std::env::temp_dir().join(format!("dyno-paths-{}", std::process::id()));
+        let with_root = |root: &Path| {
+            let mut paths = DynoPaths::default();
+            for dir in paths.as_slice_mut() {
+                *dir = root.join(dir.file_name().unwrap_or_default());
+            }
+            paths
+        };
+        let old = with_root(&root.join("old"));
+        old.validate().unwrap();
+        assert!(old.data_dir.is_dir());
+        fs::write(old.get_data_dir_file("vehicles.toml"), "a").unwrap();
+        fs::create_dir_all(old.get_data_dir_folder("Saved/dyno")).unwrap();
+        fs::write(old.get_data_dir_file("notes.txt"), "user").unwrap();
+        fs::write(old.get_config_dir_file("config.toml"), "b").unwrap();
+
+        let mut new = with_root(&root.join("new"));
+        new.config_dir = old.config_dir.clone();
+        new.validate().unwrap();
+        fs::write(new.get_data_dir_file("vehicles.toml"), "c").unwrap();
+        let migration = new.migrate_from(&old).unwrap();
+        assert_eq!(migration.moved, 1);
+        assert_eq!(migration.skipped, [old.get_data_dir_file("vehicles.toml")]);
+        assert!(new.get_data_dir_folder("Saved/dyno").is_dir());
+        assert!(new.get_config_dir_file("config.toml").exists());
+        assert!(old.get_data_dir_file("notes.txt").exists());
+        assert!(!new.get_data_dir_file("notes.txt").exists());
+
+        // `a` is moved, `b` is not moved yet when the move failed.
+        let (from, to) = (root.join("from"), root.join("to"));
+        fs::create_dir_all(&from).unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(from.join("b"), "").unwrap();
+        fs::write(to.join("a"), "").unwrap();
+        move_back(&from, &to).unwrap();
+        assert!(from.join("a").exists() && from.join("b").exists());
+        assert!(!to.exists());
+
+        let mut inside = new.clone();
+        inside.data_dir = new.get_data_dir_folder("inner");
+        assert!(inside.migrate_from(&new).is_err());
+
+        fs::write(root.join("file"), "").unwrap();
+        let mut invalid = new.clone();
+        invalid.cache_dir = root.join("file");
+        assert!(invalid.validate().is_err());
+        fs::remove_dir_all(root).ok();
+    }
+}
@@ -1,12 +1,22 @@
 use dyno_core::{
     asyncify,
-    crypto::{checksum_from_bytes, compare_checksums},
+    chrono::NaiveDateTime,
+    crypto::checksum_from_bytes,
     dynotests::{DynoTest, DynoTestDataInfo},
-    reqwest::{multipart, Client, IntoUrl, Response},
-    ApiResponse, BufferData, CompresedSaver as _, DynoErr, DynoResult,
+    reqwest::{multipart, Client, IntoUrl},
+    ApiResponse, BufferData, CompresedSaver as _, DynoConfig, DynoErr, DynoResult,
 };
 
-use crate::AsyncMsg;
+use super::{
+    error_for_status,
+    features::{self, Feature, Features},
+    transfer::{self, Progress},
+    ApiRequest, DynoPage, DynoQuery, DynoTestEntry,
+};
+use crate::{
+    formats::{datetime_from_millis, markers::Markers, summary::RunSummary},
+    AsyncMsg,
+};
 
 #[inline]
 pub(super) async fn get_info_part(config: DynoTestDataInfo) -> DynoResult<multipart::Part> {
@@ -20,16 +30,26 @@ pub(super) async fn get_info_part(config: DynoTestDataInfo) -> DynoResult<multip
 }
 
 #[inline]
-pub(super) async fn get_data_part(data: BufferData) -> DynoResult<(multipart::Part, String)> {
-    asyncify!(move || data.compress().and_then(|compressed| {
-        let checksum = dyno_core::crypto::checksum_from_bytes(&compressed);
-        let compressed_len = compressed.len() as _;
-        multipart::Part::stream_with_length(compressed, compressed_len)
-            .file_name(dyno_core::uuid::Uuid::new_v4().simple().to_string())
-            .mime_str("application/octet-stream")
-            .map_err(DynoErr::service_error)
-            .map(|part| (part, checksum))
-    }))
+fn data_part(compressed: Vec<u8>) -> Result<multipart::Part, AsyncMsg> {
+    let len = compressed.len() as _;
+    multipart::Part::stream_with_length(compressed, len)
+        .file_name(dyno_core::uuid::Uuid::new_v4().simple().to_string())
+        .mime_str("application/octet-stream")
+        .map_err(AsyncMsg::error)
+}
+
+#[inline]
+fn summary_part(summary: &RunSummary) -> Result<multipart::Part, AsyncMsg> {
+    json_part(summary)
+}
+
+#[inline]
+fn json_part(value: &impl dyno_core::serde::Serialize) -> Result<multipart::Part, AsyncMsg> {
+    let data =
+        serde_json::to_vec(value).map_err(|err| AsyncMsg::error(DynoErr::service_error(err)))?;
+    multipart::Part::bytes(data)
+        .mime_str("application/json")
+        .map_err(AsyncMsg::error)
 }
 
 pub async fn save(
@@ -37,45 +57,151 @@ pub async fn save(
     client: Client,
     token: impl std::fmt::Display,
     multiparts: multipart::Form,
-) -> Result<AsyncMsg, AsyncMsg> {
-    match client
+    request: ApiRequest,
+) -> Result<i32, AsyncMsg> {
+    let resp = client
         .post(url)
         .multipart(multiparts)
         .bearer_auth(token)
         .send()
         .await
-        .and_then(Response::error_for_status)
         .map_err(AsyncMsg::error)
-    {
-        Ok(resp) => match resp.json::<ApiResponse<i32>>().await {
-            Ok(id) => Ok(AsyncMsg::message(format!(
-                "Save data is Success with id {}",
-                id.payload
-            ))),
-            Err(err) => Err(AsyncMsg::error(err)),
-        },
-        Err(err) => Err(err),
+        .and_then(|resp| error_for_status(resp, request))?;
+    resp.json::<ApiResponse<i32>>()
+        .await
+        .map(|id| id.payload)
+        .map_err(AsyncMsg::error)
+}
+
+/// upload the compressed data (`.dyno` file) as it is in chunks, so the checksum in server
+/// is the same as the checksum of the file, or as `data` of `POST {url}` if the server has no
+/// chunked upload. `times` is the start and stop of the run, read from the data if `None`.
+/// The event markers are sent as `markers` (json) if the run has any.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn upload_compressed(
+    url: String,
+    client: Client,
+    token: String,
+    features: Features,
+    compressed: Vec<u8>,
+    config: DynoConfig,
+    markers: Markers,
+    times: Option<(NaiveDateTime, NaiveDateTime)>,
+    request: ApiRequest,
+    progress: Progress,
+) -> Result<i32, AsyncMsg> {
+    let (compressed, data) = asyncify!(
+        move || BufferData::decompress(compressed.clone()).map(|data| (compressed, data))
+    )
+    .map_err(AsyncMsg::error)?;
+    let summary = RunSummary::from_buffer(&data);
+    let (start, stop) = times.unwrap_or_else(|| {
+        let time = |ms: Option<&i64>| ms.map(|ms| datetime_from_millis(*ms)).unwrap_or_default();
+        (time(data.time_stamp.first()), time(data.time_stamp.last()))
+    });
+    drop(data);
+
+    let checksum_hex = checksum_from_bytes(&compressed);
+    let size = compressed.len() as u64;
+    let session_url = if features.is_supported(Feature::ChunkedUpload) {
+        let session_url = transfer::upload_chunks(
+            &url,
+            &client,
+            &token,
+            &compressed,
+            &checksum_hex,
+            &request,
+            &progress,
+        )
+        .await?;
+        features.set(Feature::ChunkedUpload, session_url.is_some());
+        session_url
+    } else {
+        None
+    };
+    let info = DynoTestDataInfo {
+        checksum_hex,
+        config,
+        start,
+        stop,
+    };
+    let info_part = get_info_part(info).await.map_err(AsyncMsg::error)?;
+    let (url, mut multiparts) = match session_url {
+        Some(session_url) => (
+            format!("{session_url}/complete"),
+            multipart::Form::new().part("info", info_part),
+        ),
+        None => (
+            url,
+            multipart::Form::new()
+                .part("data", data_part(compressed)?)
+                .part("info", info_part),
+        ),
+    };
+    // peak values for the run browser, only for a server with the paged query.
+    if features.is_supported(Feature::PagedQuery) {
+        multiparts = multiparts.part("summary", summary_part(&summary)?);
     }
+    if !markers.is_empty() {
+        multiparts = multiparts.part("markers", json_part(&markers)?);
+    }
+    let id = save(url, client, token, multiparts, request).await?;
+    progress.report(size, size);
+    Ok(id)
 }
 
-pub async fn get(url: impl IntoUrl, client: Client, token: impl std::fmt::Display) -> AsyncMsg {
-    match client
+/// page of the runs in server, with the paged query if the server listed it, or a page of
+/// `GET {url}?all=true` for the base server.
+async fn fetch_page(
+    url: &str,
+    client: &Client,
+    token: &str,
+    features: &Features,
+    query: &DynoQuery,
+    request: &ApiRequest,
+) -> Result<DynoPage, AsyncMsg> {
+    if features.is_supported(Feature::PagedQuery) {
+        let resp = client
+            .get(url)
+            .query(&query.params())
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(AsyncMsg::error)?;
+        if !features::is_missing(resp.status()) {
+            return error_for_status(resp, request.clone())?
+                .json::<ApiResponse<DynoPage>>()
+                .await
+                .map(|page| page.payload)
+                .map_err(AsyncMsg::error);
+        }
+        features.set(Feature::PagedQuery, false);
+    }
+    let runs = client
         .get(url)
+        .query(&[("all", "true")])
         .bearer_auth(token)
         .send()
         .await
-        .and_then(Response::error_for_status)
         .map_err(AsyncMsg::error)
-    {
-        Ok(resp) => match resp
-            .json::<ApiResponse<Vec<DynoTest>>>()
-            .await
-            .map(|x| AsyncMsg::on_load_dyno(x.payload))
-            .map_err(AsyncMsg::error)
-        {
-            Ok(ok) => ok,
-            Err(err) => err,
-        },
+        .and_then(|resp| error_for_status(resp, request.clone()))?
+        .json::<ApiResponse<Vec<DynoTest>>>()
+        .await
+        .map_err(AsyncMsg::error)?
+        .payload;
+    Ok(query.page_of(runs))
+}
+
+pub async fn get(
+    url: String,
+    client: Client,
+    token: String,
+    features: Features,
+    query: DynoQuery,
+) -> AsyncMsg {
+    let request = ApiRequest::GetDyno(query.clone());
+    match fetch_page(&url, &client, &token, &features, &query, &request).await {
+        Ok(page) => AsyncMsg::on_load_dyno(page),
         Err(err) => err,
     }
 }
@@ -84,37 +210,47 @@ pub async fn load_file(
     url: impl IntoUrl,
     client: Client,
     token: impl std::fmt::Display,
-    checksum: impl AsRef<[u8]>,
+    checksum: String,
+    request_url: String,
+    compare: Option<String>,
+    progress: Progress,
 ) -> AsyncMsg {
-    match client
-        .get(url)
-        .bearer_auth(token)
-        .send()
+    let request = ApiRequest::LoadDynoFile {
+        url: request_url,
+        checksum: checksum.clone(),
+        compare: compare.clone(),
+    };
+    match transfer::download(url, client, token, &checksum, request, &progress)
         .await
-        .and_then(Response::error_for_status)
-        .map_err(AsyncMsg::error)
-    {
-        Ok(mut resp) => {
-            let mut buffer_data = if let Some(lenght) = resp.content_length() {
-                Vec::with_capacity(lenght as _)
-            } else {
-                vec![]
-            };
-            while let Ok(Some(chunk)) = resp.chunk().await {
-                buffer_data.extend(chunk);
-            }
-            let data_checksum = checksum_from_bytes(&buffer_data);
-            if !compare_checksums(data_checksum.as_bytes(), checksum.as_ref()) {
-                return AsyncMsg::error("Data Checksum is not matching.");
-            }
-            match BufferData::decompress(buffer_data)
-                .map_err(AsyncMsg::error)
-                .map(AsyncMsg::open_buffer)
-            {
-                Ok(ok) => ok,
-                Err(err) => err,
-            }
-        }
+        .and_then(|buffer_data| BufferData::decompress(buffer_data).map_err(AsyncMsg::error))
+        .map(|data| match compare {
+            Some(name) => AsyncMsg::open_compare(name, data),
+            None => AsyncMsg::open_buffer(data),
+        }) {
+        Ok(ok) => ok,
         Err(err) => err,
     }
 }
+
+/// get all pages of the runs in server.
+pub(super) async fn get_all(
+    url: String,
+    client: Client,
+    token: String,
+    features: Features,
+) -> Result<Vec<DynoTestEntry>, AsyncMsg> {
+    let mut query = DynoQuery {
+        per_page: 200,
+        ..Default::default()
+    };
+    let mut entries = vec![];
+    loop {
+        let page = fetch_page(&url, &client, &token, &features, &query, &ApiRequest::Sync).await?;
+        let last = page.items.is_empty() || query.page >= page.pages();
+        entries.extend(page.items);
+        if last {
+            return Ok(entries);
+        }
+        query.page += 1;
+    }
+}
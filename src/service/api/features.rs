@@ -0,0 +1,105 @@
+//! Optional routes of the server. The base server only has the single multipart upload
+//! `POST /dyno` and the list of `GET /dyno?all=true`, the newer routes are proposed extensions
+//! (see `SERVER FEATURES` in the README) and only used when the server lists them in the
+//! `features` of `GET /health`:
+//!
+//! ```json
+//! { "features": ["chunked_upload", "paged_query", "review", "refresh"] }
+//! ```
+//!
+//! The features are detected on connect, a server without the list is the base server. A listed
+//! route answered with `404 Not Found`, `405 Method Not Allowed` or `501 Not Implemented` marks
+//! the feature as unsupported again, until the service is reconnected.
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use dyno_core::{
+    reqwest::{Client, IntoUrl, StatusCode},
+    serde,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// `POST /dyno/uploads`, resumable upload in chunks.
+    ChunkedUpload,
+    /// `GET /dyno` with the page, filters and sorting as query params, and the `summary` part
+    /// of the upload.
+    PagedQuery,
+    /// `GET /users/me`, `GET /dyno/reviews` and `POST /dyno/{id}/{comments|verify|reject}`,
+    /// the review of the runs by instructors.
+    Review,
+    /// `GET /auth/refresh`, without it the session is used until the token expired.
+    Refresh,
+}
+
+impl Feature {
+    const ALL: [Self; 4] = [
+        Self::ChunkedUpload,
+        Self::PagedQuery,
+        Self::Review,
+        Self::Refresh,
+    ];
+
+    /// name of the feature in the `features` of `GET /health`.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::ChunkedUpload => "chunked_upload",
+            Self::PagedQuery => "paged_query",
+            Self::Review => "review",
+            Self::Refresh => "refresh",
+        }
+    }
+}
+
+/// state of the features of the connected server, shared with the async tasks.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Features(Arc<[AtomicBool; Feature::ALL.len()]>);
+
+impl Features {
+    /// `false` until the server listed `feature` in `GET /health`.
+    #[inline]
+    pub fn is_supported(&self, feature: Feature) -> bool {
+        self.0[feature as usize].load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn set(&self, feature: Feature, supported: bool) {
+        self.0[feature as usize].store(supported, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+struct Health {
+    features: Vec<String>,
+}
+
+/// set the features listed by the server in `GET /health`, a server that answered without
+/// the list (or with other body) has none of them.
+pub(super) async fn detect(url: impl IntoUrl, client: &Client, features: &Features) {
+    let health = match client.get(url).send().await {
+        Ok(resp) if resp.status().is_success() => resp.json::<Health>().await.unwrap_or_default(),
+        Ok(resp) => {
+            dyno_core::log::warn!("Failed to detect the server features - {}", resp.status());
+            Health::default()
+        }
+        Err(err) => {
+            dyno_core::log::warn!("Failed to detect the server features - {err}");
+            Health::default()
+        }
+    };
+    for feature in Feature::ALL {
+        features.set(feature, health.features.iter().any(|f| f == feature.name()));
+    }
+}
+
+/// the server doesn't have the route of the request.
+#[inline]
+pub(super) fn is_missing(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+    )
+}
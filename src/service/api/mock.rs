@@ -0,0 +1,338 @@
+//! In-process mock of the dyno api server, started on a local port for the tests of
+//! [`super::ApiService`]. Every route can be configured to fail with a status code,
+//! and every request can be delayed to test the timeout.
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Multipart, Path, State},
+    http::{header, HeaderMap, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use dyno_core::{
+    crypto::TokenDetails,
+    serde,
+    tokio::{self, sync::oneshot},
+    ApiResponse,
+};
+use eframe::epaint::mutex::Mutex;
+
+pub const MOCK_TOKEN: &str = "mock-token";
+
+#[derive(Default)]
+struct MockState {
+    /// status and the number of requests it is responded with.
+    failures: Mutex<HashMap<String, (StatusCode, usize)>>,
+    latency: Mutex<Duration>,
+    files: Mutex<HashMap<String, Vec<u8>>>,
+    /// received data of the chunked upload sessions.
+    sessions: Mutex<HashMap<String, Vec<u8>>>,
+    uploads: Mutex<Vec<HashMap<String, Vec<u8>>>>,
+    /// path of every request.
+    requests: Mutex<Vec<String>>,
+    /// answer as the base server, without the optional routes.
+    base: AtomicBool,
+}
+
+pub struct MockServer {
+    pub addr: SocketAddr,
+    state: Arc<MockState>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// start the server on random local port, must be called inside the tokio runtime.
+    pub fn start() -> Self {
+        let state = Arc::new(MockState::default());
+        let app = Router::new()
+            .route("/api/health", get(health))
+            .route("/api/active", post(|| async { StatusCode::OK }))
+            .route("/api/non_active", post(|| async { StatusCode::OK }))
+            .route("/api/auth/login", post(login))
+            .route(
+                "/api/auth/register",
+                post(|| async { Json(ApiResponse::success(1i32)) }),
+            )
+            .route("/api/auth/logout", get(|| async { StatusCode::OK }))
+            .route("/api/dyno", get(list).post(upload))
+            .route("/api/dyno/uploads", post(upload_start))
+            .route("/api/dyno/uploads/:id", put(upload_chunk))
+            .route("/api/dyno/uploads/:id/complete", post(upload_complete))
+            .route("/files/*path", get(file))
+            .layer(middleware::from_fn_with_state(state.clone(), inject))
+            .with_state(state.clone());
+
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0)))
+            .serve(app.into_make_service());
+        let addr = server.local_addr();
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        }));
+        Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        }
+    }
+
+    #[inline]
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// every request to `path` is responded with `status`.
+    pub fn fail(&self, path: &str, status: StatusCode) {
+        self.fail_times(path, status, usize::MAX);
+    }
+
+    /// the next `times` requests to `path` are responded with `status`.
+    pub fn fail_times(&self, path: &str, status: StatusCode, times: usize) {
+        self.state
+            .failures
+            .lock()
+            .insert(path.to_owned(), (status, times));
+    }
+
+    /// answer as the base server: the list of all runs and the single multipart upload only.
+    pub fn set_base(&self) {
+        self.state.base.store(true, Ordering::Relaxed);
+    }
+
+    /// delay every request.
+    pub fn set_latency(&self, latency: Duration) {
+        *self.state.latency.lock() = latency;
+    }
+
+    /// serve `data` in `GET {path}`, `path` must start with `/files/`, `Range: bytes=N-`
+    /// is supported.
+    pub fn set_file(&self, path: &str, data: Vec<u8>) {
+        self.state.files.lock().insert(path.to_owned(), data);
+    }
+
+    /// `path` is requested at least once.
+    pub fn requested(&self, path: &str) -> bool {
+        self.state.requests.lock().iter().any(|p| p == path)
+    }
+
+    /// multipart fields (name, data) of every completed upload, the chunks of a chunked
+    /// upload are in `data`.
+    pub fn uploads(&self) -> Vec<HashMap<String, Vec<u8>>> {
+        self.state.uploads.lock().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn inject<B>(
+    State(state): State<Arc<MockState>>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    state.requests.lock().push(req.uri().path().to_owned());
+    let latency = *state.latency.lock();
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    let failure = {
+        let mut failures = state.failures.lock();
+        match failures.get_mut(req.uri().path()) {
+            Some((status, times)) if *times > 0 => {
+                *times -= 1;
+                Some(*status)
+            }
+            _ => None,
+        }
+    };
+    match failure {
+        Some(status) => status.into_response(),
+        None => next.run(req).await,
+    }
+}
+
+fn is_authorized(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value == format!("Bearer {MOCK_TOKEN}"))
+}
+
+/// the base server answers without the list of the features.
+async fn health(State(state): State<Arc<MockState>>) -> Response {
+    if state.base.load(Ordering::Relaxed) {
+        return StatusCode::OK.into_response();
+    }
+    Json(serde_json::json!({ "features": ["chunked_upload", "paged_query"] })).into_response()
+}
+
+async fn login() -> Json<ApiResponse<TokenDetails>> {
+    Json(ApiResponse::success(TokenDetails {
+        token: Some(MOCK_TOKEN.to_owned()),
+        ..Default::default()
+    }))
+}
+
+async fn list(State(state): State<Arc<MockState>>, req: Request<Body>) -> Response {
+    if !is_authorized(req.headers()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    // the base server ignores the query.
+    if state.base.load(Ordering::Relaxed) {
+        return Json(ApiResponse::success(serde_json::json!([]))).into_response();
+    }
+    let page = serde_json::json!({ "items": [], "page": 1, "per_page": 50, "total": 0 });
+    Json(ApiResponse::success(page)).into_response()
+}
+
+#[derive(serde::Deserialize)]
+#[serde(crate = "serde")]
+struct UploadStart {
+    checksum: String,
+}
+
+/// session id is the checksum, so a new session of the same data continues the old one.
+async fn upload_start(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    Json(start): Json<UploadStart>,
+) -> Response {
+    if state.base.load(Ordering::Relaxed) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let mut sessions = state.sessions.lock();
+    let offset = sessions.entry(start.checksum.clone()).or_default().len();
+    let session = serde_json::json!({ "id": start.checksum, "offset": offset });
+    Json(ApiResponse::success(session)).into_response()
+}
+
+/// start of `Content-Range: bytes start-end/size`.
+fn range_start(headers: &HeaderMap) -> Option<usize> {
+    headers
+        .get(header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn upload_chunk(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let mut sessions = state.sessions.lock();
+    let data = match sessions.get_mut(&id) {
+        Some(data) => data,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    // chunk already received, or a gap; the client continues from the returned offset.
+    if range_start(&headers) == Some(data.len()) {
+        data.extend_from_slice(&body);
+    }
+    Json(ApiResponse::success(data.len() as u64)).into_response()
+}
+
+async fn read_fields(mut multipart: Multipart) -> Result<HashMap<String, Vec<u8>>, Response> {
+    let mut fields = HashMap::new();
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().unwrap_or_default().to_owned();
+        match field.bytes().await {
+            Ok(data) => fields.insert(name, data.to_vec()),
+            Err(err) => return Err((StatusCode::BAD_REQUEST, err.to_string()).into_response()),
+        };
+    }
+    Ok(fields)
+}
+
+/// single multipart upload of the base server, the data is in `data`.
+async fn upload(
+    State(state): State<Arc<MockState>>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let fields = match read_fields(multipart).await {
+        Ok(fields) => fields,
+        Err(resp) => return resp,
+    };
+    let mut uploads = state.uploads.lock();
+    uploads.push(fields);
+    Json(ApiResponse::success(uploads.len() as i32)).into_response()
+}
+
+async fn upload_complete(
+    State(state): State<Arc<MockState>>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    multipart: Multipart,
+) -> Response {
+    if !is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let mut fields = match read_fields(multipart).await {
+        Ok(fields) => fields,
+        Err(resp) => return resp,
+    };
+    match state.sessions.lock().remove(&id) {
+        Some(data) => fields.insert("data".to_owned(), data),
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let mut uploads = state.uploads.lock();
+    uploads.push(fields);
+    Json(ApiResponse::success(uploads.len() as i32)).into_response()
+}
+
+async fn file(State(state): State<Arc<MockState>>, req: Request<Body>) -> Response {
+    if !is_authorized(req.headers()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let files = state.files.lock();
+    let data = match files.get(req.uri().path()) {
+        Some(data) => data,
+        None => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("bytes="))
+        .and_then(|value| value.trim_end_matches('-').parse::<usize>().ok());
+    match range {
+        Some(start) if start >= data.len() => StatusCode::RANGE_NOT_SATISFIABLE.into_response(),
+        Some(start) => (
+            StatusCode::PARTIAL_CONTENT,
+            Bytes::from(data[start..].to_vec()),
+        )
+            .into_response(),
+        None => Bytes::from(data.clone()).into_response(),
+    }
+}
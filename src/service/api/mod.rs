@@ -1,80 +1,199 @@
 mod dyno;
+mod features;
+#[cfg(test)]
+mod mock;
+mod query;
+mod review;
+mod session;
+mod transfer;
 mod user;
 
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
-};
+#[cfg(test)]
+mod tests;
+
+pub use query::{DynoPage, DynoQuery, DynoSort, DynoTestEntry};
+pub use review::{CurrentUser, ReviewAction, ReviewComment, ReviewEvent, ReviewRequest};
+pub use transfer::{TransferKind, TransferProgress, Transfers};
+
+use std::{path::PathBuf, time::Duration};
 
+use crate::{config::ServerProfile, formats::markers::Markers, toast_error, tr, AsyncMsg};
 use dyno_core::{
+    asyncify,
     chrono::NaiveDateTime,
     crossbeam_channel::Sender,
-    crypto::TokenDetails,
-    dynotests::DynoTestDataInfo,
     ignore_err,
     reqwest::{
         header::{HeaderMap, HeaderValue},
-        multipart, Client, Response,
+        Certificate, Client, Response, StatusCode,
     },
     tokio,
     users::{UserLogin, UserRegistration},
-    BufferData, DynoConfig, DynoErr,
+    DynoConfig, DynoErr, DynoResult,
 };
-use eframe::epaint::mutex::Mutex;
+use features::{Feature, Features};
+use session::Session;
+use transfer::Progress;
 
-use crate::{toast_error, AsyncMsg};
+use super::sync::{SyncEvent, SyncTask};
 
 static APP_USER_AGENT: &str = concat!("Dyno/Desktop-", env!("CARGO_PKG_VERSION"),);
 
+/// request that need a login, re-issued after login again when the server reject the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiRequest {
+    GetDyno(DynoQuery),
+    LoadDynoFile {
+        url: String,
+        checksum: String,
+        /// opened as comparison run with this name.
+        compare: Option<String>,
+    },
+    /// upload of outbox entry id.
+    Upload(String),
+    /// sync of the local runs with server, see [`crate::service::SyncService`].
+    Sync,
+    /// page of the runs waiting for review.
+    GetReviews(u32),
+    /// comments of the run id.
+    GetComments(i64),
+    Review(ReviewRequest),
+}
+
+/// `Response::error_for_status`, but `401 Unauthorized` is sent as
+/// [`AsyncMsg::OnApiUnauthorized`] with the `request` to retry after login.
+pub(super) fn error_for_status(resp: Response, request: ApiRequest) -> Result<Response, AsyncMsg> {
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        return Err(AsyncMsg::OnApiUnauthorized(request));
+    }
+    resp.error_for_status().map_err(AsyncMsg::error)
+}
+
 #[derive(Clone)]
 pub struct ApiService {
     pub url: String,
+    /// name of the [`ServerProfile`] this service is connected to.
+    pub profile: String,
     pub client: Client,
-    logined: Arc<AtomicBool>,
-    token_session: Arc<Mutex<Option<TokenDetails>>>,
+    session: Session,
+    /// optional routes of the server, see [`features`].
+    features: Features,
 }
 
 impl ApiService {
-    pub fn new() -> Option<Self> {
-        let url = std::env::var("DYNO_SERVER_URL").unwrap_or_else(|err| {
-            dyno_core::log::error!(
-                "Failed to Get DYNO_SERVER_URL from EnvVar, defaulting to [localhost:8000] - {err}"
-            );
-            "http://127.0.0.1:3000".to_owned()
-        });
-        let client = match Client::builder()
+    pub fn new(profile: &ServerProfile) -> Option<Self> {
+        match Self::build_client(profile) {
+            Ok(client) => Some(Self {
+                url: profile.base_url.trim_end_matches('/').to_owned(),
+                profile: profile.name.clone(),
+                client,
+                session: Session::default(),
+                features: Features::default(),
+            }),
+            Err(err) => {
+                toast_error!(tr!("Failed to create Api Client - {err}", err = err));
+                None
+            }
+        }
+    }
+
+    fn build_client(profile: &ServerProfile) -> Result<Client, DynoErr> {
+        let mut builder = Client::builder()
             .default_headers({
                 let mut headers = HeaderMap::new();
                 headers.insert("AppDyno", HeaderValue::from_static("Desktop"));
                 headers
             })
             .user_agent(APP_USER_AGENT)
-            .build()
-            .map_err(DynoErr::service_error)
-        {
-            Ok(ok) => ok,
-            Err(err) => {
-                toast_error!("Failed to create Api Client - {err}");
-                return None;
-            }
+            .timeout(Duration::from_secs(profile.timeout.max(1)))
+            .danger_accept_invalid_certs(profile.accept_invalid_certs);
+        if let Some(path) = &profile.ca_certificate {
+            let pem = std::fs::read(path)?;
+            let cert = Certificate::from_pem(&pem).map_err(DynoErr::service_error)?;
+            builder = builder.add_root_certificate(cert);
+        }
+        builder.build().map_err(DynoErr::service_error)
+    }
+
+    /// restore the login session saved in `file` (see [`ServerProfile::session_file`]),
+    /// returns `true` if a valid session is restored.
+    #[inline]
+    pub fn restore_session(&mut self, file: PathBuf) -> bool {
+        self.session.restore(file)
+    }
+
+    /// detect the optional routes of the server in background (see [`features`]), then fetch
+    /// the logged in user of a restored session.
+    pub fn detect_features(&self) {
+        let api = self.clone();
+        tokio::spawn(async move { api.update_features().await });
+    }
+
+    async fn update_features(&self) {
+        features::detect(self.api_url("/health"), &self.client, &self.features).await;
+        self.fetch_user();
+    }
+
+    /// fetch the logged in user (role) of the session in background, only the server with
+    /// the review has the user route.
+    fn fetch_user(&self) {
+        if !self.features.is_supported(Feature::Review) {
+            return;
+        }
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => return,
         };
-        Some(Self {
-            url,
-            client,
-            logined: Arc::new(AtomicBool::new(false)),
-            token_session: Default::default(),
-        })
+        let client = self.client.clone();
+        let session = self.session.clone();
+        let features = self.features.clone();
+        let url = self.api_url("/users/me");
+        tokio::spawn(async move {
+            match review::get_me(url, client, token, features).await {
+                Ok(user) => session.set_user(user),
+                Err(err) => dyno_core::log::warn!("Failed to get the logged in user - {err:?}"),
+            }
+        });
+    }
+
+    /// the logged in user, `None` if not login or not fetched yet.
+    #[inline]
+    pub fn user(&self) -> Option<CurrentUser> {
+        self.session.user()
+    }
+
+    /// the logged in user is an instructor and the server has the review routes.
+    #[inline]
+    pub fn is_reviewer(&self) -> bool {
+        self.has_review() && self.session.user().map_or(false, |user| user.is_reviewer())
+    }
+
+    /// the server listed the review routes, see [`features`].
+    #[inline]
+    pub fn has_review(&self) -> bool {
+        self.features.is_supported(Feature::Review)
+    }
+
+    /// the server listed the paged query, with the peak values of the runs.
+    #[inline]
+    pub fn has_paged_query(&self) -> bool {
+        self.features.is_supported(Feature::PagedQuery)
     }
 
     #[inline]
     pub fn is_logined(&self) -> bool {
-        self.logined.load(Ordering::Relaxed)
+        self.session.is_logined()
+    }
+
+    /// forget the session rejected by the server, the user need to login again.
+    #[inline]
+    pub fn clear_session(&self) {
+        self.session.clear()
     }
 
     #[inline]
     fn get_token(&self) -> Option<String> {
-        let lock = self.token_session.lock();
-        lock.as_ref().and_then(|tok| tok.token.clone())
+        self.session.token()
     }
 
     fn api_url(&self, url: impl AsRef<str>) -> String {
@@ -117,7 +236,7 @@ impl ApiService {
                 .and_then(Response::error_for_status)
                 .map_err(DynoErr::service_error)
             {
-                Ok(_resp) => tx.send(AsyncMsg::message("Success Connecting to API Server!")),
+                Ok(_resp) => tx.send(AsyncMsg::message(tr!("Success Connecting to API Server!"))),
                 Err(err) => tx.send(AsyncMsg::error(err)),
             }
         };
@@ -135,7 +254,9 @@ impl ApiService {
                 .and_then(Response::error_for_status)
                 .map_err(DynoErr::service_error)
             {
-                Ok(_resp) => tx.send(AsyncMsg::message("Success Disconnecting to API Server!")),
+                Ok(_resp) => tx.send(AsyncMsg::message(tr!(
+                    "Success Disconnecting to API Server!"
+                ))),
                 Err(err) => tx.send(AsyncMsg::error(err)),
             }
         };
@@ -144,15 +265,23 @@ impl ApiService {
 
     pub fn login(&self, login: UserLogin, tx: Sender<AsyncMsg>) {
         let client = self.client.clone();
-        let logined = self.logined.clone();
-        let token_session = self.token_session.clone();
+        let session = self.session.clone();
+        let features = self.features.clone();
         let url = self.api_url("/auth/login");
+        let me_url = self.api_url("/users/me");
         tokio::spawn(async move {
-            match user::user_login(url, client, login).await {
+            match user::user_login(url, client.clone(), login).await {
                 Ok(resp) => {
-                    logined.store(true, Ordering::Relaxed);
-                    let mut token_lock = token_session.lock();
-                    *token_lock = Some(resp.payload);
+                    let token = resp.payload.token.clone().unwrap_or_default();
+                    session.set(resp.payload);
+                    if features.is_supported(Feature::Review) {
+                        match review::get_me(me_url, client, token, features).await {
+                            Ok(user) => session.set_user(user),
+                            Err(err) => {
+                                dyno_core::log::warn!("Failed to get the logged in user - {err:?}")
+                            }
+                        }
+                    }
                     ignore_err!(tx.send(AsyncMsg::OnApiLogin));
                 }
                 Err(err) => ignore_err!(tx.send(err)),
@@ -160,6 +289,56 @@ impl ApiService {
         });
     }
 
+    /// refresh the session token before it expired, called every frame, only
+    /// request to the server when the token is near expiry. Without the refresh route
+    /// in the server, the session is kept until the token expired.
+    pub fn refresh_session(&self, tx: &Sender<AsyncMsg>) {
+        if !self.session.should_refresh() {
+            return;
+        }
+        if !self.features.is_supported(Feature::Refresh) {
+            if self.session.is_expired() {
+                self.session.clear();
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
+                    "Login session is expired, please Login again."
+                )))));
+            }
+            return;
+        }
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => return,
+        };
+        self.session.set_refreshing(true);
+        let tx = tx.clone();
+        let client = self.client.clone();
+        let session = self.session.clone();
+        let features = self.features.clone();
+        let url = self.api_url("/auth/refresh");
+        tokio::spawn(async move {
+            match user::user_refresh(url, client, token).await {
+                Ok(Some(resp)) => {
+                    features.set(Feature::Refresh, true);
+                    session.set(resp.payload);
+                }
+                Ok(None) => {
+                    dyno_core::log::info!("Server has no session refresh, keeping the session");
+                    features.set(Feature::Refresh, false);
+                }
+                Err(err) => {
+                    dyno_core::log::warn!("Failed to refresh session - {err:?}");
+                    if session.is_expired() {
+                        session.clear();
+                        ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
+                            "Login session is expired, please Login again."
+                        )))));
+                    }
+                }
+            }
+            session.set_refreshing(false);
+        });
+    }
+
     pub fn register(&self, register: UserRegistration, tx: Sender<AsyncMsg>) {
         let client = self.client.clone();
         let url = self.api_url("/auth/register");
@@ -176,25 +355,20 @@ impl ApiService {
         let token = match self.get_token() {
             Some(tok) => tok,
             None => {
-                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
                     "You are not Login, please Login first."
-                ))));
+                )))));
                 return;
             }
         };
 
-        let logined = self.logined.clone();
-        let token_session = self.token_session.clone();
+        let session = self.session.clone();
         let client = self.client.clone();
         let url = self.api_url("/auth/logout");
         tokio::spawn(async move {
             match user::user_logout(url, client, token).await {
                 Ok(ok) => {
-                    logined.store(false, Ordering::Relaxed);
-                    {
-                        let mut token_lock = token_session.lock();
-                        *token_lock = None;
-                    }
+                    session.clear();
                     ignore_err!(tx.send(ok))
                 }
                 Err(err) => ignore_err!(tx.send(err)),
@@ -204,92 +378,352 @@ impl ApiService {
 }
 
 impl ApiService {
+    /// upload the outbox entry `id` from its data `path`, the result is sent as
+    /// [`AsyncMsg::OnUploaded`] or [`AsyncMsg::OnUploadFailed`].
     pub fn save_dyno(
         &self,
-        data: BufferData,
+        id: String,
+        path: PathBuf,
         config: DynoConfig,
         start: NaiveDateTime,
         stop: NaiveDateTime,
         tx: Sender<AsyncMsg>,
+    ) {
+        self.upload_file(id, path, config, (start, stop), false, tx)
+    }
+
+    /// retry the upload of outbox entry `id` from `path`, only if `check_health` of server
+    /// success. The chunks already in server are not sent again.
+    pub fn retry_save_dyno(
+        &self,
+        id: String,
+        path: PathBuf,
+        config: DynoConfig,
+        start: NaiveDateTime,
+        stop: NaiveDateTime,
+        tx: Sender<AsyncMsg>,
+    ) {
+        self.upload_file(id, path, config, (start, stop), true, tx)
+    }
+
+    fn upload_file(
+        &self,
+        id: String,
+        path: PathBuf,
+        config: DynoConfig,
+        times: (NaiveDateTime, NaiveDateTime),
+        check_health: bool,
+        tx: Sender<AsyncMsg>,
     ) {
         let token = match self.get_token() {
             Some(tok) => tok,
             None => {
-                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(
-                    "You are not Login, please Login first."
-                ))));
+                let err = DynoErr::api_error(tr!("You are not Login, please Login first."));
+                ignore_err!(tx.send(AsyncMsg::upload_failed(id, err)));
                 return;
             }
         };
 
         let client = self.client.clone();
+        let features = self.features.clone();
+        let health_url = self.api_url("/health");
         let url = self.api_url("/dyno");
 
         tokio::spawn(async move {
-            let (file_part, checksum_hex) = match dyno::get_data_part(data).await {
-                Ok(ok) => ok,
-                Err(err) => {
-                    ignore_err!(tx.send(AsyncMsg::error(err)));
-                    return;
+            let request = ApiRequest::Upload(id.clone());
+            let progress = Progress::new(&id, TransferKind::Upload, tx.clone());
+            let result = async {
+                if check_health {
+                    client
+                        .get(health_url)
+                        .send()
+                        .await
+                        .and_then(Response::error_for_status)
+                        .map_err(AsyncMsg::error)?;
+                }
+                let (compressed, markers) =
+                    asyncify!(move || read_run(&path)).map_err(AsyncMsg::error)?;
+                dyno::upload_compressed(
+                    url,
+                    client,
+                    token,
+                    features,
+                    compressed,
+                    config,
+                    markers,
+                    Some(times),
+                    request,
+                    progress,
+                )
+                .await
+            };
+            ignore_err!(tx.send(upload_result(id, result.await)));
+        });
+    }
+
+    /// get a page of the runs in server, filtered and sorted by `query`.
+    pub fn get_dyno(&self, query: DynoQuery, tx: Sender<AsyncMsg>) {
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => {
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
+                    "You are not Login, please Login first."
+                )))));
+                return;
+            }
+        };
+        let client = self.client.clone();
+        let features = self.features.clone();
+        let url = self.api_url("/dyno");
+        tokio::spawn(async move {
+            let result = dyno::get(url, client, token, features, query).await;
+            ignore_err!(tx.send(result));
+        });
+    }
+
+    /// send the request again, after login again.
+    pub fn retry_request(&self, request: ApiRequest, tx: Sender<AsyncMsg>) {
+        match request {
+            ApiRequest::GetDyno(query) => self.get_dyno(query, tx),
+            ApiRequest::LoadDynoFile {
+                url,
+                checksum,
+                compare,
+            } => self.load_dyno_file(url, checksum, compare, tx),
+            ApiRequest::GetReviews(page) => self.get_reviews(page, tx),
+            ApiRequest::GetComments(id) => self.get_comments(id, tx),
+            ApiRequest::Review(request) => self.review(request, tx),
+            // uploads are retried from the outbox, and sync is started again by `DynoControl`.
+            ApiRequest::Upload(_) | ApiRequest::Sync => {}
+        }
+    }
+
+    /// download the run file, opened as the buffer or as comparison run named `compare`.
+    pub fn load_dyno_file(
+        &self,
+        url: String,
+        checksum: String,
+        compare: Option<String>,
+        tx: Sender<AsyncMsg>,
+    ) {
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => {
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
+                    "You are not Login, please Login first."
+                )))));
+                return;
+            }
+        };
+        let client = self.client.clone();
+        let data_url = self.data_url(&url);
+        tokio::spawn(async move {
+            let progress = Progress::new(&checksum, TransferKind::Download, tx.clone());
+            let result =
+                dyno::load_file(data_url, client, token, checksum, url, compare, progress).await;
+            ignore_err!(tx.send(result));
+        });
+    }
+}
+
+impl ApiService {
+    /// list all runs in server for the sync, sent as [`SyncEvent::Remote`].
+    pub fn sync_list(&self, tx: Sender<AsyncMsg>) {
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => {
+                let err = DynoErr::api_error(tr!("You are not Login, please Login first."));
+                ignore_err!(tx.send(AsyncMsg::OnSync(SyncEvent::ScanFailed(err))));
+                return;
+            }
+        };
+        let client = self.client.clone();
+        let features = self.features.clone();
+        let url = self.api_url("/dyno");
+        tokio::spawn(async move {
+            let msg = match dyno::get_all(url, client, token, features).await {
+                Ok(entries) => {
+                    AsyncMsg::OnSync(SyncEvent::Remote(entries.iter().map(From::from).collect()))
                 }
+                Err(AsyncMsg::OnError(err)) => AsyncMsg::OnSync(SyncEvent::ScanFailed(err)),
+                Err(msg) => msg,
             };
-            let config_data = DynoTestDataInfo {
-                checksum_hex,
-                config,
-                start,
-                stop,
+            ignore_err!(tx.send(msg));
+        });
+    }
+
+    /// upload or download the run of sync `task`.
+    pub fn sync_transfer(&self, task: SyncTask, tx: Sender<AsyncMsg>) {
+        let checksum = match &task {
+            SyncTask::Upload(local) => local.checksum.clone(),
+            SyncTask::Download(remote, _) => remote.checksum.clone(),
+        };
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => {
+                let err = DynoErr::api_error(tr!("You are not Login, please Login first."));
+                ignore_err!(tx.send(AsyncMsg::OnSync(SyncEvent::Failed { checksum, err })));
+                return;
+            }
+        };
+        let client = self.client.clone();
+        let features = self.features.clone();
+        let upload_url = self.api_url("/dyno");
+        let task_url = match &task {
+            SyncTask::Download(remote, _) => self.data_url(&remote.data_url),
+            SyncTask::Upload(_) => String::new(),
+        };
+        tokio::spawn(async move {
+            let kind = match &task {
+                SyncTask::Upload(_) => TransferKind::Upload,
+                SyncTask::Download(..) => TransferKind::Download,
             };
-            let info_part = match dyno::get_info_part(config_data).await {
-                Ok(ok) => ok,
-                Err(err) => {
-                    ignore_err!(tx.send(AsyncMsg::error(err)));
-                    return;
+            let progress = Progress::new(&checksum, kind, tx.clone());
+            let result = match task {
+                SyncTask::Upload(local) => {
+                    let path = local.path.clone();
+                    match asyncify!(move || read_sync_run(&path)) {
+                        Ok((compressed, markers, config)) => dyno::upload_compressed(
+                            upload_url,
+                            client,
+                            token,
+                            features,
+                            compressed,
+                            config,
+                            markers,
+                            None,
+                            ApiRequest::Sync,
+                            progress,
+                        )
+                        .await
+                        .map(|id| SyncEvent::Uploaded {
+                            checksum: checksum.clone(),
+                            id: i64::from(id),
+                        }),
+                        Err(err) => Err(AsyncMsg::error(err)),
+                    }
                 }
+                SyncTask::Download(remote, path) => {
+                    match transfer::download(
+                        task_url,
+                        client,
+                        token,
+                        &checksum,
+                        ApiRequest::Sync,
+                        &progress,
+                    )
+                    .await
+                    {
+                        Ok(data) => {
+                            let save_path = path.clone();
+                            asyncify!(move || std::fs::write(save_path, data).map_err(DynoErr::from))
+                                .map(|_| SyncEvent::Downloaded {
+                                    checksum: remote.checksum,
+                                    path,
+                                })
+                                .map_err(AsyncMsg::error)
+                        }
+                        Err(err) => Err(err),
+                    }
+                }
+            };
+            let msg = match result {
+                Ok(event) => AsyncMsg::OnSync(event),
+                Err(AsyncMsg::OnError(err)) => {
+                    AsyncMsg::OnSync(SyncEvent::Failed { checksum, err })
+                }
+                Err(msg) => msg,
             };
-            let multiparts = multipart::Form::new()
-                .part("data", file_part)
-                .part("info", info_part);
+            ignore_err!(tx.send(msg));
+        });
+    }
+}
 
-            match dyno::save(url, client, token, multiparts).await {
-                Ok(ok) => ignore_err!(tx.send(ok)),
-                Err(err) => ignore_err!(tx.send(err)),
+impl ApiService {
+    /// page of the runs waiting for review, only for reviewer.
+    pub fn get_reviews(&self, page: u32, tx: Sender<AsyncMsg>) {
+        let token = match self.get_token() {
+            Some(tok) => tok,
+            None => {
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
+                    "You are not Login, please Login first."
+                )))));
+                return;
             }
+        };
+        let client = self.client.clone();
+        let features = self.features.clone();
+        let url = self.api_url("/dyno/reviews");
+        tokio::spawn(async move {
+            let result = review::get_pending(url, client, token, features, page).await;
+            ignore_err!(tx.send(result.map_or_else(|err| err, AsyncMsg::OnReview)));
         });
     }
 
-    pub fn get_dyno(&self, tx: Sender<AsyncMsg>) {
+    pub fn get_comments(&self, id: i64, tx: Sender<AsyncMsg>) {
         let token = match self.get_token() {
             Some(tok) => tok,
             None => {
-                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
                     "You are not Login, please Login first."
-                ))));
+                )))));
                 return;
             }
         };
         let client = self.client.clone();
-        let url = self.api_url("/dyno?all=true");
+        let features = self.features.clone();
+        let url = self.api_url(format!("/dyno/{id}/comments"));
         tokio::spawn(async move {
-            let result = dyno::get(url, client, token).await;
-            ignore_err!(tx.send(result));
+            let result = review::get_comments(url, client, token, features, id).await;
+            ignore_err!(tx.send(result.map_or_else(|err| err, AsyncMsg::OnReview)));
         });
     }
 
-    pub fn load_dyno_file(&self, url: String, checksum: String, tx: Sender<AsyncMsg>) {
+    /// comment, verify or reject the run.
+    pub fn review(&self, request: ReviewRequest, tx: Sender<AsyncMsg>) {
         let token = match self.get_token() {
             Some(tok) => tok,
             None => {
-                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(
+                ignore_err!(tx.send(AsyncMsg::error(DynoErr::api_error(tr!(
                     "You are not Login, please Login first."
-                ))));
+                )))));
                 return;
             }
         };
         let client = self.client.clone();
-        let url = self.data_url(url);
+        let features = self.features.clone();
+        let url = self.api_url("/dyno");
         tokio::spawn(async move {
-            let result = dyno::load_file(url, client, token, checksum).await;
-            ignore_err!(tx.send(result));
+            let result = review::send(url, client, token, features, request).await;
+            ignore_err!(tx.send(result.map_or_else(|err| err, AsyncMsg::OnReview)));
         });
     }
 }
+
+/// upload result of outbox entry `id` as message, keeping the unauthorized message.
+fn upload_result(id: String, result: Result<i32, AsyncMsg>) -> AsyncMsg {
+    match result {
+        Ok(server_id) => AsyncMsg::uploaded(id, server_id),
+        Err(AsyncMsg::OnError(err)) => AsyncMsg::upload_failed(id, err),
+        Err(msg) => msg,
+    }
+}
+
+/// compressed run file to upload, with the event markers saved next to it.
+fn read_run(path: &std::path::Path) -> DynoResult<(Vec<u8>, Markers)> {
+    let compressed = std::fs::read(path)?;
+    Ok((compressed, Markers::open_sidecar(path)))
+}
+
+/// [`read_run`] with the `DynoConfig` the run is recorded with, a run with unknown config is not
+/// uploaded, the current config may be different.
+fn read_sync_run(path: &std::path::Path) -> DynoResult<(Vec<u8>, Markers, DynoConfig)> {
+    let config = crate::formats::raw::open_config(path).ok_or_else(|| {
+        DynoErr::input_error(tr!(
+            "The config of `{path}` is unknown, the run is not uploaded",
+            path = path.display()
+        ))
+    })?;
+    let (compressed, markers) = read_run(path)?;
+    Ok((compressed, markers, config))
+}
@@ -0,0 +1,183 @@
+//! Paged query of the dyno runs in server, `GET /api/dyno` with the filters as query params.
+//! The base server only answers all runs (`?all=true`), the page is made from it with
+//! [`DynoQuery::page_of`].
+use dyno_core::{
+    chrono::NaiveDate,
+    dynotests::{DynoTest, DynoTestDataInfo},
+    serde,
+};
+
+use crate::formats::summary::RunSummary;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DynoSort {
+    Id,
+    Motor,
+    Owner,
+    Verified,
+    Updated,
+    #[default]
+    Created,
+}
+
+impl DynoSort {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Motor => "motor",
+            Self::Owner => "owner",
+            Self::Verified => "verified",
+            Self::Updated => "updated_at",
+            Self::Created => "created_at",
+        }
+    }
+}
+
+/// filters, sorting and page of the runs in server, every field is sent as query param.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynoQuery {
+    /// page number, start from 1.
+    pub page: u32,
+    pub per_page: u32,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// part of the motor name.
+    pub motor: String,
+    pub verified: Option<bool>,
+    /// username or email of the owner.
+    pub owner: String,
+    pub sort: DynoSort,
+    pub descending: bool,
+}
+
+impl Default for DynoQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            per_page: 50,
+            from: None,
+            to: None,
+            motor: String::new(),
+            verified: None,
+            owner: String::new(),
+            sort: DynoSort::default(),
+            descending: true,
+        }
+    }
+}
+
+impl DynoQuery {
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("page", self.page.max(1).to_string()),
+            ("per_page", self.per_page.to_string()),
+            ("sort", self.sort.as_str().to_owned()),
+            (
+                "order",
+                if self.descending { "desc" } else { "asc" }.to_owned(),
+            ),
+        ];
+        if let Some(from) = self.from {
+            params.push(("from", from.format("%Y-%m-%d").to_string()));
+        }
+        if let Some(to) = self.to {
+            params.push(("to", to.format("%Y-%m-%d").to_string()));
+        }
+        if !self.motor.trim().is_empty() {
+            params.push(("motor", self.motor.trim().to_owned()));
+        }
+        if let Some(verified) = self.verified {
+            params.push(("verified", verified.to_string()));
+        }
+        if !self.owner.trim().is_empty() {
+            params.push(("owner", self.owner.trim().to_owned()));
+        }
+        params
+    }
+
+    /// page of all `runs` of the base server. The list has no motor and owner, so those
+    /// filters and sorting are not applied.
+    pub fn page_of(&self, runs: Vec<DynoTest>) -> DynoPage {
+        let mut runs = runs
+            .into_iter()
+            .filter(|run| self.from.map_or(true, |from| run.created_at.date() >= from))
+            .filter(|run| self.to.map_or(true, |to| run.created_at.date() <= to))
+            .filter(|run| {
+                self.verified
+                    .map_or(true, |verified| run.verified == verified)
+            })
+            .collect::<Vec<_>>();
+        runs.sort_by(|a, b| match self.sort {
+            DynoSort::Id => a.id.cmp(&b.id),
+            DynoSort::Verified => a.verified.cmp(&b.verified),
+            DynoSort::Updated => a.updated_at.cmp(&b.updated_at),
+            DynoSort::Motor | DynoSort::Owner | DynoSort::Created => {
+                a.created_at.cmp(&b.created_at)
+            }
+        });
+        if self.descending {
+            runs.reverse();
+        }
+        let (page, per_page) = (self.page.max(1), self.per_page.max(1));
+        DynoPage {
+            total: runs.len() as u64,
+            items: runs
+                .into_iter()
+                .skip((page - 1) as usize * per_page as usize)
+                .take(per_page as usize)
+                .map(|dyno| DynoTestEntry {
+                    dyno,
+                    owner: None,
+                    info: None,
+                    summary: None,
+                })
+                .collect(),
+            page,
+            per_page,
+        }
+    }
+}
+
+/// run in server with the metadata for the run browser.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct DynoTestEntry {
+    #[serde(flatten)]
+    pub dyno: DynoTest,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub info: Option<DynoTestDataInfo>,
+    /// peak values, sent with the upload as `summary` part.
+    #[serde(default)]
+    pub summary: Option<RunSummary>,
+}
+
+impl DynoTestEntry {
+    pub fn motor_name(&self) -> String {
+        self.info
+            .as_ref()
+            .map(|info| info.config.motor_type.to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, crate = "serde")]
+pub struct DynoPage {
+    pub items: Vec<DynoTestEntry>,
+    pub page: u32,
+    pub per_page: u32,
+    /// number of runs matching the filters, in all pages.
+    pub total: u64,
+}
+
+impl DynoPage {
+    pub fn pages(&self) -> u32 {
+        if self.per_page == 0 {
+            return 1;
+        }
+        let per_page = self.per_page as u64;
+        (((self.total + per_page - 1) / per_page) as u32).max(1)
+    }
+}
@@ -0,0 +1,188 @@
+//! Review of the runs by instructors (`Roles::Admin`), students upload the runs and
+//! the instructors verify or reject them with comments.
+//!
+//! The review routes are not in the base server, they are only used when the server listed
+//! [`Feature::Review`], and the review is hidden without it.
+use dyno_core::{
+    chrono::NaiveDateTime,
+    reqwest::{Client, IntoUrl, Response},
+    role::Roles,
+    serde, ApiResponse, DynoErr,
+};
+
+use super::{
+    error_for_status,
+    features::{self, Feature, Features},
+    ApiRequest, DynoPage,
+};
+use crate::{tr, AsyncMsg};
+
+/// the logged in user, `GET /api/users/me`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct CurrentUser {
+    pub id: i64,
+    pub nim: String,
+    #[serde(default)]
+    pub email: String,
+    pub role: Roles,
+}
+
+impl CurrentUser {
+    /// only instructors can verify or reject the runs.
+    #[inline]
+    pub fn is_reviewer(&self) -> bool {
+        self.role == Roles::Admin
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAction {
+    Comment,
+    Verify,
+    Reject,
+}
+
+impl ReviewAction {
+    const fn path(self) -> &'static str {
+        match self {
+            Self::Comment => "comments",
+            Self::Verify => "verify",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewRequest {
+    /// id of the run in server.
+    pub id: i64,
+    pub action: ReviewAction,
+    pub comment: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(crate = "serde")]
+pub struct ReviewComment {
+    /// nim or name of the commenter.
+    pub user: String,
+    pub comment: String,
+    /// `Some` if the comment is written when verifying or rejecting the run.
+    #[serde(default)]
+    pub verified: Option<bool>,
+    pub created_at: NaiveDateTime,
+}
+
+/// result of the review requests, sent as [`crate::AsyncMsg::OnReview`].
+#[derive(Debug, Clone)]
+pub enum ReviewEvent {
+    Pending(DynoPage),
+    Comments(i64, Vec<ReviewComment>),
+    Done(ReviewRequest),
+}
+
+#[derive(serde::Serialize)]
+#[serde(crate = "serde")]
+struct CommentBody<'a> {
+    comment: &'a str,
+}
+
+/// response of the review route, a missing route marks [`Feature::Review`] unsupported.
+fn check_route(
+    resp: Response,
+    features: &Features,
+    request: Option<ApiRequest>,
+) -> Result<Response, AsyncMsg> {
+    if features::is_missing(resp.status()) {
+        features.set(Feature::Review, false);
+        return Err(AsyncMsg::error(DynoErr::api_error(tr!(
+            "The server does not support the review of runs"
+        ))));
+    }
+    match request {
+        Some(request) => error_for_status(resp, request),
+        None => resp.error_for_status().map_err(AsyncMsg::error),
+    }
+}
+
+pub async fn get_me(
+    url: impl IntoUrl,
+    client: Client,
+    token: impl std::fmt::Display,
+    features: Features,
+) -> Result<CurrentUser, AsyncMsg> {
+    client
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)
+        .and_then(|resp| check_route(resp, &features, None))?
+        .json::<ApiResponse<CurrentUser>>()
+        .await
+        .map(|resp| resp.payload)
+        .map_err(AsyncMsg::error)
+}
+
+pub async fn get_pending(
+    url: impl IntoUrl,
+    client: Client,
+    token: impl std::fmt::Display,
+    features: Features,
+    page: u32,
+) -> Result<ReviewEvent, AsyncMsg> {
+    let page = client
+        .get(url)
+        .query(&[("page", page)])
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)
+        .and_then(|resp| check_route(resp, &features, Some(ApiRequest::GetReviews(page))))?
+        .json::<ApiResponse<DynoPage>>()
+        .await
+        .map_err(AsyncMsg::error)?;
+    Ok(ReviewEvent::Pending(page.payload))
+}
+
+pub async fn get_comments(
+    url: impl IntoUrl,
+    client: Client,
+    token: impl std::fmt::Display,
+    features: Features,
+    id: i64,
+) -> Result<ReviewEvent, AsyncMsg> {
+    client
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)
+        .and_then(|resp| check_route(resp, &features, Some(ApiRequest::GetComments(id))))?
+        .json::<ApiResponse<Vec<ReviewComment>>>()
+        .await
+        .map(|resp| ReviewEvent::Comments(id, resp.payload))
+        .map_err(AsyncMsg::error)
+}
+
+/// `POST /api/dyno/{id}/{comments|verify|reject}` with the comment.
+pub async fn send(
+    base_url: String,
+    client: Client,
+    token: impl std::fmt::Display,
+    features: Features,
+    request: ReviewRequest,
+) -> Result<ReviewEvent, AsyncMsg> {
+    let url = format!("{base_url}/{}/{}", request.id, request.action.path());
+    client
+        .post(url)
+        .json(&CommentBody {
+            comment: &request.comment,
+        })
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)
+        .and_then(|resp| check_route(resp, &features, Some(ApiRequest::Review(request.clone()))))?;
+    Ok(ReviewEvent::Done(request))
+}
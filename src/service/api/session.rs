@@ -0,0 +1,243 @@
+//! Login session (`TokenDetails`) persisted in the config dir, so the user does not
+//! need to login on every launch. The file is only readable by the owner, with the unix mode
+//! `0600` or without the inherited ACL of the folder on windows (`icacls`), and the session is
+//! not saved if the permissions can't be restricted.
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use base64::Engine as _;
+use dyno_core::{crypto::TokenDetails, DynoErr, DynoResult};
+use eframe::epaint::mutex::Mutex;
+
+use super::review::CurrentUser;
+
+/// seconds before the token expired to refresh the session.
+const REFRESH_BEFORE: i64 = 120;
+
+/// `exp` claim (unix timestamp in seconds) of the jwt `token`.
+pub fn token_expiry(token: &str) -> Option<i64> {
+    let payload = token.split('.').nth(1)?;
+    let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    serde_json::from_slice::<serde_json::Value>(&decoded)
+        .ok()?
+        .get("exp")?
+        .as_i64()
+}
+
+/// the token of session is still valid for at least `margin` seconds,
+/// token without `exp` claim is assumed valid until the server reject it.
+fn is_valid(details: &TokenDetails, margin: i64) -> bool {
+    match details.token.as_deref() {
+        Some(token) => token_expiry(token).map_or(true, |exp| {
+            exp - margin > dyno_core::chrono::Utc::now().timestamp()
+        }),
+        None => false,
+    }
+}
+
+/// shared login state of [`super::ApiService`], cloned into the async tasks.
+#[derive(Clone, Default)]
+pub(super) struct Session {
+    logined: Arc<AtomicBool>,
+    refreshing: Arc<AtomicBool>,
+    token: Arc<Mutex<Option<TokenDetails>>>,
+    /// the logged in user, fetched after login.
+    user: Arc<Mutex<Option<CurrentUser>>>,
+    file: Option<PathBuf>,
+}
+
+impl Session {
+    /// restore the saved session from `file`, and save the next session there.
+    pub fn restore(&mut self, file: PathBuf) -> bool {
+        let restored = match load(&file) {
+            Some(details) => {
+                *self.token.lock() = Some(details);
+                self.logined.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        };
+        self.file = Some(file);
+        restored
+    }
+
+    #[inline]
+    pub fn is_logined(&self) -> bool {
+        self.logined.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn token(&self) -> Option<String> {
+        self.token.lock().as_ref().and_then(|tok| tok.token.clone())
+    }
+
+    /// session need to be refreshed, and no refresh is running.
+    pub fn should_refresh(&self) -> bool {
+        self.is_logined()
+            && !self.refreshing.load(Ordering::Relaxed)
+            && self
+                .token
+                .lock()
+                .as_ref()
+                .map_or(false, |details| !is_valid(details, REFRESH_BEFORE))
+    }
+
+    #[inline]
+    pub fn set_refreshing(&self, refreshing: bool) {
+        self.refreshing.store(refreshing, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, details: TokenDetails) {
+        if let Some(file) = &self.file {
+            if let Err(err) = store(file, &details) {
+                dyno_core::log::error!("Failed to save session - {err}");
+                remove(file);
+            }
+        }
+        *self.token.lock() = Some(details);
+        self.logined.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn user(&self) -> Option<CurrentUser> {
+        self.user.lock().clone()
+    }
+
+    #[inline]
+    pub fn set_user(&self, user: CurrentUser) {
+        *self.user.lock() = Some(user);
+    }
+
+    pub fn clear(&self) {
+        self.logined.store(false, Ordering::Relaxed);
+        *self.token.lock() = None;
+        *self.user.lock() = None;
+        if let Some(file) = &self.file {
+            remove(file);
+        }
+    }
+
+    /// token is already expired, not only near expiry.
+    pub fn is_expired(&self) -> bool {
+        self.token
+            .lock()
+            .as_ref()
+            .map_or(true, |details| !is_valid(details, 0))
+    }
+}
+
+fn load(path: &Path) -> Option<TokenDetails> {
+    let data = fs::read(path).ok()?;
+    match serde_json::from_slice::<TokenDetails>(&data) {
+        Ok(details) if is_valid(&details, 0) => Some(details),
+        Ok(_) => {
+            remove(path);
+            None
+        }
+        Err(err) => {
+            dyno_core::log::warn!("Failed to read saved session - {err}");
+            None
+        }
+    }
+}
+
+fn store(path: &Path, details: &TokenDetails) -> DynoResult<()> {
+    let data = serde_json::to_vec(details).map_err(DynoErr::input_output_error)?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // the mode of `open` only applies to a new file, restrict the existing file before writing.
+    restrict(&file, path)?;
+    file.write_all(&data).map_err(From::from)
+}
+
+#[cfg(unix)]
+fn restrict(file: &fs::File, _path: &Path) -> DynoResult<()> {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+        .map_err(From::from)
+}
+
+/// remove the inherited permissions of the folder, and grant only the current user.
+#[cfg(windows)]
+fn restrict(_file: &fs::File, path: &Path) -> DynoResult<()> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let user = std::env::var("USERNAME").map_err(DynoErr::input_output_error)?;
+    let status = std::process::Command::new("icacls")
+        .arg(path)
+        .args(["/inheritance:r", "/grant:r"])
+        .arg(format!("{user}:F"))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DynoErr::input_output_error(format!(
+            "Failed to restrict the permissions of `{}` - {status}",
+            path.display()
+        )))
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn restrict(_file: &fs::File, _path: &Path) -> DynoResult<()> {
+    Ok(())
+}
+
+fn remove(path: &Path) {
+    if path.exists() {
+        if let Err(err) = fs::remove_file(path) {
+            dyno_core::log::error!("Failed to remove saved session - {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jwt_expiry_claim() {
+        // {"alg":"RS256","typ":"JWT"} . {"sub":"1","exp":1700000000} . signature
+        let token =
+            "eyJhbGciOiJSUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxIiwiZXhwIjoxNzAwMDAwMDAwfQ.c2ln";
+        assert_eq!(token_expiry(token), Some(1_700_000_000));
+        assert_eq!(token_expiry("not-a-token"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn store_restricts_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("dyno-session-{}", std::process::id()));
+        fs::write(&path, b"{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let details = TokenDetails {
+            token: Some("token".to_owned()),
+            ..Default::default()
+        };
+        store(&path, &details).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
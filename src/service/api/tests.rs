@@ -0,0 +1,328 @@
+use std::{path::PathBuf, time::Duration};
+
+use axum::http::StatusCode;
+use dyno_core::{
+    chrono::NaiveDateTime,
+    crossbeam_channel::{unbounded, Receiver},
+    crypto::{checksum_from_bytes, TokenDetails},
+    dynotests::DynoTestDataInfo,
+    tokio::runtime::{Builder, Runtime},
+    users::{UserLogin, UserRegistration},
+    BufferData, CompresedSaver as _, DynoConfig,
+};
+
+use super::mock::{MockServer, MOCK_TOKEN};
+use super::transfer::part_path;
+use super::{ApiRequest, ApiService, DynoQuery};
+use crate::{config::ServerProfile, formats::markers::Markers, AsyncMsg};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn runtime() -> Runtime {
+    Builder::new_multi_thread()
+        .worker_threads(2)
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+/// service connected to `server`, with the features of the server detected.
+fn service(rt: &Runtime, server: &MockServer, token: Option<&str>) -> ApiService {
+    let api = ApiService::new(&ServerProfile {
+        name: "mock".to_owned(),
+        base_url: server.url(),
+        timeout: 1,
+        ..Default::default()
+    })
+    .unwrap();
+    if let Some(token) = token {
+        api.session.set(TokenDetails {
+            token: Some(token.to_owned()),
+            ..Default::default()
+        });
+    }
+    rt.block_on(api.update_features());
+    api
+}
+
+/// next result of the request, the transfer progress is skipped.
+fn recv(rx: &Receiver<AsyncMsg>) -> AsyncMsg {
+    loop {
+        match rx
+            .recv_timeout(TIMEOUT)
+            .expect("no response from ApiService")
+        {
+            AsyncMsg::OnTransfer(_) => continue,
+            msg => return msg,
+        }
+    }
+}
+
+/// compressed `BufferData` written as outbox data file, returns the path and the bytes.
+fn data_file(name: &str) -> (PathBuf, Vec<u8>) {
+    let path = std::env::temp_dir().join(format!("dyno-mock-{name}-{}.dyno", std::process::id()));
+    BufferData::new().compress_to_path(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    (path, bytes)
+}
+
+#[test]
+fn login_and_register() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, None);
+    let (tx, rx) = unbounded();
+
+    api.register(UserRegistration::default(), tx.clone());
+    assert!(matches!(recv(&rx), AsyncMsg::OnApiRegister));
+
+    api.login(
+        UserLogin {
+            nim: "E41200000".to_owned(),
+            password: "password".to_owned(),
+        },
+        tx.clone(),
+    );
+    assert!(matches!(recv(&rx), AsyncMsg::OnApiLogin));
+    assert!(api.is_logined());
+    assert_eq!(api.get_token().as_deref(), Some(MOCK_TOKEN));
+
+    server.fail("/api/auth/login", StatusCode::INTERNAL_SERVER_ERROR);
+    api.login(
+        UserLogin {
+            nim: "E41200000".to_owned(),
+            password: "password".to_owned(),
+        },
+        tx,
+    );
+    assert!(matches!(recv(&rx), AsyncMsg::OnError(_)));
+}
+
+#[test]
+fn save_dyno_chunked() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, Some(MOCK_TOKEN));
+    let (tx, rx) = unbounded();
+
+    let time = NaiveDateTime::default();
+    let (path, bytes) = data_file("upload");
+    let mut markers = Markers::default();
+    markers.push(1_000, "gear shift");
+    markers.save_sidecar(&path).unwrap();
+    api.save_dyno(
+        "outbox-1".to_owned(),
+        path.clone(),
+        DynoConfig::default(),
+        time,
+        time,
+        tx.clone(),
+    );
+    match recv(&rx) {
+        AsyncMsg::OnUploaded(id, server_id) => {
+            assert_eq!(id, "outbox-1");
+            assert_eq!(server_id, 1);
+        }
+        msg => panic!("expected OnUploaded, got {msg:?}"),
+    }
+
+    let uploads = server.uploads();
+    assert_eq!(uploads.len(), 1);
+    let parts = &uploads[0];
+    for name in ["data", "info", "summary"] {
+        assert!(parts.contains_key(name), "missing multipart `{name}`");
+    }
+    assert_eq!(parts["data"], bytes);
+    let info = DynoTestDataInfo::decompress(parts["info"].clone()).unwrap();
+    assert_eq!(info.checksum_hex, checksum_from_bytes(&bytes));
+    let sent = serde_json::from_slice::<Markers>(&parts["markers"]).unwrap();
+    assert_eq!(sent, markers);
+
+    // server error is reported to the outbox entry, not as unauthorized.
+    server.fail("/api/dyno/uploads", StatusCode::INTERNAL_SERVER_ERROR);
+    api.save_dyno(
+        "outbox-2".to_owned(),
+        path.clone(),
+        DynoConfig::default(),
+        time,
+        time,
+        tx,
+    );
+    assert!(matches!(recv(&rx), AsyncMsg::OnUploadFailed(id, _) if id == "outbox-2"));
+    std::fs::remove_file(Markers::sidecar_path(&path)).ok();
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn base_server() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    server.set_base();
+    let api = service(&rt, &server, Some(MOCK_TOKEN));
+    let (tx, rx) = unbounded();
+
+    // the page is made from the list of all runs.
+    api.get_dyno(DynoQuery::default(), tx.clone());
+    match recv(&rx) {
+        AsyncMsg::OnApiLoadDyno(page) => assert_eq!(page.total, 0),
+        msg => panic!("expected OnApiLoadDyno, got {msg:?}"),
+    }
+
+    // without the chunked upload, the data is sent in `POST /api/dyno`.
+    let time = NaiveDateTime::default();
+    let (path, bytes) = data_file("multipart");
+    for id in ["outbox-1", "outbox-2"] {
+        api.save_dyno(
+            id.to_owned(),
+            path.clone(),
+            DynoConfig::default(),
+            time,
+            time,
+            tx.clone(),
+        );
+        assert!(matches!(recv(&rx), AsyncMsg::OnUploaded(..)));
+    }
+    let uploads = server.uploads();
+    assert_eq!(uploads.len(), 2);
+    assert_eq!(uploads[1]["data"], bytes);
+    assert!(uploads[1].contains_key("info"));
+    assert!(!uploads[1].contains_key("summary"));
+    // the optional routes are not tried on the base server.
+    assert!(!server.requested("/api/dyno/uploads"));
+    assert!(!server.requested("/api/users/me"));
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn upload_chunk_failure() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, Some(MOCK_TOKEN));
+    let (tx, rx) = unbounded();
+
+    let time = NaiveDateTime::default();
+    let (path, bytes) = data_file("chunk");
+    let chunk_path = format!("/api/dyno/uploads/{}", checksum_from_bytes(&bytes));
+
+    // every attempt of the chunk failed, the upload is failed and not completed.
+    server.fail_times(&chunk_path, StatusCode::BAD_GATEWAY, 3);
+    api.save_dyno(
+        "outbox-1".to_owned(),
+        path.clone(),
+        DynoConfig::default(),
+        time,
+        time,
+        tx.clone(),
+    );
+    assert!(matches!(recv(&rx), AsyncMsg::OnUploadFailed(..)));
+    assert!(server.uploads().is_empty());
+
+    // a single failure is retried in the same upload.
+    server.fail_times(&chunk_path, StatusCode::BAD_GATEWAY, 1);
+    api.retry_save_dyno(
+        "outbox-1".to_owned(),
+        path.clone(),
+        DynoConfig::default(),
+        time,
+        time,
+        tx,
+    );
+    assert!(matches!(recv(&rx), AsyncMsg::OnUploaded(..)));
+    assert_eq!(server.uploads()[0]["data"], bytes);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn load_file_checksum() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, Some(MOCK_TOKEN));
+    let (tx, rx) = unbounded();
+
+    let compressed = BufferData::new().compress().unwrap();
+    let checksum = checksum_from_bytes(&compressed);
+    server.set_file("/files/run.dyno", compressed);
+
+    api.load_dyno_file("/files/run.dyno".to_owned(), checksum, None, tx.clone());
+    assert!(matches!(recv(&rx), AsyncMsg::OnOpenBuffer(_)));
+
+    api.load_dyno_file(
+        "/files/run.dyno".to_owned(),
+        checksum_from_bytes(b"other data"),
+        None,
+        tx,
+    );
+    match recv(&rx) {
+        AsyncMsg::OnError(err) => assert!(err.to_string().contains("Checksum")),
+        msg => panic!("expected checksum error, got {msg:?}"),
+    }
+}
+
+#[test]
+fn download_resume() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, Some(MOCK_TOKEN));
+    let (tx, rx) = unbounded();
+
+    let (path, compressed) = data_file("download");
+    std::fs::remove_file(path).ok();
+    let checksum = checksum_from_bytes(&compressed);
+    let half = compressed.len() / 2;
+
+    // the first half in server is garbage, only valid if the download continue the part file.
+    let part = part_path(&checksum);
+    std::fs::write(&part, &compressed[..half]).unwrap();
+    let mut served = vec![0u8; half];
+    served.extend_from_slice(&compressed[half..]);
+    server.set_file("/files/resume.dyno", served);
+
+    api.load_dyno_file("/files/resume.dyno".to_owned(), checksum, None, tx);
+    assert!(matches!(recv(&rx), AsyncMsg::OnOpenBuffer(_)));
+    assert!(!part.exists());
+}
+
+#[test]
+fn unauthorized_is_retried() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, Some("expired-token"));
+    let (tx, rx) = unbounded();
+
+    let query = DynoQuery::default();
+    api.get_dyno(query.clone(), tx.clone());
+    match recv(&rx) {
+        AsyncMsg::OnApiUnauthorized(request) => assert_eq!(request, ApiRequest::GetDyno(query)),
+        msg => panic!("expected OnApiUnauthorized, got {msg:?}"),
+    }
+
+    api.load_dyno_file("/files/run.dyno".to_owned(), String::new(), None, tx);
+    assert!(matches!(
+        recv(&rx),
+        AsyncMsg::OnApiUnauthorized(ApiRequest::LoadDynoFile { .. })
+    ));
+}
+
+#[test]
+fn server_latency_timeout() {
+    let rt = runtime();
+    let _guard = rt.enter();
+    let server = MockServer::start();
+    let api = service(&rt, &server, None);
+    let (tx, rx) = unbounded();
+
+    api.check_health(tx.clone());
+    assert!(matches!(recv(&rx), AsyncMsg::OnCheckHealthApi(status) if status.is_success()));
+
+    // the client timeout of the profile is 1 second.
+    server.set_latency(Duration::from_millis(1500));
+    api.check_health(tx);
+    assert!(matches!(recv(&rx), AsyncMsg::OnError(_)));
+}
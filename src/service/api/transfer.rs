@@ -0,0 +1,353 @@
+//! Chunked and resumable transfer of the run data, so hour long runs survive slow or
+//! unstable links.
+//!
+//! Upload protocol:
+//! - `POST {dyno}/uploads` with `{checksum, size}`, the server returns the session id and the
+//!   offset it already has, an interrupted upload of the same data continues from there.
+//! - `PUT {dyno}/uploads/{id}` for every chunk with `Content-Range: bytes start-end/size`,
+//!   returns the new offset.
+//! - `POST {dyno}/uploads/{id}/complete` with the `info` multipart.
+//!
+//! A server without `{dyno}/uploads` gets the whole data in `POST {dyno}`, see [`super::features`].
+//!
+//! Download continues the `.part` file with the `Range` header.
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use dyno_core::{
+    asyncify,
+    crossbeam_channel::Sender,
+    crypto::{checksum_from_bytes, compare_checksums},
+    ignore_err,
+    reqwest::{header, Client, IntoUrl, StatusCode},
+    serde, tokio, ApiResponse, DynoErr,
+};
+
+use super::{error_for_status, features, ApiRequest};
+use crate::AsyncMsg;
+
+/// size of an upload chunk, and the interval of the progress report.
+pub const CHUNK_SIZE: usize = 256 * 1024;
+/// attempts of one chunk before the upload fails.
+const CHUNK_ATTEMPTS: u32 = 3;
+const CHUNK_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    Upload,
+    Download,
+}
+
+/// progress of a transfer, sent as [`AsyncMsg::OnTransfer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// outbox entry id for uploads, data checksum for downloads and sync.
+    pub id: String,
+    pub kind: TransferKind,
+    pub done: u64,
+    /// `0` if the size is unknown.
+    pub total: u64,
+}
+
+impl TransferProgress {
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.total > 0 && self.done >= self.total
+    }
+
+    #[inline]
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        (self.done as f64 / self.total as f64).min(1.0) as f32
+    }
+}
+
+impl std::fmt::Display for TransferProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let icon = match self.kind {
+            TransferKind::Upload => '⬆',
+            TransferKind::Download => '⬇',
+        };
+        const MB: f64 = 1024.0 * 1024.0;
+        if self.total == 0 {
+            return write!(f, "{icon} {:.1} MB", self.done as f64 / MB);
+        }
+        write!(
+            f,
+            "{icon} {:.0}% ({:.1} / {:.1} MB)",
+            self.fraction() * 100.0,
+            self.done as f64 / MB,
+            self.total as f64 / MB
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct Progress {
+    id: String,
+    kind: TransferKind,
+    tx: Sender<AsyncMsg>,
+}
+
+impl Progress {
+    pub fn new(id: impl ToString, kind: TransferKind, tx: Sender<AsyncMsg>) -> Self {
+        Self {
+            id: id.to_string(),
+            kind,
+            tx,
+        }
+    }
+
+    pub fn report(&self, done: u64, total: u64) {
+        ignore_err!(self.tx.send(AsyncMsg::OnTransfer(TransferProgress {
+            id: self.id.clone(),
+            kind: self.kind,
+            done,
+            total,
+        })));
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(crate = "serde")]
+struct UploadStart<'a> {
+    checksum: &'a str,
+    size: u64,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(crate = "serde")]
+struct UploadSession {
+    id: String,
+    #[serde(default)]
+    offset: u64,
+}
+
+/// upload `data` in chunks to `{base_url}/uploads`, returns the url of the upload session
+/// to complete with the info of the data, `None` if the server has no chunked upload.
+pub async fn upload_chunks(
+    base_url: &str,
+    client: &Client,
+    token: &str,
+    data: &[u8],
+    checksum: &str,
+    request: &ApiRequest,
+    progress: &Progress,
+) -> Result<Option<String>, AsyncMsg> {
+    let size = data.len() as u64;
+    let resp = client
+        .post(format!("{base_url}/uploads"))
+        .json(&UploadStart { checksum, size })
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)?;
+    if features::is_missing(resp.status()) {
+        return Ok(None);
+    }
+    let session = error_for_status(resp, request.clone())?
+        .json::<ApiResponse<UploadSession>>()
+        .await
+        .map_err(AsyncMsg::error)?
+        .payload;
+    let url = format!("{base_url}/uploads/{}", session.id);
+
+    let mut offset = session.offset.min(size);
+    progress.report(offset, size);
+    while offset < size {
+        let start = offset as usize;
+        let end = (start + CHUNK_SIZE).min(data.len());
+        offset = upload_chunk(&url, client, token, &data[start..end], start, size, request).await?;
+        progress.report(offset, size);
+    }
+    Ok(Some(url))
+}
+
+/// send one chunk, retried on network or server error, returns the offset of the server.
+async fn upload_chunk(
+    url: &str,
+    client: &Client,
+    token: &str,
+    chunk: &[u8],
+    start: usize,
+    size: u64,
+    request: &ApiRequest,
+) -> Result<u64, AsyncMsg> {
+    let range = format!("bytes {start}-{}/{size}", start + chunk.len() - 1);
+    let mut attempt = 1;
+    loop {
+        let result = client
+            .put(url)
+            .header(header::CONTENT_RANGE, &range)
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .body(chunk.to_vec())
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(AsyncMsg::error)
+            .and_then(|resp| error_for_status(resp, request.clone()));
+        let result = match result {
+            Ok(resp) => resp
+                .json::<ApiResponse<u64>>()
+                .await
+                .map(|resp| resp.payload)
+                .map_err(AsyncMsg::error),
+            Err(err) => Err(err),
+        };
+        match result {
+            // the server should always move forward, or the upload never ends.
+            Ok(offset) if offset as usize > start => return Ok(offset.min(size)),
+            Ok(offset) => {
+                return Err(AsyncMsg::error(DynoErr::api_error(format!(
+                    "Server did not accept the chunk at {start} (offset {offset})"
+                ))))
+            }
+            Err(AsyncMsg::OnError(err)) if attempt < CHUNK_ATTEMPTS => {
+                dyno_core::log::warn!("Upload chunk at {start} failed ({attempt}) - {err}");
+                attempt += 1;
+                tokio::time::sleep(CHUNK_RETRY_DELAY).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// partial download of the data with `checksum`.
+pub fn part_path(checksum: &str) -> PathBuf {
+    let name = checksum
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>();
+    std::env::temp_dir().join(format!("dyno-{name}.part"))
+}
+
+async fn write_part(path: PathBuf, data: Vec<u8>) {
+    if let Err(err) = asyncify!(move || std::fs::write(path, data).map_err(DynoErr::from)) {
+        dyno_core::log::warn!("Failed to keep the partial download - {err}");
+    }
+}
+
+async fn remove_part(path: PathBuf) {
+    ignore_err!(asyncify!(
+        move || std::fs::remove_file(path).map_err(DynoErr::from)
+    ));
+}
+
+/// download the compressed data file, continued from the `.part` file of the previous
+/// failed download, and check it with `checksum`. On failure the received data is kept
+/// for the next attempt.
+pub async fn download(
+    url: impl IntoUrl,
+    client: Client,
+    token: impl std::fmt::Display,
+    checksum: &str,
+    request: ApiRequest,
+    progress: &Progress,
+) -> Result<Vec<u8>, AsyncMsg> {
+    let part = part_path(checksum);
+    let read_part = part.clone();
+    let mut data = asyncify!(move || Ok(std::fs::read(read_part).unwrap_or_default()))
+        .map_err(AsyncMsg::error)?;
+
+    let mut builder = client.get(url).bearer_auth(token);
+    if !data.is_empty() {
+        builder = builder.header(header::RANGE, format!("bytes={}-", data.len()));
+    }
+    let resp = builder.send().await.map_err(AsyncMsg::error)?;
+    let mut resp = match resp.status() {
+        // the part file is already complete, or not matching the file in server anymore.
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            resp.bytes().await.ok();
+            if checksum_matched(&data, checksum) {
+                remove_part(part).await;
+                return Ok(data);
+            }
+            remove_part(part).await;
+            return Err(AsyncMsg::error("Partial download is not valid, try again."));
+        }
+        StatusCode::PARTIAL_CONTENT => resp,
+        _ => {
+            // server ignore the range, download from the start.
+            data.clear();
+            error_for_status(resp, request)?
+        }
+    };
+
+    let total = resp
+        .content_length()
+        .map_or(0, |len| len + data.len() as u64);
+    data.reserve(total.saturating_sub(data.len() as u64) as _);
+    let mut reported = data.len();
+    progress.report(data.len() as _, total);
+    loop {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                data.extend_from_slice(&chunk);
+                if data.len() - reported >= CHUNK_SIZE {
+                    reported = data.len();
+                    progress.report(data.len() as _, total);
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                write_part(part, data).await;
+                return Err(AsyncMsg::error(err));
+            }
+        }
+    }
+    progress.report(data.len() as _, total.max(data.len() as _));
+
+    remove_part(part).await;
+    if !checksum_matched(&data, checksum) {
+        return Err(AsyncMsg::error("Data Checksum is not matching."));
+    }
+    Ok(data)
+}
+
+#[inline]
+fn checksum_matched(data: &[u8], checksum: &str) -> bool {
+    compare_checksums(checksum_from_bytes(data).as_bytes(), checksum.as_bytes())
+}
+
+/// running transfers, for the progress in the UI.
+#[derive(Debug, Clone, Default)]
+pub struct Transfers {
+    items: Vec<(TransferProgress, Instant)>,
+}
+
+impl Transfers {
+    /// transfer without progress for this long is failed or finished.
+    const STALE: Duration = Duration::from_secs(30);
+
+    pub fn update(&mut self, progress: TransferProgress) {
+        self.items
+            .retain(|(item, updated)| item.id != progress.id && updated.elapsed() < Self::STALE);
+        if !progress.is_done() {
+            self.items.push((progress, Instant::now()));
+        }
+    }
+
+    #[inline]
+    pub fn remove(&mut self, id: &str) {
+        self.items.retain(|(item, _)| item.id != id);
+    }
+
+    #[inline]
+    pub fn get(&self, id: &str) -> Option<&TransferProgress> {
+        self.iter().find(|item| item.id == id)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &TransferProgress> {
+        self.items.iter().map(|(item, _)| item)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
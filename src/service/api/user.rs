@@ -65,3 +65,28 @@ pub async fn user_logout(
         Err(err) => Err(err),
     }
 }
+
+/// `None` if the server doesn't have the refresh route.
+pub async fn user_refresh(
+    url: impl IntoUrl,
+    client: Client,
+    token: impl std::fmt::Display,
+) -> Result<Option<ApiResponse<TokenDetails>>, AsyncMsg> {
+    let resp = client
+        .get(url)
+        .bearer_auth(token)
+        .send()
+        .await
+        .map_err(AsyncMsg::error)?;
+    if super::features::is_missing(resp.status()) {
+        return Ok(None);
+    }
+    match resp.error_for_status().map_err(AsyncMsg::error) {
+        Ok(resp) => resp
+            .json::<ApiResponse<TokenDetails>>()
+            .await
+            .map(Some)
+            .map_err(AsyncMsg::error),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,416 @@
+//! Calibration of the chassis constants (roller, gear and load) with the live data:
+//! - known distance roll: the odometer must match a distance measured on the roller surface,
+//!   the error is corrected in `diameter_roller`.
+//! - spin-down: the deceleration of the free roller, the loss torque of the bearing is
+//!   `I * alpha`. The inertia is not measured, it is assumed from `berat_beban` and
+//!   `diameter_roller_beban` as a solid cylinder.
+//! - load reference: the torque with a reference weight on a lever arm, the error is saved as
+//!   the load cell factor of [`CalculationConfig`], the roller constants are not changed.
+//!
+//! Every calibration is saved as dated record in `calibration.toml` in data dir.
+use std::{fs, path::PathBuf};
+
+use dyno_core::{
+    chrono::{NaiveDateTime, Utc},
+    serde, toml, Data, DynoConfig, DynoErr, DynoResult,
+};
+
+use super::RollerConstants;
+use crate::{calculation::CalculationConfig, paths::DynoPaths};
+
+/// standard gravity, m/s^2
+const GRAVITY: f64 = 9.80665;
+/// correction factor outside this range is a wiring or configuration error, not calibration.
+const FACTOR_RANGE: std::ops::RangeInclusive<f64> = 0.5..=2.0;
+const MIN_SAMPLES: usize = 5;
+
+/// live data point for the calibration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// milliseconds
+    pub time: i64,
+    /// metres
+    pub odo: f64,
+    pub rpm_roda: f64,
+    /// newton metres
+    pub torque: f64,
+}
+
+impl From<&Data> for Sample {
+    fn from(data: &Data) -> Self {
+        Self {
+            time: data.time_stamp.and_utc().timestamp_millis(),
+            odo: data.odo.value() * 1000.0,
+            rpm_roda: data.rpm_roda.value(),
+            torque: data.torque.value(),
+        }
+    }
+}
+
+fn check_factor(factor: f64, what: &str) -> DynoResult<()> {
+    if !factor.is_finite() || !FACTOR_RANGE.contains(&factor) {
+        return Err(DynoErr::input_error(format!(
+            "{what} correction x{factor:.3} is out of range, check the sensor wiring and the configuration first"
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct DistanceResult {
+    /// metres, measured on the roller surface
+    pub known: f64,
+    /// metres, from the odometer
+    pub measured: f64,
+    pub factor: f64,
+}
+
+impl DistanceResult {
+    pub fn compute(samples: &[Sample], known: f64) -> DynoResult<Self> {
+        let (first, last) = match (samples.first(), samples.last()) {
+            (Some(first), Some(last)) if samples.len() >= 2 => (first, last),
+            _ => return Err(DynoErr::input_error("roll the roller while recording")),
+        };
+        if known <= 0.0 {
+            return Err(DynoErr::input_error("known distance must be more than 0"));
+        }
+        let measured = last.odo - first.odo;
+        if measured <= 0.0 {
+            return Err(DynoErr::input_error(
+                "odometer is not moving, check the encoder",
+            ));
+        }
+        let factor = known / measured;
+        check_factor(factor, "Roller diameter")?;
+        Ok(Self {
+            known,
+            measured,
+            factor,
+        })
+    }
+
+    pub fn apply(&self, rollers: &mut RollerConstants) {
+        rollers.diameter_roller *= self.factor;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct SpinDownResult {
+    pub from_rpm: f64,
+    pub to_rpm: f64,
+    /// seconds
+    pub duration: f64,
+    /// angular deceleration, rad/s^2
+    pub deceleration: f64,
+    /// kg m^2, assumed as a solid cylinder from the roller constants, not measured.
+    pub inertia: f64,
+    /// newton metres
+    pub loss_torque: f64,
+}
+
+impl SpinDownResult {
+    /// deceleration from the highest rpm to the end, with least squares of `omega(t)`.
+    pub fn compute(samples: &[Sample], rollers: &RollerConstants) -> DynoResult<Self> {
+        let peak = samples
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.rpm_roda.total_cmp(&b.rpm_roda))
+            .map_or(0, |(idx, _)| idx);
+        let coast = &samples[peak..];
+        if coast.len() < MIN_SAMPLES {
+            return Err(DynoErr::input_error(
+                "spin the roller, then release it while recording",
+            ));
+        }
+        let t0 = coast[0].time;
+        let points = coast
+            .iter()
+            .map(|s| {
+                let t = (s.time - t0) as f64 / 1000.0;
+                let omega = s.rpm_roda * std::f64::consts::TAU / 60.0;
+                (t, omega)
+            })
+            .collect::<Vec<_>>();
+        let duration = points[points.len() - 1].0;
+        if duration < 1.0 {
+            return Err(DynoErr::input_error(
+                "spin-down must be recorded for at least 1 s",
+            ));
+        }
+        let n = points.len() as f64;
+        let (sum_t, sum_w) = points
+            .iter()
+            .fold((0.0, 0.0), |(st, sw), (t, w)| (st + t, sw + w));
+        let (mean_t, mean_w) = (sum_t / n, sum_w / n);
+        let (cov, var) = points.iter().fold((0.0, 0.0), |(cov, var), (t, w)| {
+            (
+                cov + (t - mean_t) * (w - mean_w),
+                var + (t - mean_t).powi(2),
+            )
+        });
+        let deceleration = -cov / var;
+        if !deceleration.is_finite() || deceleration <= 0.0 {
+            return Err(DynoErr::input_error("roller is not slowing down"));
+        }
+        let radius = rollers.diameter_roller_beban / 2.0;
+        let inertia = 0.5 * rollers.berat_beban * radius * radius;
+        Ok(Self {
+            from_rpm: coast[0].rpm_roda,
+            to_rpm: coast[coast.len() - 1].rpm_roda,
+            duration,
+            deceleration,
+            inertia,
+            loss_torque: inertia * deceleration,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct LoadResult {
+    /// kilogram
+    pub weight: f64,
+    /// metres
+    pub arm: f64,
+    /// newton metres, `weight * g * arm`
+    pub reference: f64,
+    /// newton metres, average of the samples
+    pub measured: f64,
+    pub factor: f64,
+}
+
+impl LoadResult {
+    pub fn compute(samples: &[Sample], weight: f64, arm: f64) -> DynoResult<Self> {
+        if samples.len() < MIN_SAMPLES {
+            return Err(DynoErr::input_error(
+                "record the torque with the reference weight",
+            ));
+        }
+        if weight <= 0.0 || arm <= 0.0 {
+            return Err(DynoErr::input_error("weight and arm must be more than 0"));
+        }
+        let measured = samples.iter().map(|s| s.torque).sum::<f64>() / samples.len() as f64;
+        if measured <= 0.0 {
+            return Err(DynoErr::input_error("no torque is measured"));
+        }
+        let reference = weight * GRAVITY * arm;
+        let factor = reference / measured;
+        check_factor(factor, "Load")?;
+        Ok(Self {
+            weight,
+            arm,
+            reference,
+            measured,
+            factor,
+        })
+    }
+
+    /// the samples are the torque as recorded, so the factor replaces the previous factor.
+    pub fn apply(&self, calculation: &mut CalculationConfig) {
+        calculation.load_factor = self.factor;
+    }
+}
+
+/// warnings of the constants that are not physically possible for the chassis.
+pub fn validate(rollers: &RollerConstants) -> Vec<String> {
+    let mut warnings = vec![];
+    let mut check = |name: &str, value: f64, min: f64, max: f64, unit: &str| {
+        if !(min..=max).contains(&value) {
+            warnings.push(format!(
+                "{name} {value:.3} {unit} is outside {min} - {max} {unit}"
+            ));
+        }
+    };
+    check("Diameter Roller", rollers.diameter_roller, 0.05, 1.5, "m");
+    check(
+        "Diameter Roller Beban",
+        rollers.diameter_roller_beban,
+        0.05,
+        1.5,
+        "m",
+    );
+    check(
+        "Diameter Gear Encoder",
+        rollers.diameter_gear_encoder,
+        0.005,
+        0.5,
+        "m",
+    );
+    check(
+        "Diameter Gear Beban",
+        rollers.diameter_gear_beban,
+        0.005,
+        0.5,
+        "m",
+    );
+    check("Jarak Antar Gear", rollers.jarak_gear, 0.01, 2.0, "m");
+    check("Berat Roller Beban", rollers.berat_beban, 1.0, 1000.0, "kg");
+    warnings
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct CalibrationRecord {
+    pub time: NaiveDateTime,
+    /// constants after the calibration.
+    pub rollers: RollerConstants,
+    #[serde(default)]
+    pub distance: Option<DistanceResult>,
+    #[serde(default)]
+    pub spin_down: Option<SpinDownResult>,
+    #[serde(default)]
+    pub load: Option<LoadResult>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationStatus {
+    Valid,
+    Never,
+    /// constants in the config are not the calibrated constants.
+    Changed,
+    /// days since the last calibration.
+    Stale(i64),
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+struct CalibrationIndex {
+    records: Vec<CalibrationRecord>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationLog {
+    path: PathBuf,
+    records: Vec<CalibrationRecord>,
+}
+
+impl CalibrationLog {
+    pub const FILE_NAME: &'static str = "calibration.toml";
+    /// calibration older than this is stale.
+    pub const STALE_DAYS: i64 = 30;
+
+    pub fn load(paths: &DynoPaths) -> Self {
+        let path = paths.get_data_dir_file(Self::FILE_NAME);
+        let records = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| match toml::from_str::<CalibrationIndex>(&s) {
+                Ok(index) => Some(index.records),
+                Err(err) => {
+                    dyno_core::log::error!("Failed to read calibration records - {err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self { path, records }
+    }
+
+    /// add the record, and save the log.
+    pub fn push(&mut self, record: CalibrationRecord) -> DynoResult<()> {
+        self.records.push(record);
+        let index = CalibrationIndex {
+            records: self.records.clone(),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, toml::to_string(&index)?).map_err(From::from)
+    }
+
+    #[inline]
+    pub fn records(&self) -> &[CalibrationRecord] {
+        &self.records
+    }
+
+    #[inline]
+    pub fn latest(&self) -> Option<&CalibrationRecord> {
+        self.records.iter().max_by_key(|r| r.time)
+    }
+
+    /// status of the calibration for the constants in `config`.
+    pub fn status(&self, config: &DynoConfig) -> CalibrationStatus {
+        let latest = match self.latest() {
+            Some(latest) => latest,
+            None => return CalibrationStatus::Never,
+        };
+        // constants edited by hand after the calibration.
+        if latest.rollers != RollerConstants::from_config(config) {
+            return CalibrationStatus::Changed;
+        }
+        let days = (Utc::now().naive_local() - latest.time).num_days();
+        if days >= Self::STALE_DAYS {
+            return CalibrationStatus::Stale(days);
+        }
+        CalibrationStatus::Valid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(time: i64, odo: f64, rpm_roda: f64, torque: f64) -> Sample {
+        Sample {
+            time,
+            odo,
+            rpm_roda,
+            torque,
+        }
+    }
+
+    #[test]
+    fn compute_calibration() {
+        let rollers = RollerConstants {
+            diameter_roller: 0.3,
+            diameter_roller_beban: 0.4,
+            diameter_gear_encoder: 0.05,
+            diameter_gear_beban: 0.1,
+            jarak_gear: 0.2,
+            berat_beban: 50.0,
+        };
+
+        // odometer read 9.5 m for 10 m on the roller surface.
+        let samples = [sample(0, 100.0, 0.0, 0.0), sample(5000, 109.5, 0.0, 0.0)];
+        let distance = DistanceResult::compute(&samples, 10.0).unwrap();
+        let mut corrected = rollers;
+        distance.apply(&mut corrected);
+        assert!((corrected.diameter_roller - 0.3 * 10.0 / 9.5).abs() < 1e-9);
+        assert!(DistanceResult::compute(&samples, 30.0).is_err());
+
+        // 600 rpm to 0 in 6 s after the peak, omega drops 62.83 rad/s in 6 s.
+        let samples = (0..=8)
+            .map(|i| {
+                let rpm = if i < 2 {
+                    300.0 * i as f64
+                } else {
+                    600.0 - 100.0 * (i - 2) as f64
+                };
+                sample(i * 1000, 0.0, rpm, 0.0)
+            })
+            .collect::<Vec<_>>();
+        let spin = SpinDownResult::compute(&samples, &rollers).unwrap();
+        assert!((spin.deceleration - std::f64::consts::TAU * 100.0 / 60.0).abs() < 1e-6);
+        assert!((spin.inertia - 0.5 * 50.0 * 0.2 * 0.2).abs() < 1e-9);
+
+        // 10 kg on 0.5 m arm, measured 45 Nm.
+        let samples = (0..5)
+            .map(|i| sample(i, 0.0, 0.0, 45.0))
+            .collect::<Vec<_>>();
+        let load = LoadResult::compute(&samples, 10.0, 0.5).unwrap();
+        assert!((load.factor - 10.0 * GRAVITY * 0.5 / 45.0).abs() < 1e-9);
+        let mut calculation = CalculationConfig::default();
+        load.apply(&mut calculation);
+        assert_eq!(calculation.load_factor, load.factor);
+        assert!(calculation.is_computed());
+
+        assert!(validate(&rollers).is_empty());
+        let invalid = RollerConstants {
+            berat_beban: 0.0,
+            ..rollers
+        };
+        assert_eq!(validate(&invalid).len(), 1);
+    }
+}
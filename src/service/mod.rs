@@ -1,5 +1,11 @@
 mod api;
+pub mod calibration;
+mod outbox;
+mod presets;
 mod serial;
+mod sync;
+mod telemetry;
+mod vehicles;
 
 // pub use api::*;
 
@@ -8,4 +14,19 @@ pub use serial::{
     SerialService,
 };
 
-pub use api::ApiService;
+pub use api::{
+    ApiRequest, ApiService, CurrentUser, DynoPage, DynoQuery, DynoSort, DynoTestEntry,
+    ReviewAction, ReviewComment, ReviewEvent, ReviewRequest, TransferKind, TransferProgress,
+    Transfers,
+};
+pub use calibration::{
+    CalibrationLog, CalibrationRecord, CalibrationStatus, DistanceResult, LoadResult, Sample,
+    SpinDownResult,
+};
+pub use outbox::{Outbox, OutboxEntry};
+pub use presets::{ConfigPreset, Presets};
+pub use sync::{
+    scan_local, LocalRun, RemoteRun, SyncEvent, SyncRun, SyncService, SyncState, SyncTask,
+};
+pub use telemetry::{TelemetrySample, TelemetryService};
+pub use vehicles::{Customer, RollerConstants, Vehicle, VehicleDb, VehicleRun};
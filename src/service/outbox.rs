@@ -0,0 +1,197 @@
+//! Outbox of server uploads, every `Save to Server` is written to the data dir first
+//! and removed after the server accept it, failed uploads are retried with backoff.
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use dyno_core::{
+    chrono::{NaiveDateTime, Utc},
+    serde, toml, BufferData, CompresedSaver as _, DynoConfig, DynoErr, DynoResult,
+};
+
+use crate::{formats::markers::Markers, paths::DynoPaths};
+
+const RETRY_BASE: Duration = Duration::from_secs(15);
+const RETRY_MAX: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct OutboxEntry {
+    pub id: String,
+    pub created: NaiveDateTime,
+    pub start: NaiveDateTime,
+    pub stop: NaiveDateTime,
+    pub config: DynoConfig,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// id of the vehicle in [`crate::service::VehicleDb`], linked to the run after uploaded.
+    #[serde(default)]
+    pub vehicle: Option<String>,
+
+    #[serde(skip)]
+    next_retry: Option<Instant>,
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+impl OutboxEntry {
+    #[inline]
+    pub fn is_uploading(&self) -> bool {
+        self.in_flight
+    }
+
+    /// time until the next retry, `None` if it's due now.
+    pub fn retry_in(&self) -> Option<Duration> {
+        self.next_retry
+            .and_then(|t| t.checked_duration_since(Instant::now()))
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+struct OutboxIndex {
+    entries: Vec<OutboxEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Outbox {
+    dir: PathBuf,
+    entries: Vec<OutboxEntry>,
+}
+
+impl Outbox {
+    pub const FOLDER_NAME: &'static str = "Outbox";
+    const INDEX_NAME: &'static str = "outbox.toml";
+
+    /// load the pending uploads from `Outbox` folder in data dir.
+    pub fn load(paths: &DynoPaths) -> Self {
+        let dir = paths.get_data_dir_folder(Self::FOLDER_NAME);
+        let entries = fs::read_to_string(dir.join(Self::INDEX_NAME))
+            .ok()
+            .and_then(|s| match toml::from_str::<OutboxIndex>(&s) {
+                Ok(index) => Some(index.entries),
+                Err(err) => {
+                    dyno_core::log::error!("Failed to read upload outbox index - {err}");
+                    None
+                }
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|e| dir.join(Self::data_name(&e.id)).exists())
+            .collect();
+        Self { dir, entries }
+    }
+
+    #[inline]
+    fn data_name(id: &str) -> String {
+        format!("{id}.dyno")
+    }
+
+    #[inline]
+    pub fn data_path(&self, id: &str) -> PathBuf {
+        self.dir.join(Self::data_name(id))
+    }
+
+    fn persist(&self) -> DynoResult<()> {
+        let index = OutboxIndex {
+            entries: self.entries.clone(),
+        };
+        let data = toml::to_string(&index)?;
+        fs::write(self.dir.join(Self::INDEX_NAME), data).map_err(From::from)
+    }
+
+    /// write the upload to disk with its event markers, returns the id of the entry.
+    pub fn push(
+        &mut self,
+        data: &BufferData,
+        markers: &Markers,
+        config: DynoConfig,
+        start: NaiveDateTime,
+        stop: NaiveDateTime,
+        vehicle: Option<String>,
+    ) -> DynoResult<String> {
+        fs::create_dir_all(&self.dir)?;
+        let id = dyno_core::uuid::Uuid::new_v4().simple().to_string();
+        data.compress_to_path(self.data_path(&id))?;
+        markers.save_sidecar(&self.data_path(&id))?;
+        self.entries.push(OutboxEntry {
+            id: id.clone(),
+            created: Utc::now().naive_local(),
+            start,
+            stop,
+            config,
+            attempts: 0,
+            last_error: None,
+            vehicle,
+            next_retry: None,
+            in_flight: true,
+        });
+        self.persist()?;
+        Ok(id)
+    }
+
+    /// remove the entry and its data, after uploaded or discarded by user.
+    pub fn remove(&mut self, id: &str) -> DynoResult<()> {
+        self.entries.retain(|e| e.id != id);
+        let path = self.data_path(id);
+        for path in [Markers::sidecar_path(&path), path] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        self.persist()
+    }
+
+    pub fn set_failed(&mut self, id: &str, err: &DynoErr) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) {
+            entry.attempts += 1;
+            entry.last_error = Some(err.to_string());
+            entry.in_flight = false;
+            let backoff = RETRY_BASE
+                .saturating_mul(1 << entry.attempts.min(10).saturating_sub(1))
+                .min(RETRY_MAX);
+            entry.next_retry = Some(Instant::now() + backoff);
+        }
+        if let Err(err) = self.persist() {
+            dyno_core::log::error!("Failed to write upload outbox index - {err}");
+        }
+    }
+
+    /// next entry to retry, the entry is marked as uploading.
+    pub fn take_due(&mut self) -> Option<OutboxEntry> {
+        if self.entries.iter().any(|e| e.in_flight) {
+            return None;
+        }
+        let now = Instant::now();
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|e| e.next_retry.map_or(true, |t| t <= now))?;
+        entry.in_flight = true;
+        Some(entry.clone())
+    }
+
+    /// retry all entries on the next poll.
+    pub fn retry_now(&mut self) {
+        for entry in self.entries.iter_mut() {
+            entry.next_retry = None;
+        }
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[OutboxEntry] {
+        &self.entries
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,240 @@
+//! Named `DynoConfig` presets, one TOML file per preset in the `presets` folder of config dir,
+//! so a preset file can be copied to another PC or shared with [`Presets::export`].
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use dyno_core::{serde, toml, DynoConfig, DynoErr, DynoResult};
+
+use crate::paths::DynoPaths;
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct ConfigPreset {
+    pub name: String,
+    pub config: DynoConfig,
+    /// file of the preset, the name in the file may be different from the file name.
+    #[serde(skip)]
+    #[serde(default)]
+    pub path: PathBuf,
+}
+
+impl ConfigPreset {
+    /// `true` if `config` is the same as the config of the preset.
+    pub fn is_same(&self, config: &DynoConfig) -> bool {
+        match (toml::to_string(&self.config), toml::to_string(config)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Presets {
+    dir: PathBuf,
+    presets: Vec<ConfigPreset>,
+}
+
+/// file name of the preset, only the alphanumeric of the name is kept.
+fn file_name(name: &str) -> String {
+    let name = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    format!("{name}.toml")
+}
+
+/// read a preset file, or a plain `DynoConfig` file named after the file.
+fn read_preset(path: &Path) -> DynoResult<ConfigPreset> {
+    let data = fs::read_to_string(path)?;
+    if let Ok(preset) = toml::from_str::<ConfigPreset>(&data) {
+        return Ok(preset);
+    }
+    let config = toml::from_str::<DynoConfig>(&data)?;
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(ConfigPreset {
+        name,
+        config,
+        path: path.to_path_buf(),
+    })
+}
+
+impl Presets {
+    pub const FOLDER: &'static str = "presets";
+
+    pub fn load(paths: &DynoPaths) -> Self {
+        Self::from_dir(paths.get_config_dir_folder(Self::FOLDER))
+    }
+
+    fn from_dir(dir: PathBuf) -> Self {
+        let mut presets = Self {
+            dir,
+            presets: vec![],
+        };
+        presets.reload();
+        presets
+    }
+
+    /// read the presets in the folder again, after the files changed outside the app.
+    pub fn reload(&mut self) {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => {
+                self.presets.clear();
+                return;
+            }
+        };
+        self.presets = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map_or(false, |e| e == "toml"))
+            .filter_map(|path| match read_preset(&path) {
+                Ok(preset) => Some(ConfigPreset { path, ..preset }),
+                Err(err) => {
+                    dyno_core::log::error!("Failed to read preset `{}` - {err}", path.display());
+                    None
+                }
+            })
+            .collect();
+        self.presets.sort_by_key(|p| p.name.to_lowercase());
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigPreset> {
+        self.presets.iter()
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&ConfigPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+
+    /// file for a new preset `name`, a number is added if the file of another preset has the
+    /// same name ("Chassis B" and "Chassis-B" are both `Chassis_B.toml`).
+    fn new_path(&self, name: &str) -> PathBuf {
+        let file = file_name(name);
+        let stem = file.trim_end_matches(".toml");
+        std::iter::once(self.dir.join(&file))
+            .chain((2..).map(|n| self.dir.join(format!("{stem}_{n}.toml"))))
+            .find(|path| !path.exists())
+            .unwrap_or_else(|| self.dir.join(file))
+    }
+
+    /// save `config` as preset `name`, replace the preset with the same name.
+    pub fn save(&mut self, name: &str, config: &DynoConfig) -> DynoResult<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(DynoErr::input_error("Preset name is empty"));
+        }
+        let path = match self.get(name) {
+            Some(preset) => preset.path.clone(),
+            None => self.new_path(name),
+        };
+        let preset = ConfigPreset {
+            name: name.to_owned(),
+            config: config.clone(),
+            path: PathBuf::new(),
+        };
+        fs::create_dir_all(&self.dir)?;
+        fs::write(path, toml::to_string(&preset)?)?;
+        self.reload();
+        Ok(())
+    }
+
+    /// remove the file of the preset, where the preset is read from.
+    pub fn remove(&mut self, name: &str) -> DynoResult<()> {
+        if let Some(preset) = self.get(name) {
+            fs::remove_file(&preset.path)?;
+        }
+        self.reload();
+        Ok(())
+    }
+
+    /// copy the preset file in `path` to the presets, returns the name of the preset.
+    pub fn import(&mut self, path: &Path) -> DynoResult<String> {
+        let preset = read_preset(path)?;
+        self.save(&preset.name, &preset.config)?;
+        Ok(preset.name)
+    }
+
+    pub fn export(&self, name: &str, path: &Path) -> DynoResult<()> {
+        let preset = self
+            .get(name)
+            .ok_or_else(|| DynoErr::input_error(format!("Preset `{name}` doesn't exists")))?;
+        fs::write(path, toml::to_string(preset)?).map_err(From::from)
+    }
+
+    /// file name for the export of preset `name`.
+    #[inline]
+    pub fn export_name(name: &str) -> String {
+        file_name(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_import_export() {
+        let root = std::env::temp_dir().join(format!("dyno-presets-{}", std::process::id()));
+        let mut presets = Presets::from_dir(root.join("a"));
+        let config = DynoConfig::default();
+        presets.save("Honda Beat 110 CVT", &config).unwrap();
+        presets.save("Chassis B", &config).unwrap();
+        assert!(presets.get("Honda Beat 110 CVT").unwrap().is_same(&config));
+        assert_eq!(
+            presets.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            ["Chassis B", "Honda Beat 110 CVT"]
+        );
+
+        let exported = root.join("shared.toml");
+        presets.export("Chassis B", &exported).unwrap();
+        let mut other = Presets::from_dir(root.join("b"));
+        assert_eq!(other.import(&exported).unwrap(), "Chassis B");
+        assert!(other.get("Chassis B").is_some());
+
+        // plain config file is imported with the file name.
+        let plain = root.join("config.toml");
+        fs::write(&plain, toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(other.import(&plain).unwrap(), "config");
+
+        // same file name is not overwritten.
+        presets.save("Chassis-B", &config).unwrap();
+        presets.save("Chassis B", &config).unwrap();
+        assert_eq!(presets.iter().count(), 3);
+        assert_eq!(
+            presets.get("Chassis-B").unwrap().path,
+            root.join("a").join("Chassis_B_2.toml")
+        );
+
+        presets.remove("Chassis B").unwrap();
+        assert!(presets.get("Chassis B").is_none());
+        assert!(presets.get("Chassis-B").is_some());
+
+        // preset copied by hand with a different file name.
+        let preset = ConfigPreset {
+            name: "Hand Copied".to_owned(),
+            config: config.clone(),
+            path: PathBuf::new(),
+        };
+        fs::write(
+            root.join("a").join("copy.toml"),
+            toml::to_string(&preset).unwrap(),
+        )
+        .unwrap();
+        presets.reload();
+        presets.remove("Hand Copied").unwrap();
+        assert!(presets.get("Hand Copied").is_none());
+        fs::remove_dir_all(root).ok();
+    }
+}
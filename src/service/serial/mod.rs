@@ -17,7 +17,7 @@ use std::sync::{
     Arc,
 };
 
-use crate::{toast_error, AsyncMsg};
+use crate::{toast_error, tr, AsyncMsg};
 
 use self::impl_serial::open_async;
 
@@ -36,14 +36,14 @@ impl SerialService {
             Ok(ok) => match ok {
                 Some(some) => some,
                 None => {
-                    toast_error!(
+                    toast_error!(tr!(
                         "Failed to get port info, there is no port available in this machine"
-                    );
+                    ));
                     return None;
                 }
             },
             Err(err) => {
-                toast_error!("Failed to get port info, {err}");
+                toast_error!(tr!("Failed to get port info, {err}", err = err));
                 return None;
             }
         };
@@ -73,27 +73,28 @@ impl SerialService {
                 if !running.load(Ordering::Relaxed) {
                     break 'loops;
                 }
-                // membaca data sampai dengan menemui Delimiter '\n',
-                // dan menyimpannya pada `buffer`
+                // read the data until the Delimiter '\n',
+                // and keep it in `buffer`
                 match serial_port.read_until(SerialData::DELIM, &mut buffer).await {
-                    // jika tidak ada yang terbaca ( byte yang terbaca 0 ), ulang kembali loop
+                    // nothing is read ( 0 byte read ), repeat the loop
                     Ok(0) => continue,
-                    // jika terbaca dan diakhiri delimiter, proses data tersebut
+                    // read and ended with the delimiter, process the data
                     Ok(len) if buffer[last + len - 1] == SerialData::DELIM => {
-                        // memproses data dan menkonversi byte data tersebut ke tipe data 'SerialData'
-                        // dan mengirimnya melalui mpsc channel
-                        if let Some(data) = SerialData::from_bytes(&buffer[..len - 1]) {
-                            ignore_err!(tx.send(AsyncMsg::OnSerialData(data)))
+                        // process the data, convert the bytes to 'SerialData'
+                        // and send it through the mpsc channel
+                        let frame = &buffer[..len - 1];
+                        if let Some(data) = SerialData::from_bytes(frame) {
+                            ignore_err!(tx.send(AsyncMsg::OnSerialData(data, frame.to_vec())))
                         }
-                        // menghapus buffer, untuk menyiapkan data pada iterasi selanjutnya
-                        // yang akan diterima
+                        // clear the buffer, ready for the data received
+                        // in the next iteration
                         buffer.clear();
                         last = 0;
                     }
-                    // jika tidak diakhiri delimiter, tambah jumlah 'last' variable dengan jumlah
-                    // byte yang terbaca
+                    // not ended with the delimiter, add the number of bytes read
+                    // to the 'last' variable
                     Ok(len) => last += len,
-                    // jika error
+                    // on error
                     Err(err) => {
                         if matches!(
                             err.kind(),
@@ -0,0 +1,469 @@
+//! Two-way sync of the `.dyno` runs in the local `Saved` folder with the runs in server.
+//! Runs are matched by the checksum of the compressed data, the same checksum as
+//! `data_checksum` in server, so a run saved in both places is never transferred twice.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use dyno_core::{
+    chrono::{NaiveDateTime, Utc},
+    crypto::checksum_from_bytes,
+    serde, toml, DynoErr, DynoResult,
+};
+
+use crate::{paths::DynoPaths, state::DynoFileType, tr};
+
+/// maximum number of uploads and downloads at the same time.
+const MAX_TRANSFERS: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    Synced,
+    LocalOnly,
+    ServerOnly,
+    Uploading,
+    Downloading,
+    Failed,
+}
+
+impl SyncState {
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Synced => tr!("✔ synced"),
+            Self::LocalOnly => tr!("⬆ local"),
+            Self::ServerOnly => tr!("⬇ server"),
+            Self::Uploading => tr!("⟳ uploading"),
+            Self::Downloading => tr!("⟳ downloading"),
+            Self::Failed => tr!("⚠ failed"),
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Synced => tr!("the run is in this computer and in the server"),
+            Self::LocalOnly => tr!("the run is only in this computer, it will be uploaded on sync"),
+            Self::ServerOnly => tr!("the run is only in the server, it will be downloaded on sync"),
+            Self::Uploading => tr!("uploading the run to the server"),
+            Self::Downloading => tr!("downloading the run from the server"),
+            Self::Failed => tr!("failed to sync the run, it will be tried again on the next sync"),
+        }
+    }
+}
+
+/// `.dyno` file in the local `Saved` folder.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct LocalRun {
+    pub path: PathBuf,
+    pub checksum: String,
+    size: u64,
+    /// modified time (unix seconds), the checksum is only calculated again if it changed.
+    modified: u64,
+}
+
+impl LocalRun {
+    fn metadata(path: &Path) -> DynoResult<(u64, u64)> {
+        let meta = fs::metadata(path)?;
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |dur| dur.as_secs());
+        Ok((meta.len(), modified))
+    }
+
+    pub fn from_path(path: PathBuf, checksum: String) -> DynoResult<Self> {
+        let (size, modified) = Self::metadata(&path)?;
+        Ok(Self {
+            path,
+            checksum,
+            size,
+            modified,
+        })
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// run in server, from [`super::DynoTestEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct RemoteRun {
+    pub id: i64,
+    pub checksum: String,
+    pub data_url: String,
+    pub motor: String,
+}
+
+impl From<&super::DynoTestEntry> for RemoteRun {
+    fn from(entry: &super::DynoTestEntry) -> Self {
+        Self {
+            id: i64::from(entry.dyno.id),
+            checksum: entry.dyno.data_checksum.clone(),
+            data_url: entry.dyno.data_url.clone(),
+            motor: entry.motor_name(),
+        }
+    }
+}
+
+/// result of the sync tasks, sent as [`crate::AsyncMsg::OnSync`].
+#[derive(Debug, Clone)]
+pub enum SyncEvent {
+    Local(Vec<LocalRun>),
+    Remote(Vec<RemoteRun>),
+    ScanFailed(DynoErr),
+    Uploaded { checksum: String, id: i64 },
+    Downloaded { checksum: String, path: PathBuf },
+    Failed { checksum: String, err: DynoErr },
+}
+
+/// upload or download that need to be done by [`super::ApiService`].
+#[derive(Debug, Clone)]
+pub enum SyncTask {
+    Upload(LocalRun),
+    Download(RemoteRun, PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncRun {
+    pub checksum: String,
+    pub local: Option<LocalRun>,
+    pub remote: Option<RemoteRun>,
+    pub state: SyncState,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+struct SyncIndex {
+    last_sync: Option<NaiveDateTime>,
+    local: Vec<LocalRun>,
+    remote: Vec<RemoteRun>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncService {
+    dir: PathBuf,
+    index: SyncIndex,
+    runs: Vec<SyncRun>,
+    /// scan result waiting for the other side.
+    scanned_local: Option<Vec<LocalRun>>,
+    scanned_remote: Option<Vec<RemoteRun>>,
+    scanning: bool,
+    transferring: bool,
+}
+
+impl SyncService {
+    const INDEX_NAME: &'static str = "sync.toml";
+
+    /// load the last sync state of the `Saved` folder in data dir.
+    pub fn load(paths: &DynoPaths) -> Self {
+        let dir = DynoFileType::Dyno.path(paths.get_data_dir_folder("Saved"));
+        let index = fs::read_to_string(dir.join(Self::INDEX_NAME))
+            .ok()
+            .and_then(|s| match toml::from_str::<SyncIndex>(&s) {
+                Ok(index) => Some(index),
+                Err(err) => {
+                    dyno_core::log::error!("Failed to read sync index - {err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let runs = merge(&index.local, &index.remote);
+        Self {
+            dir,
+            index,
+            runs,
+            ..Default::default()
+        }
+    }
+
+    fn persist(&self) {
+        let result = fs::create_dir_all(&self.dir)
+            .map_err(DynoErr::from)
+            .and_then(|_| toml::to_string(&self.index).map_err(DynoErr::from))
+            .and_then(|data| fs::write(self.dir.join(Self::INDEX_NAME), data).map_err(From::from));
+        if let Err(err) = result {
+            dyno_core::log::error!("Failed to write sync index - {err}");
+        }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    #[inline]
+    pub fn runs(&self) -> &[SyncRun] {
+        &self.runs
+    }
+
+    #[inline]
+    pub fn last_sync(&self) -> Option<NaiveDateTime> {
+        self.index.last_sync
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.scanning || self.transferring
+    }
+
+    /// cached local runs, to skip the checksum of unchanged files in [`scan_local`].
+    #[inline]
+    pub fn local_cache(&self) -> Vec<LocalRun> {
+        self.index.local.clone()
+    }
+
+    /// mark the sync as started, the scan of both sides is done by the caller.
+    pub fn start(&mut self) -> bool {
+        if self.is_running() {
+            return false;
+        }
+        self.scanning = true;
+        self.scanned_local = None;
+        self.scanned_remote = None;
+        true
+    }
+
+    /// stop the sync (session expired), in progress transfers are done again on the next sync.
+    pub fn cancel(&mut self) {
+        self.scanning = false;
+        self.transferring = false;
+        for run in self.runs.iter_mut() {
+            run.state = match run.state {
+                SyncState::Uploading => SyncState::LocalOnly,
+                SyncState::Downloading => SyncState::ServerOnly,
+                state => state,
+            };
+        }
+    }
+
+    /// update the state with the result of sync task, returns the error of the scan.
+    pub fn on_event(&mut self, event: SyncEvent) -> Option<DynoErr> {
+        match event {
+            SyncEvent::Local(local) => self.scanned_local = Some(local),
+            SyncEvent::Remote(remote) => self.scanned_remote = Some(remote),
+            SyncEvent::ScanFailed(err) => {
+                self.scanning = false;
+                return Some(err);
+            }
+            SyncEvent::Uploaded { checksum, id } => {
+                if let Some(run) = self.runs.iter_mut().find(|r| r.checksum == checksum) {
+                    let remote = RemoteRun {
+                        id,
+                        checksum: checksum.clone(),
+                        data_url: String::new(),
+                        motor: String::new(),
+                    };
+                    self.index.remote.push(remote.clone());
+                    run.remote = Some(remote);
+                    run.state = SyncState::Synced;
+                    run.error = None;
+                }
+                self.persist();
+            }
+            SyncEvent::Downloaded { checksum, path } => {
+                let local = LocalRun::from_path(path, checksum.clone());
+                if let Some(run) = self.runs.iter_mut().find(|r| r.checksum == checksum) {
+                    match local {
+                        Ok(local) => {
+                            self.index.local.push(local.clone());
+                            run.local = Some(local);
+                            run.state = SyncState::Synced;
+                            run.error = None;
+                        }
+                        Err(err) => {
+                            run.state = SyncState::Failed;
+                            run.error = Some(err.to_string());
+                        }
+                    }
+                }
+                self.persist();
+            }
+            SyncEvent::Failed { checksum, err } => {
+                if let Some(run) = self.runs.iter_mut().find(|r| r.checksum == checksum) {
+                    run.state = SyncState::Failed;
+                    run.error = Some(err.to_string());
+                }
+            }
+        }
+
+        if let (Some(_), Some(_)) = (&self.scanned_local, &self.scanned_remote) {
+            let local = self.scanned_local.take().unwrap_or_default();
+            let remote = self.scanned_remote.take().unwrap_or_default();
+            self.runs = merge(&local, &remote);
+            self.index = SyncIndex {
+                last_sync: Some(Utc::now().naive_local()),
+                local,
+                remote,
+            };
+            self.persist();
+            self.scanning = false;
+            self.transferring = true;
+        }
+        None
+    }
+
+    /// next upload or download of the running sync, the run is marked as transferring.
+    pub fn take_next(&mut self) -> Option<SyncTask> {
+        if !self.transferring {
+            return None;
+        }
+        let in_flight = self
+            .runs
+            .iter()
+            .filter(|r| matches!(r.state, SyncState::Uploading | SyncState::Downloading))
+            .count();
+        let next = self
+            .runs
+            .iter_mut()
+            .find(|r| matches!(r.state, SyncState::LocalOnly | SyncState::ServerOnly));
+        let run = match next {
+            Some(run) if in_flight < MAX_TRANSFERS => run,
+            Some(_) => return None,
+            None => {
+                self.transferring = in_flight > 0;
+                return None;
+            }
+        };
+        match (&run.local, &run.remote) {
+            (Some(local), _) => {
+                run.state = SyncState::Uploading;
+                Some(SyncTask::Upload(local.clone()))
+            }
+            (None, Some(remote)) => {
+                run.state = SyncState::Downloading;
+                let path = download_path(&self.dir, remote);
+                Some(SyncTask::Download(remote.clone(), path))
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+/// path of downloaded run, never overwrite the existing file.
+fn download_path(dir: &Path, remote: &RemoteRun) -> PathBuf {
+    let path = dir.join(format!("dynotest_server_{}.dyno", remote.id));
+    if !path.exists() {
+        return path;
+    }
+    let short = remote.checksum.get(..8).unwrap_or(&remote.checksum);
+    dir.join(format!("dynotest_server_{}_{short}.dyno", remote.id))
+}
+
+/// checksum of every `.dyno` file in `dir`, `cache` is used for the unchanged files.
+pub fn scan_local(dir: &Path, cache: &[LocalRun]) -> DynoResult<Vec<LocalRun>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut runs = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if DynoFileType::from_path(&path) != Some(DynoFileType::Dyno) {
+            continue;
+        }
+        let (size, modified) = LocalRun::metadata(&path)?;
+        let cached = cache
+            .iter()
+            .find(|c| c.path == path && c.size == size && c.modified == modified);
+        let checksum = match cached {
+            Some(cached) => cached.checksum.clone(),
+            None => checksum_from_bytes(&fs::read(&path)?),
+        };
+        runs.push(LocalRun {
+            path,
+            checksum,
+            size,
+            modified,
+        });
+    }
+    Ok(runs)
+}
+
+/// match the local and remote runs by checksum.
+fn merge(local: &[LocalRun], remote: &[RemoteRun]) -> Vec<SyncRun> {
+    let mut runs: Vec<SyncRun> = vec![];
+    for run in local {
+        if runs.iter().any(|r| r.checksum == run.checksum) {
+            continue;
+        }
+        let remote = remote.iter().find(|r| r.checksum == run.checksum).cloned();
+        runs.push(SyncRun {
+            checksum: run.checksum.clone(),
+            state: match remote {
+                Some(_) => SyncState::Synced,
+                None => SyncState::LocalOnly,
+            },
+            local: Some(run.clone()),
+            remote,
+            error: None,
+        });
+    }
+    for run in remote {
+        if runs.iter().any(|r| r.checksum == run.checksum) {
+            continue;
+        }
+        runs.push(SyncRun {
+            checksum: run.checksum.clone(),
+            local: None,
+            remote: Some(run.clone()),
+            state: SyncState::ServerOnly,
+            error: None,
+        });
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(name: &str, checksum: &str) -> LocalRun {
+        LocalRun {
+            path: PathBuf::from(name),
+            checksum: checksum.to_owned(),
+            size: 0,
+            modified: 0,
+        }
+    }
+
+    fn remote(id: i64, checksum: &str) -> RemoteRun {
+        RemoteRun {
+            id,
+            checksum: checksum.to_owned(),
+            data_url: String::new(),
+            motor: String::new(),
+        }
+    }
+
+    #[test]
+    fn merge_by_checksum() {
+        let runs = merge(
+            &[
+                local("a.dyno", "aa"),
+                local("b.dyno", "bb"),
+                local("c.dyno", "aa"),
+            ],
+            &[remote(1, "bb"), remote(2, "cc")],
+        );
+        let states = runs
+            .iter()
+            .map(|r| (r.checksum.as_str(), r.state))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            [
+                ("aa", SyncState::LocalOnly),
+                ("bb", SyncState::Synced),
+                ("cc", SyncState::ServerOnly)
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Local live telemetry server for second screens (TV, phone) in the waiting area.
+//!
+//! `GET /`            simple live view page
+//! `GET /ws`          WebSocket, every sample is sent as json text message
+//! `GET /api/live`    last sample as json
+//! `GET /api/summary` summary of the current run as json
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use dyno_core::{
+    log, serde,
+    tokio::{
+        self,
+        sync::{broadcast, oneshot},
+    },
+    Data, DynoErr, DynoResult,
+};
+use eframe::epaint::mutex::Mutex;
+
+use crate::formats::{summary::RunSummary, Record};
+
+const INDEX_HTML: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/assets/telemetry.html"
+));
+const SAMPLE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct TelemetrySample {
+    /// milliseconds unix timestamp.
+    pub time_stamp: i64,
+    pub speed: f64,
+    pub rpm_roda: f64,
+    pub rpm_engine: f64,
+    pub torque: f64,
+    pub horsepower: f64,
+    pub odo: f64,
+    pub temp: f64,
+}
+
+impl From<&Record> for TelemetrySample {
+    fn from(record: &Record) -> Self {
+        let [speed, rpm_roda, rpm_engine, torque, horsepower, odo, temp] = record.values;
+        Self {
+            time_stamp: record.time_stamp,
+            speed,
+            rpm_roda,
+            rpm_engine,
+            torque,
+            horsepower,
+            odo,
+            temp,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LiveRun {
+    start: Option<i64>,
+    last: Option<TelemetrySample>,
+    summary: RunSummary,
+}
+
+struct Shared {
+    samples: broadcast::Sender<TelemetrySample>,
+    run: Mutex<LiveRun>,
+}
+
+/// embedded HTTP/WebSocket server, fed by `DynoControl` on every `AsyncMsg::OnSerialData`.
+pub struct TelemetryService {
+    shared: Arc<Shared>,
+    running: Option<(SocketAddr, oneshot::Sender<()>)>,
+}
+
+impl Default for TelemetryService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TelemetryService {
+    pub fn new() -> Self {
+        let (samples, _) = broadcast::channel(SAMPLE_CAPACITY);
+        Self {
+            shared: Arc::new(Shared {
+                samples,
+                run: Mutex::new(LiveRun::default()),
+            }),
+            running: None,
+        }
+    }
+
+    /// start the server on `address`, must be called inside the tokio runtime.
+    pub fn start(&mut self, address: &str) -> DynoResult<SocketAddr> {
+        if let Some((addr, _)) = &self.running {
+            return Err(DynoErr::service_error(format!(
+                "Telemetry Server Already Running on {addr}"
+            )));
+        }
+        let addr = address.parse::<SocketAddr>().map_err(|err| {
+            DynoErr::validation_error(format!("invalid telemetry address `{address}` - {err}"))
+        })?;
+
+        let app = Router::new()
+            .route("/", get(index))
+            .route("/ws", get(websocket))
+            .route("/api/live", get(live))
+            .route("/api/summary", get(summary))
+            .with_state(self.shared.clone());
+        let server = axum::Server::try_bind(&addr)
+            .map_err(DynoErr::service_error)?
+            .serve(app.into_make_service());
+        let addr = server.local_addr();
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = server.with_graceful_shutdown(async {
+            let _ = shutdown_rx.await;
+        });
+        tokio::spawn(async move {
+            if let Err(err) = server.await {
+                log::error!("Telemetry Server stopped with error - {err}");
+            }
+        });
+        self.running = Some((addr, shutdown_tx));
+        log::info!("Telemetry Server listening on http://{addr}");
+        Ok(addr)
+    }
+
+    pub fn stop(&mut self) {
+        if let Some((addr, shutdown)) = self.running.take() {
+            let _ = shutdown.send(());
+            log::info!("Telemetry Server on {addr} stopped");
+        }
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.running.is_some()
+    }
+
+    #[inline]
+    pub fn address(&self) -> Option<SocketAddr> {
+        self.running.as_ref().map(|(addr, _)| *addr)
+    }
+
+    /// number of connected WebSocket clients.
+    #[inline]
+    pub fn clients(&self) -> usize {
+        self.shared.samples.receiver_count()
+    }
+
+    /// send the last sample to connected clients, and update the run summary.
+    pub fn publish(&self, data: &Data) {
+        if !self.is_running() {
+            return;
+        }
+        let record = Record::from_data(data);
+        let sample = TelemetrySample::from(&record);
+        {
+            let mut run = self.shared.run.lock();
+            let start = *run.start.get_or_insert(record.time_stamp);
+            run.summary.push(&record, start);
+            run.last = Some(sample);
+        }
+        // error only when there is no client connected.
+        let _ = self.shared.samples.send(sample);
+    }
+
+    /// clear the run summary, when the recorded buffer is reset.
+    pub fn reset(&self) {
+        *self.shared.run.lock() = LiveRun::default();
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn live(State(shared): State<Arc<Shared>>) -> Json<Option<TelemetrySample>> {
+    Json(shared.run.lock().last)
+}
+
+async fn summary(State(shared): State<Arc<Shared>>) -> Json<RunSummary> {
+    Json(shared.run.lock().summary)
+}
+
+async fn websocket(ws: WebSocketUpgrade, State(shared): State<Arc<Shared>>) -> Response {
+    let samples = shared.samples.subscribe();
+    ws.on_upgrade(move |socket| stream_samples(socket, samples))
+        .into_response()
+}
+
+async fn stream_samples(mut socket: WebSocket, mut samples: broadcast::Receiver<TelemetrySample>) {
+    loop {
+        let sample = match samples.recv().await {
+            Ok(sample) => sample,
+            // slow client, skip the old samples.
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let text = match serde_json::to_string(&sample) {
+            Ok(text) => text,
+            Err(err) => {
+                log::error!("Failed to serialize telemetry sample - {err}");
+                continue;
+            }
+        };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
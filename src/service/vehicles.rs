@@ -0,0 +1,286 @@
+//! Local database of customers and their vehicles, selecting a vehicle before a run fill the
+//! `DynoConfig` with its motor spec and roller constants, every saved run is linked to it.
+use std::{fs, path::PathBuf};
+
+use dyno_core::{
+    chrono::{NaiveDateTime, Utc},
+    serde, toml, DynoConfig, DynoResult, MotorType,
+};
+
+use crate::paths::DynoPaths;
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+pub struct Customer {
+    pub id: String,
+    pub name: String,
+    pub phone: String,
+    pub email: String,
+}
+
+/// roller constants of the dyno that override the `DynoConfig` for a vehicle, for example
+/// a different roller or load for big bikes.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+pub struct RollerConstants {
+    /// metres
+    pub diameter_roller: f64,
+    /// metres
+    pub diameter_roller_beban: f64,
+    /// metres
+    pub diameter_gear_encoder: f64,
+    /// metres
+    pub diameter_gear_beban: f64,
+    /// metres
+    pub jarak_gear: f64,
+    /// kilogram
+    pub berat_beban: f64,
+}
+
+impl Default for RollerConstants {
+    fn default() -> Self {
+        Self::from_config(&DynoConfig::default())
+    }
+}
+
+impl RollerConstants {
+    pub fn from_config(config: &DynoConfig) -> Self {
+        Self {
+            diameter_roller: config.diameter_roller.value(),
+            diameter_roller_beban: config.diameter_roller_beban.value(),
+            diameter_gear_encoder: config.diameter_gear_encoder.value(),
+            diameter_gear_beban: config.diameter_gear_beban.value(),
+            jarak_gear: config.jarak_gear.value(),
+            berat_beban: config.berat_beban.value(),
+        }
+    }
+
+    pub fn apply(&self, config: &mut DynoConfig) {
+        *config.diameter_roller.value_mut() = self.diameter_roller;
+        *config.diameter_roller_beban.value_mut() = self.diameter_roller_beban;
+        *config.diameter_gear_encoder.value_mut() = self.diameter_gear_encoder;
+        *config.diameter_gear_beban.value_mut() = self.diameter_gear_beban;
+        *config.jarak_gear.value_mut() = self.jarak_gear;
+        *config.berat_beban.value_mut() = self.berat_beban;
+    }
+}
+
+/// run of the vehicle, saved to a local file or uploaded to server.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(crate = "serde")]
+pub struct VehicleRun {
+    pub time: NaiveDateTime,
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub server_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+pub struct Vehicle {
+    pub id: String,
+    /// id of the [`Customer`] that own the vehicle.
+    pub owner: String,
+    pub plate: String,
+    pub motor: MotorType,
+    /// `None` to use the roller constants of the current `DynoConfig`.
+    pub rollers: Option<RollerConstants>,
+    pub notes: String,
+    pub runs: Vec<VehicleRun>,
+}
+
+impl Default for Vehicle {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            owner: String::new(),
+            plate: String::new(),
+            motor: DynoConfig::default().motor_type,
+            rollers: None,
+            notes: String::new(),
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl Vehicle {
+    /// fill the `config` for the run of this vehicle.
+    pub fn apply(&self, config: &mut DynoConfig) {
+        config.motor_type = self.motor.clone();
+        if let Some(rollers) = &self.rollers {
+            rollers.apply(config);
+        }
+    }
+
+    /// `true` if `query` is in the plate or the motor name, case insensitive.
+    pub fn matches(&self, query: &str, owner: Option<&Customer>) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.plate.to_lowercase().contains(&query)
+            || self.motor.to_string().to_lowercase().contains(&query)
+            || owner.map_or(false, |c| c.name.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default, crate = "serde")]
+struct VehicleIndex {
+    customers: Vec<Customer>,
+    vehicles: Vec<Vehicle>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct VehicleDb {
+    path: PathBuf,
+    customers: Vec<Customer>,
+    vehicles: Vec<Vehicle>,
+}
+
+#[inline]
+fn new_id() -> String {
+    dyno_core::uuid::Uuid::new_v4().simple().to_string()
+}
+
+impl VehicleDb {
+    pub const FILE_NAME: &'static str = "vehicles.toml";
+
+    /// load the database from `vehicles.toml` in data dir.
+    pub fn load(paths: &DynoPaths) -> Self {
+        let path = paths.get_data_dir_file(Self::FILE_NAME);
+        let index = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| match toml::from_str::<VehicleIndex>(&s) {
+                Ok(index) => Some(index),
+                Err(err) => {
+                    dyno_core::log::error!("Failed to read vehicle database - {err}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            customers: index.customers,
+            vehicles: index.vehicles,
+        }
+    }
+
+    pub fn persist(&self) -> DynoResult<()> {
+        let index = VehicleIndex {
+            customers: self.customers.clone(),
+            vehicles: self.vehicles.clone(),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let data = toml::to_string(&index)?;
+        fs::write(&self.path, data).map_err(From::from)
+    }
+
+    #[inline]
+    pub fn customers(&self) -> &[Customer] {
+        &self.customers
+    }
+
+    #[inline]
+    pub fn customers_mut(&mut self) -> &mut [Customer] {
+        &mut self.customers
+    }
+
+    #[inline]
+    pub fn vehicles(&self) -> &[Vehicle] {
+        &self.vehicles
+    }
+
+    #[inline]
+    pub fn vehicles_mut(&mut self) -> &mut [Vehicle] {
+        &mut self.vehicles
+    }
+
+    #[inline]
+    pub fn customer(&self, id: &str) -> Option<&Customer> {
+        self.customers.iter().find(|c| c.id == id)
+    }
+
+    #[inline]
+    pub fn vehicle(&self, id: &str) -> Option<&Vehicle> {
+        self.vehicles.iter().find(|v| v.id == id)
+    }
+
+    /// add a new customer, returns its id.
+    pub fn add_customer(&mut self, name: impl ToString) -> String {
+        let id = new_id();
+        self.customers.push(Customer {
+            id: id.clone(),
+            name: name.to_string(),
+            ..Default::default()
+        });
+        id
+    }
+
+    /// add a new vehicle of `owner` with the motor of `config`, returns its id.
+    pub fn add_vehicle(&mut self, owner: &str, config: &DynoConfig) -> String {
+        let id = new_id();
+        self.vehicles.push(Vehicle {
+            id: id.clone(),
+            owner: owner.to_owned(),
+            motor: config.motor_type.clone(),
+            ..Default::default()
+        });
+        id
+    }
+
+    /// remove the customer and all of its vehicles.
+    pub fn remove_customer(&mut self, id: &str) {
+        self.customers.retain(|c| c.id != id);
+        self.vehicles.retain(|v| v.owner != id);
+    }
+
+    pub fn remove_vehicle(&mut self, id: &str) {
+        self.vehicles.retain(|v| v.id != id);
+    }
+
+    /// link the run to the vehicle `id`, and save the database.
+    pub fn add_run(&mut self, id: &str, path: Option<PathBuf>, server_id: Option<i32>) {
+        let vehicle = match self.vehicles.iter_mut().find(|v| v.id == id) {
+            Some(vehicle) => vehicle,
+            None => return,
+        };
+        vehicle.runs.push(VehicleRun {
+            time: Utc::now().naive_local(),
+            path,
+            server_id,
+        });
+        if let Err(err) = self.persist() {
+            dyno_core::log::error!("Failed to write vehicle database - {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vehicle_fill_config() {
+        let mut db = VehicleDb::default();
+        let owner = db.add_customer("Budi");
+        let mut config = DynoConfig::default();
+        let id = db.add_vehicle(&owner, &config);
+
+        let rollers = RollerConstants {
+            berat_beban: 42.0,
+            ..RollerConstants::from_config(&config)
+        };
+        db.vehicles_mut()[0].rollers = Some(rollers);
+        db.vehicle(&id).unwrap().apply(&mut config);
+        assert_eq!(RollerConstants::from_config(&config), rollers);
+        assert!(db
+            .vehicle(&id)
+            .unwrap()
+            .matches("budi", db.customer(&owner)));
+
+        db.remove_customer(&owner);
+        assert!(db.vehicles().is_empty());
+    }
+}
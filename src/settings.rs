@@ -0,0 +1,164 @@
+//! Backup of the application settings in one human readable TOML file, to copy the settings
+//! to another PC. Every section is optional, so a file with only some of the sections can be
+//! imported. Only files of [`SETTINGS_VERSION`] are imported.
+use std::path::Path;
+
+use dyno_core::{
+    serde::{de::DeserializeOwned, Serialize},
+    toml::{self, value::Table, Value},
+    DynoErr, DynoResult,
+};
+
+use crate::tr;
+
+/// version of the settings file schema, increased on every incompatible change.
+pub const SETTINGS_VERSION: i64 = 1;
+
+const VERSION_KEY: &str = "version";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsSection {
+    /// [`crate::config::ApplicationConfig`]
+    App,
+    /// [`crate::paths::DynoPaths`]
+    Paths,
+    /// [`dyno_core::DynoConfig`]
+    Dyno,
+    /// [`crate::widgets::RealtimePlot`]
+    Plot,
+}
+
+impl SettingsSection {
+    pub const ALL: [Self; 4] = [Self::App, Self::Paths, Self::Dyno, Self::Plot];
+
+    /// key of the section in the settings file.
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::Paths => "paths",
+            Self::Dyno => "dyno",
+            Self::Plot => "plot",
+        }
+    }
+}
+
+impl std::fmt::Display for SettingsSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::App => tr!("Application"),
+            Self::Paths => tr!("Paths"),
+            Self::Dyno => tr!("Dyno Config"),
+            Self::Plot => tr!("Plot"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsFile {
+    table: Table,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        let mut table = Table::new();
+        table.insert(VERSION_KEY.to_owned(), Value::Integer(SETTINGS_VERSION));
+        Self { table }
+    }
+}
+
+impl SettingsFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add `value` as the `section` of the file.
+    pub fn with<T: Serialize>(mut self, section: SettingsSection, value: &T) -> DynoResult<Self> {
+        let value = Value::try_from(value).map_err(|err| {
+            DynoErr::input_output_error(format!("Failed to write settings `{section}` - {err}"))
+        })?;
+        self.table.insert(section.key().to_owned(), value);
+        Ok(self)
+    }
+
+    /// the `section` of the file, `None` if the file doesn't have the section.
+    pub fn get<T: DeserializeOwned>(&self, section: SettingsSection) -> DynoResult<Option<T>> {
+        match self.table.get(section.key()) {
+            Some(value) => value.clone().try_into().map(Some).map_err(|err| {
+                DynoErr::input_error(format!("Invalid settings `{section}` - {err}"))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// sections in the file.
+    pub fn sections(&self) -> impl Iterator<Item = SettingsSection> + '_ {
+        SettingsSection::ALL
+            .into_iter()
+            .filter(|s| self.table.contains_key(s.key()))
+    }
+
+    /// parse the settings file, refused if the file is not of the current version.
+    pub fn parse(data: &str) -> DynoResult<Self> {
+        let table = match toml::from_str::<Value>(data)? {
+            Value::Table(table) => table,
+            _ => return Err(DynoErr::input_error("Settings file is not a TOML table")),
+        };
+        match table.get(VERSION_KEY) {
+            Some(Value::Integer(SETTINGS_VERSION)) => Ok(Self { table }),
+            Some(Value::Integer(version)) if *version > SETTINGS_VERSION => {
+                Err(DynoErr::input_error(format!(
+                    "Settings file version {version} is newer than this application (version {SETTINGS_VERSION})"
+                )))
+            }
+            _ => Err(DynoErr::input_error("Invalid version of settings file")),
+        }
+    }
+
+    pub fn to_toml(&self) -> DynoResult<String> {
+        // tables are written after the values by `Value`, required by TOML.
+        toml::to_string_pretty(&Value::Table(self.table.clone())).map_err(From::from)
+    }
+
+    pub fn save(&self, path: &Path) -> DynoResult<()> {
+        std::fs::write(path, self.to_toml()?).map_err(From::from)
+    }
+
+    pub fn open(path: &Path) -> DynoResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dyno_core::DynoConfig;
+
+    #[test]
+    fn versions_and_roundtrip() {
+        let config = DynoConfig::default();
+        let file = SettingsFile::new()
+            .with(SettingsSection::Dyno, &config)
+            .unwrap();
+        let parsed = SettingsFile::parse(&file.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed, file);
+        assert_eq!(
+            parsed.sections().collect::<Vec<_>>(),
+            [SettingsSection::Dyno]
+        );
+        assert!(parsed
+            .get::<DynoConfig>(SettingsSection::Paths)
+            .unwrap()
+            .is_none());
+
+        // a file without a version is not a settings file.
+        let mut unversioned = Table::new();
+        unversioned.insert("dyno".to_owned(), Value::try_from(&config).unwrap());
+        assert!(
+            SettingsFile::parse(&toml::to_string(&Value::Table(unversioned)).unwrap()).is_err()
+        );
+        assert!(SettingsFile::parse("version = 0").is_err());
+
+        let newer = format!("version = {}", SETTINGS_VERSION + 1);
+        assert!(SettingsFile::parse(&newer).is_err());
+    }
+}
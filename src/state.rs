@@ -1,12 +1,15 @@
 use dyno_core::AsStr;
 use dyno_core::{paste::paste, serde};
 
-#[derive(Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 #[serde(crate = "serde")]
 pub enum DynoFileType {
     Dyno,
     Csv,
     Excel,
+    Json,
+    Parquet,
+    Mdf,
 }
 
 impl std::fmt::Display for DynoFileType {
@@ -22,6 +25,9 @@ impl AsStr<'static> for DynoFileType {
             DynoFileType::Dyno => "Binaries",
             DynoFileType::Csv => "Csv",
             DynoFileType::Excel => "Excel",
+            DynoFileType::Json => "Json",
+            DynoFileType::Parquet => "Parquet",
+            DynoFileType::Mdf => "Mdf",
         }
     }
 }
@@ -33,11 +39,30 @@ impl DynoFileType {
     {
         parent.as_ref().join(self.as_str())
     }
+    pub const fn extension(self) -> &'static str {
+        match self {
+            DynoFileType::Dyno => "dyno",
+            DynoFileType::Csv => "csv",
+            DynoFileType::Excel => "xlsx",
+            DynoFileType::Json => "jsonl",
+            DynoFileType::Parquet => "parquet",
+            DynoFileType::Mdf => "mf4",
+        }
+    }
+    /// file type from the extension of `path`, case insensitive.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| Self::from_extension(&ext.to_string_lossy().to_lowercase()))
+    }
     pub fn from_extension(ext: &str) -> Option<Self> {
         match ext {
             "dyno" | "dbin" => Some(Self::Dyno),
             "csv" | "dynocsv" => Some(Self::Csv),
             "xlsx" => Some(Self::Excel),
+            "jsonl" | "ndjson" | "json" => Some(Self::Json),
+            "parquet" => Some(Self::Parquet),
+            "mf4" | "mdf" => Some(Self::Mdf),
             _ => None,
         }
     }
@@ -50,6 +75,9 @@ pub enum OperatorData {
     Noop,
     SaveFile(DynoFileType),
     OpenFile(DynoFileType),
+    /// open a file from known path (dropped file, recent files, command line),
+    /// the path is kept in `DynoControl`.
+    OpenPath,
 }
 impl OperatorData {
     pub fn save_default() -> Self {
@@ -112,24 +140,6 @@ impl DynoState {
     }
 }
 
-impl DynoState {
-    pub fn key_events(&mut self, ctx: &eframe::egui::Context) {
-        use eframe::egui::{Key, Modifiers};
-        ctx.input_mut(|i| {
-            if i.consume_key(
-                Modifiers {
-                    ctrl: true,
-                    shift: true,
-                    ..Default::default()
-                },
-                Key::S,
-            ) {
-                self.operator = OperatorData::save_default();
-            }
-        });
-    }
-}
-
 macro_rules! impl_cond_and {
     ($($name:ident: $tp:ty => $def:expr),* $(,)?) => {
         impl DynoState {
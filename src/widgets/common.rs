@@ -1,6 +1,8 @@
 use dyno_core::derive_more::Display;
 
 use eframe::egui::{pos2, Color32, Pos2, Rgba, Stroke, Vec2};
+
+use crate::tr;
 // ----------------------------------------------------------------------------
 /// Anchor where to show toasts
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -484,7 +486,7 @@ impl DynoWidgets for eframe::egui::Ui {
             .map(|value| self.selectable_value(current_value, value, format!("{value}")))
             .reduce(|result, response| result.union(response))
             .unwrap_or_else(|| {
-                self.colored_label(self.style().visuals.error_fg_color, "\u{1F525} No items")
+                self.colored_label(self.style().visuals.error_fg_color, tr!("🔥 No items"))
             })
     }
 
@@ -500,7 +502,7 @@ impl DynoWidgets for eframe::egui::Ui {
             .map(|value| self.radio_value(current_value, value, format!("{value}")))
             .reduce(|result, response| result.union(response))
             .unwrap_or_else(|| {
-                self.colored_label(self.style().visuals.error_fg_color, "\u{1F525} No items")
+                self.colored_label(self.style().visuals.error_fg_color, tr!("🔥 No items"))
             })
     }
 
@@ -520,7 +522,7 @@ impl DynoWidgets for eframe::egui::Ui {
                     .map(|value| ui.selectable_value(current_value, value, format!("{value}")))
                     .reduce(|result, response| result.union(response))
                     .unwrap_or_else(|| {
-                        ui.colored_label(ui.style().visuals.error_fg_color, "\u{1F525} No items")
+                        ui.colored_label(ui.style().visuals.error_fg_color, tr!("🔥 No items"))
                     })
             });
         combobox_response
@@ -541,7 +543,7 @@ impl DynoWidgets for eframe::egui::Ui {
             .map(|value| self.selectable_value(current_value, value.clone(), format!("{value}")))
             .reduce(|result, response| result.union(response))
             .unwrap_or_else(|| {
-                self.colored_label(self.style().visuals.error_fg_color, "\u{1F525} No items")
+                self.colored_label(self.style().visuals.error_fg_color, tr!("🔥 No items"))
             })
     }
     fn radio_value_from_slice<V>(
@@ -557,7 +559,7 @@ impl DynoWidgets for eframe::egui::Ui {
             .map(|value| self.radio_value(current_value, value.clone(), format!("{value}")))
             .reduce(|result, response| result.union(response))
             .unwrap_or_else(|| {
-                self.colored_label(self.style().visuals.error_fg_color, "\u{1F525} No items")
+                self.colored_label(self.style().visuals.error_fg_color, tr!("🔥 No items"))
             })
     }
     fn combobox_from_slice<'s, V>(
@@ -577,7 +579,7 @@ impl DynoWidgets for eframe::egui::Ui {
                     .map(|value| ui.selectable_value(current_value, value.clone(), value.as_str()))
                     .reduce(|result, response| result.union(response))
                     .unwrap_or_else(|| {
-                        ui.colored_label(ui.style().visuals.error_fg_color, "\u{1F525} No items")
+                        ui.colored_label(ui.style().visuals.error_fg_color, tr!("🔥 No items"))
                     })
             });
         combobox.inner.unwrap_or(combobox.response)
@@ -597,7 +599,7 @@ impl DynoWidgets for eframe::egui::Ui {
             .map(|(idx, item)| self.toggle_value(item, label(idx)))
             .reduce(|result, response| result.union(response))
             .unwrap_or_else(|| {
-                self.colored_label(self.style().visuals.error_fg_color, "\u{1F525} No items")
+                self.colored_label(self.style().visuals.error_fg_color, tr!("🔥 No items"))
             })
     }
 
@@ -21,27 +21,27 @@ pub use common::*;
 #[macro_export]
 macro_rules! row_label_value {
     ($ui:ident, $v:ident, $name:literal, $desc:literal $(,)?) => {
-        $ui.link($name).on_hover_ui(|hover_ui| {
-            hover_ui.label(format!(
-                "Value for `{}` type of `{}`",
-                $name,
-                $v.name_type()
+        $ui.link($crate::tr!($name)).on_hover_ui(|hover_ui| {
+            hover_ui.label($crate::tr!(
+                "Value for `{name}` type of `{kind}`",
+                name = $crate::tr!($name),
+                kind = $v.name_type()
             ));
-            hover_ui.monospace($desc);
+            hover_ui.monospace($crate::tr!($desc));
         });
         $ui.label($v.to_string());
     };
     ($ui:ident, $v:expr, $name:literal, $desc:literal $(,)?) => {
-        $ui.link($name).on_hover_ui(|hover_ui| {
-            hover_ui.label(concat!("Value for ", $name));
-            hover_ui.monospace($desc);
+        $ui.link($crate::tr!($name)).on_hover_ui(|hover_ui| {
+            hover_ui.label($crate::tr!("Value for {name}", name = $crate::tr!($name)));
+            hover_ui.monospace($crate::tr!($desc));
         });
         $ui.add($v)
     };
     ($ui:ident => $v:expr, $name:literal, $desc:literal $(,)?) => {
-        $ui.link($name).on_hover_ui(|hover_ui| {
-            hover_ui.label(concat!("Value for ", $name));
-            hover_ui.monospace($desc);
+        $ui.link($crate::tr!($name)).on_hover_ui(|hover_ui| {
+            hover_ui.label($crate::tr!("Value for {name}", name = $crate::tr!($name)));
+            hover_ui.monospace($crate::tr!($desc));
         });
         $v
     };
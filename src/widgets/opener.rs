@@ -191,8 +191,15 @@ impl_file_picker!(
         ("Binaries File",       &["bin"]),
         ("Csv File",            &["csv", "dcsv"]),
         ("Excel File",          &["xlsx", "xls"]),
+        ("Json Lines File",     &["jsonl", "ndjson", "json"]),
+        ("Parquet File",        &["parquet"]),
+        ("MDF4 File",           &["mf4", "mdf"]),
     ],
     binaries    -> [("Dyno Binaries File", &["dyno", "dbin"]), ("Binaries File", &["bin"])],
     csv         -> [("Csv File",      &["csv", "dcsv"])],
-    excel       -> [("Excel File",    &["xlsx", "xls"])]
+    excel       -> [("Excel File",    &["xlsx", "xls"])],
+    json        -> [("Json Lines File", &["jsonl", "ndjson", "json"])],
+    parquet     -> [("Parquet File",  &["parquet"])],
+    mdf         -> [("MDF4 File",     &["mf4", "mdf"])],
+    toml        -> [("Toml File",     &["toml"])]
 );
@@ -1,3 +1,5 @@
+use crate::formats::{markers::Markers, records, Record};
+use crate::tr;
 use dyno_core::{serde, AsStr, BufferData, PointShowed};
 use eframe::egui::*;
 use std::hash::Hash;
@@ -15,17 +17,58 @@ pub enum PlotPanel {
 impl AsStr<'static> for PlotPanel {
     fn as_str(&self) -> &'static str {
         match self {
-            PlotPanel::All => "All Plot",
-            PlotPanel::First => "First Plot",
-            PlotPanel::Second => "Second Plot",
+            PlotPanel::All => tr!("All Plot"),
+            PlotPanel::First => tr!("First Plot"),
+            PlotPanel::Second => tr!("Second Plot"),
         }
     }
 }
+impl PlotPanel {
+    pub const fn next(self) -> Self {
+        match self {
+            PlotPanel::All => PlotPanel::First,
+            PlotPanel::First => PlotPanel::Second,
+            PlotPanel::Second => PlotPanel::All,
+        }
+    }
+}
+
+/// other run overlaid on the plots for comparison, drawn with dotted lines.
+#[derive(Debug, Clone)]
+pub struct CompareRun {
+    pub name: String,
+    pub records: Vec<Record>,
+    pub visible: bool,
+}
+
+impl CompareRun {
+    fn line(&self, idx: usize, scale: f64, name: &str) -> plot::Line {
+        let first = self
+            .records
+            .first()
+            .map(|r| r.time_stamp)
+            .unwrap_or_default();
+        let points = self
+            .records
+            .iter()
+            .map(|r| [(r.time_stamp - first) as f64, r.values[idx] * scale])
+            .collect::<plot::PlotPoints>();
+        plot::Line::new(points)
+            .width(2.0)
+            .style(plot::LineStyle::dotted_dense())
+            .name(format!("{name} [{}]", self.name))
+    }
+}
 
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(crate = "serde")]
 pub struct RealtimePlot {
+    // constant ids, not saved, a hashed `Id` doesn't fit in the TOML integer of settings file.
+    #[serde(skip)]
+    #[serde(default = "RealtimePlot::group_id")]
     group: Id,
+    #[serde(skip)]
+    #[serde(default = "RealtimePlot::cursor_group_id")]
     cursor_group: Id,
 
     first_showed_x: i64,
@@ -40,15 +83,16 @@ pub struct RealtimePlot {
     allow_scroll: bool,
     allow_boxed_zoom: bool,
     animates: bool,
+
+    #[serde(skip)]
+    compares: Vec<CompareRun>,
 }
 
 impl Default for RealtimePlot {
     fn default() -> Self {
-        let group = Id::new("dyno_plot_group_id");
-        let cursor_group = Id::new("dyno_plot_cursor_group_id");
         Self {
-            group,
-            cursor_group,
+            group: Self::group_id(),
+            cursor_group: Self::cursor_group_id(),
             panel: Default::default(),
             showed: PointShowed::default(),
             first_showed_x: 0,
@@ -60,6 +104,7 @@ impl Default for RealtimePlot {
             allow_scroll: true,
             allow_boxed_zoom: true,
             animates: false,
+            compares: vec![],
         }
     }
 }
@@ -68,6 +113,37 @@ impl RealtimePlot {
     pub fn new() -> Self {
         Self::default()
     }
+
+    fn group_id() -> Id {
+        Id::new("dyno_plot_group_id")
+    }
+
+    fn cursor_group_id() -> Id {
+        Id::new("dyno_plot_cursor_group_id")
+    }
+
+    pub fn add_compare(&mut self, name: impl ToString, data: &BufferData) {
+        self.compares.push(CompareRun {
+            name: name.to_string(),
+            records: records(data),
+            visible: true,
+        });
+    }
+
+    #[inline]
+    pub fn compares(&self) -> &[CompareRun] {
+        &self.compares
+    }
+
+    #[inline]
+    pub fn clear_compares(&mut self) {
+        self.compares.clear();
+    }
+
+    #[inline]
+    pub fn next_panel(&mut self) {
+        self.panel = self.panel.next();
+    }
 }
 
 impl RealtimePlot {
@@ -76,23 +152,26 @@ impl RealtimePlot {
         background_alpha: 0.75,
         position: plot::Corner::RightTop,
     };
+    const MARKER_COLOR: Color32 = Color32::from_rgb(255, 140, 0);
 
-    pub fn ui(&mut self, ui: &mut Ui, data: &'_ BufferData) -> Response {
+    pub fn ui(&mut self, ui: &mut Ui, data: &'_ BufferData, markers: &Markers) -> Response {
         if self.animates {
             ui.ctx().request_repaint();
         }
         ui.horizontal(|ui| {
-            ui.collapsing("Instructions", |ui| {
-                ui.label("Pan by dragging, or scroll (+ shift = horizontal).");
-                ui.label(
-                    "Box zooming: Middle (Scroll) click to zoom in and zoom out using a selection.",
-                );
-                ui.label("Zoom with ctrl + scroll.");
-                ui.label("Reset view with double-click.");
-                ui.label("Change behaviour with context menu right click in the plot");
+            ui.collapsing(tr!("Instructions"), |ui| {
+                ui.label(tr!("Pan by dragging, or scroll (+ shift = horizontal)."));
+                ui.label(tr!(
+                    "Box zooming: Middle (Scroll) click to zoom in and zoom out using a selection."
+                ));
+                ui.label(tr!("Zoom with ctrl + scroll."));
+                ui.label(tr!("Reset view with double-click."));
+                ui.label(tr!(
+                    "Change behaviour with context menu right click in the plot"
+                ));
             });
             ui.with_layout(Layout::right_to_left(Align::Min), |left_ui| {
-                ComboBox::new("plot_panel_combobox", "Select Plot to Show")
+                ComboBox::new("plot_panel_combobox", tr!("Select Plot to Show"))
                     .selected_text(self.panel.as_str())
                     .show_ui(left_ui, |ui| {
                         ui.selectable_value(
@@ -111,15 +190,34 @@ impl RealtimePlot {
                             PlotPanel::Second.as_str(),
                         );
                     });
-                left_ui.menu_button("Plot Config", |cfg_ui| {
+                if !self.compares.is_empty() {
+                    left_ui.menu_button(tr!("Compare"), |cmp_ui| {
+                        self.compares.retain_mut(|run| {
+                            cmp_ui
+                                .horizontal(|ui| {
+                                    ui.checkbox(&mut run.visible, run.name.as_str());
+                                    !ui.small_button("\u{2716}")
+                                        .on_hover_text(tr!("Remove"))
+                                        .clicked()
+                                })
+                                .inner
+                        });
+                        cmp_ui.separator();
+                        if cmp_ui.button(tr!("Clear All")).clicked() {
+                            self.compares.clear();
+                            cmp_ui.close_menu();
+                        }
+                    });
+                }
+                left_ui.menu_button(tr!("Plot Config"), |cfg_ui| {
                     Grid::new("button_grid_plot").show(cfg_ui, |gridui| {
-                        gridui.checkbox(&mut self.allow_drag, "Drag");
-                        gridui.checkbox(&mut self.allow_zoom, "Zoom");
+                        gridui.checkbox(&mut self.allow_drag, tr!("Drag"));
+                        gridui.checkbox(&mut self.allow_zoom, tr!("Zoom"));
                         gridui.end_row();
-                        gridui.checkbox(&mut self.allow_scroll, "Scroll");
-                        gridui.checkbox(&mut self.allow_boxed_zoom, "Boxed Zoom");
+                        gridui.checkbox(&mut self.allow_scroll, tr!("Scroll"));
+                        gridui.checkbox(&mut self.allow_boxed_zoom, tr!("Boxed Zoom"));
                         gridui.end_row();
-                        gridui.checkbox(&mut self.animates, "Animate");
+                        gridui.checkbox(&mut self.animates, tr!("Animate"));
                     });
                 })
             });
@@ -129,16 +227,20 @@ impl RealtimePlot {
         self.first_x = data.time_stamp.first_value();
         self.last_x = data.time_stamp.last_value();
         ui.horizontal(|ui| {
-            ComboBox::new("point_showed_combobox", "Points to Show")
+            ComboBox::new("point_showed_combobox", tr!("Points to Show"))
                 .selected_text(format!("{}", self.showed))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut self.showed, PointShowed::All, "All Points");
-                    ui.selectable_value(&mut self.showed, PointShowed::Half, "Half Points");
-                    ui.selectable_value(&mut self.showed, PointShowed::Quarter, "Quarter Points");
+                    ui.selectable_value(&mut self.showed, PointShowed::All, tr!("All Points"));
+                    ui.selectable_value(&mut self.showed, PointShowed::Half, tr!("Half Points"));
+                    ui.selectable_value(
+                        &mut self.showed,
+                        PointShowed::Quarter,
+                        tr!("Quarter Points"),
+                    );
                     let slider = ui.add(
                         Slider::new(&mut self.first_showed_x, 0..=(self.last_x - self.first_x))
                             .step_by(1000f64)
-                            .text("Show From")
+                            .text(tr!("Show From"))
                             .custom_formatter(|x, _| timestamp_diff_fmt(x as _)),
                     );
 
@@ -153,12 +255,37 @@ impl RealtimePlot {
                 });
         });
 
+        // taken out while drawing, the plot closures can't borrow `self`.
+        let compares = std::mem::take(&mut self.compares);
+        let visible_compares = || compares.iter().filter(|run| run.visible);
+        // event markers as vertical lines, with the text on top of the plot.
+        let first_x = self.first_x;
+        let draw_markers = |pui: &mut plot::PlotUi| {
+            let top = pui.plot_bounds().max()[1];
+            for marker in markers.iter() {
+                let x = (marker.time_stamp - first_x) as f64;
+                pui.vline(
+                    plot::VLine::new(x)
+                        .color(Self::MARKER_COLOR)
+                        .width(1.5)
+                        .style(plot::LineStyle::dashed_loose()),
+                );
+                if !marker.text.is_empty() {
+                    pui.text(
+                        plot::Text::new(plot::PlotPoint::new(x, top), marker.text.as_str())
+                            .color(Self::MARKER_COLOR)
+                            .anchor(Align2::LEFT_TOP),
+                    );
+                }
+            }
+        };
+
         let draw_plot_first = |pui: &mut plot::PlotUi| {
             pui.line(
                 plot::Line::new(data.speed.into_points::<plot::PlotPoints>(showed))
                     .width(3.0)
                     .style(plot::LineStyle::Solid)
-                    .name("Speed (km/h)"),
+                    .name(tr!("Speed (km/h)")),
             );
             pui.line(
                 plot::Line::new(
@@ -167,27 +294,33 @@ impl RealtimePlot {
                 )
                 .width(3.0)
                 .style(plot::LineStyle::dashed_dense())
-                .name("RPM (engine) (rpm x 1000)"),
+                .name(tr!("RPM (engine) (rpm x 1000)")),
             );
             pui.line(
                 plot::Line::new(data.temp.into_points::<plot::PlotPoints>(showed))
                     .width(3.0)
                     .style(plot::LineStyle::dashed_loose())
-                    .name("Temp (°C)"),
+                    .name(tr!("Temp (°C)")),
             );
+            for run in visible_compares() {
+                pui.line(run.line(0, 1.0, tr!("Speed (km/h)")));
+                pui.line(run.line(2, 0.001, tr!("RPM (engine) (rpm x 1000)")));
+                pui.line(run.line(6, 1.0, tr!("Temp (°C)")));
+            }
+            draw_markers(pui);
         };
         let draw_plot_second = |pui: &mut plot::PlotUi| {
             pui.line(
                 plot::Line::new(data.torque.into_points::<plot::PlotPoints>(showed))
                     .width(3.0)
                     .style(plot::LineStyle::Solid)
-                    .name("Torque (Nm)"),
+                    .name(tr!("Torque (Nm)")),
             );
             pui.line(
                 plot::Line::new(data.horsepower.into_points::<plot::PlotPoints>(showed))
                     .width(3.0)
                     .style(plot::LineStyle::Solid)
-                    .name("HorsePower (HP)"),
+                    .name(tr!("HorsePower (HP)")),
             );
             pui.line(
                 plot::Line::new(
@@ -196,29 +329,38 @@ impl RealtimePlot {
                 )
                 .width(3.0)
                 .style(plot::LineStyle::dashed_dense())
-                .name("RPM (roda) (rpm x 1000)"),
+                .name(tr!("RPM (tire) (rpm x 1000)")),
             );
+            for run in visible_compares() {
+                pui.line(run.line(3, 1.0, tr!("Torque (Nm)")));
+                pui.line(run.line(4, 1.0, tr!("HorsePower (HP)")));
+                pui.line(run.line(1, 0.001, tr!("RPM (tire) (rpm x 1000)")));
+            }
+            draw_markers(pui);
         };
 
-        ui.vertical_centered(|ui| {
-            let width = ui.available_width() - (ui.spacing().item_spacing.x * 2.0);
-            let height = ui.available_height() - (ui.spacing().item_spacing.y * 2.0);
-            match self.panel {
-                PlotPanel::All => {
-                    let height = height * 0.5;
-                    self.draw_plot(ui, "dyno_plot_first", height, width, draw_plot_first);
-                    ui.separator();
-                    self.draw_plot(ui, "dyno_plot_second", height, width, draw_plot_second)
-                }
-                PlotPanel::First => {
-                    self.draw_plot(ui, "dyno_plot_first", height, width, draw_plot_first)
-                }
-                PlotPanel::Second => {
-                    self.draw_plot(ui, "dyno_plot_second", height, width, draw_plot_second)
+        let response = ui
+            .vertical_centered(|ui| {
+                let width = ui.available_width() - (ui.spacing().item_spacing.x * 2.0);
+                let height = ui.available_height() - (ui.spacing().item_spacing.y * 2.0);
+                match self.panel {
+                    PlotPanel::All => {
+                        let height = height * 0.5;
+                        self.draw_plot(ui, "dyno_plot_first", height, width, draw_plot_first);
+                        ui.separator();
+                        self.draw_plot(ui, "dyno_plot_second", height, width, draw_plot_second)
+                    }
+                    PlotPanel::First => {
+                        self.draw_plot(ui, "dyno_plot_first", height, width, draw_plot_first)
+                    }
+                    PlotPanel::Second => {
+                        self.draw_plot(ui, "dyno_plot_second", height, width, draw_plot_second)
+                    }
                 }
-            }
-        })
-        .response
+            })
+            .response;
+        self.compares = compares;
+        response
     }
 
     #[inline]
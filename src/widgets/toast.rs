@@ -605,20 +605,24 @@ fn ease_in_cubic(x: f32) -> f32 {
 
 #[macro_export]
 macro_rules! toast_error {
-    ($($args:tt)*) => { $crate::TOAST_MSG.lock().error(format!($($args)*)) };
+    ($fmt:literal $($args:tt)*) => { $crate::TOAST_MSG.lock().error(format!($fmt $($args)*)) };
+    ($msg:expr) => { $crate::TOAST_MSG.lock().error($msg) };
 }
 
 #[macro_export]
 macro_rules! toast_warn {
-    ($($args:tt)*) => { $crate::TOAST_MSG.lock().warning(format!($($args)*)) };
+    ($fmt:literal $($args:tt)*) => { $crate::TOAST_MSG.lock().warning(format!($fmt $($args)*)) };
+    ($msg:expr) => { $crate::TOAST_MSG.lock().warning($msg) };
 }
 
 #[macro_export]
 macro_rules! toast_info {
-    ($($args:tt)*) => { $crate::TOAST_MSG.lock().info(format!($($args)*)) };
+    ($fmt:literal $($args:tt)*) => { $crate::TOAST_MSG.lock().info(format!($fmt $($args)*)) };
+    ($msg:expr) => { $crate::TOAST_MSG.lock().info($msg) };
 }
 
 #[macro_export]
 macro_rules! toast_success {
-    ($($args:tt)*) => { $crate::TOAST_MSG.lock().success(format!($($args)*)) };
+    ($fmt:literal $($args:tt)*) => { $crate::TOAST_MSG.lock().success(format!($fmt $($args)*)) };
+    ($msg:expr) => { $crate::TOAST_MSG.lock().success($msg) };
 }
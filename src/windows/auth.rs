@@ -1,4 +1,4 @@
-use crate::{toast_warn, widgets::DynoWidgets};
+use crate::{toast_warn, tr, widgets::DynoWidgets};
 use dyno_core::{
     role::Roles,
     users::{UserLogin, UserRegistration},
@@ -22,8 +22,8 @@ impl Default for AuthSection {
 impl AuthSection {
     fn as_str(&self) -> &'static str {
         match self {
-            AuthSection::Login => "Login",
-            AuthSection::Register => "Register",
+            AuthSection::Login => tr!("Login"),
+            AuthSection::Register => tr!("Register"),
         }
     }
     fn opposite(self) -> Self {
@@ -51,9 +51,9 @@ impl LoginSection {
             password_strength,
         } = self;
 
-        text_edit_validate(ui, nim, status_nim, "nim: ", validate_nim);
+        text_edit_validate(ui, nim, status_nim, tr!("nim: "), validate_nim);
         text_edit_validate_password(
-            "password: ",
+            tr!("password: "),
             ui,
             password,
             status_password,
@@ -62,7 +62,7 @@ impl LoginSection {
             validate_password,
         );
         ui.horizontal(|ui| {
-            ui.checkbox(show_password, "show password");
+            ui.checkbox(show_password, tr!("show password"));
             let (val, col) = password_strength.percent_color();
             ui.add(
                 ProgressBar::new(val)
@@ -101,10 +101,10 @@ impl RegistrationSection {
             password_strength,
             show_password,
         } = self;
-        text_edit_validate(ui, nim, status_nim, "nim: ", validate_nim);
-        text_edit_validate(ui, email, status_email, "email: ", validate_email);
+        text_edit_validate(ui, nim, status_nim, tr!("nim: "), validate_nim);
+        text_edit_validate(ui, email, status_email, tr!("email: "), validate_email);
         text_edit_validate_password(
-            "password: ",
+            tr!("password: "),
             ui,
             password,
             status_password,
@@ -113,7 +113,7 @@ impl RegistrationSection {
             validate_password,
         );
         text_edit_validate_password(
-            "confirm password: ",
+            tr!("confirm password: "),
             ui,
             confirm_password,
             status_confirm_password,
@@ -121,19 +121,19 @@ impl RegistrationSection {
             !*show_password,
             |s| {
                 if s != password {
-                    Err(crate::DynoErr::validation_error(
-                        "Password not matching with first password",
-                    ))
+                    Err(crate::DynoErr::validation_error(tr!(
+                        "Password not matching with first password"
+                    )))
                 } else {
                     Ok(())
                 }
             },
         );
 
-        ui.combobox_from_slice("Role", role, &[Roles::User, Roles::Guest]);
+        ui.combobox_from_slice(tr!("Role"), role, &[Roles::User, Roles::Guest]);
 
         ui.horizontal(|ui| {
-            ui.checkbox(show_password, "show password");
+            ui.checkbox(show_password, tr!("show password"));
             let (val, col) = password_strength.percent_color();
             ui.add(
                 ProgressBar::new(val)
@@ -211,7 +211,7 @@ impl super::WindowState for AuthWindow {
 
                     vertui.add_space(30.);
                     let submit_btn = vertui.add(
-                        Button::new(RichText::new("Submit").color(Color32::BLACK))
+                        Button::new(RichText::new(tr!("Submit")).color(Color32::BLACK))
                             .rounding(Rounding::same(4.))
                             .fill(Color32::LIGHT_BLUE)
                             .min_size(vec2(280., 30.)),
@@ -226,7 +226,9 @@ impl super::WindowState for AuthWindow {
                             }
                             None => {
                                 control.unset_loading();
-                                toast_warn!("Aplication not connected Api Server - trying reconnecting.. and try again.");
+                                toast_warn!(tr!(
+                                    "Aplication not connected Api Server - trying reconnecting.. and try again."
+                                ));
                                 control.reconnect_api();
                             }
                         }
@@ -0,0 +1,433 @@
+use dyno_core::chrono::Utc;
+use eframe::egui::{Button, DragValue, Grid, Id, RichText, SelectableLabel, TextEdit, Ui, Window};
+
+use crate::service::{
+    calibration, CalibrationRecord, DistanceResult, LoadResult, RollerConstants, Sample,
+    SpinDownResult,
+};
+use crate::widgets::button::ButtonExt;
+use crate::{row_label_value, toast_error, toast_success, tr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Step {
+    #[default]
+    Distance,
+    SpinDown,
+    Load,
+    Review,
+}
+
+impl Step {
+    const ALL: [Self; 4] = [Self::Distance, Self::SpinDown, Self::Load, Self::Review];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Distance => tr!("1. Known Distance"),
+            Self::SpinDown => tr!("2. Spin-Down"),
+            Self::Load => tr!("3. Load Reference"),
+            Self::Review => tr!("4. Review"),
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::Distance => Self::SpinDown,
+            Self::SpinDown => Self::Load,
+            Self::Load | Self::Review => Self::Review,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Self::Distance | Self::SpinDown => Self::Distance,
+            Self::Load => Self::SpinDown,
+            Self::Review => Self::Load,
+        }
+    }
+}
+
+/// guided calibration of the roller and load constants with the live data.
+#[derive(Debug, Clone)]
+pub struct CalibrationWindow {
+    open: bool,
+    step: Step,
+    recording: bool,
+    samples: Vec<Sample>,
+    /// metres
+    known_distance: f64,
+    /// kilogram
+    weight: f64,
+    /// metres
+    arm: f64,
+    distance: Option<DistanceResult>,
+    spin_down: Option<SpinDownResult>,
+    load: Option<LoadResult>,
+    error: Option<String>,
+    notes: String,
+}
+
+impl Default for CalibrationWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            step: Step::default(),
+            recording: false,
+            samples: Vec::new(),
+            known_distance: 10.0,
+            weight: 10.0,
+            arm: 0.5,
+            distance: None,
+            spin_down: None,
+            load: None,
+            error: None,
+            notes: String::new(),
+        }
+    }
+}
+
+impl CalibrationWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&mut self, control: &crate::control::DynoControl) {
+        let sample = Sample::from(control.buffer().last());
+        if self
+            .samples
+            .last()
+            .map_or(true, |last| last.time != sample.time)
+        {
+            self.samples.push(sample);
+        }
+    }
+
+    /// compute the result of the current step from the recorded samples.
+    fn compute(&mut self, rollers: &RollerConstants) {
+        let result = match self.step {
+            Step::Distance => DistanceResult::compute(&self.samples, self.known_distance)
+                .map(|r| self.distance = Some(r)),
+            Step::SpinDown => {
+                SpinDownResult::compute(&self.samples, rollers).map(|r| self.spin_down = Some(r))
+            }
+            Step::Load => LoadResult::compute(&self.samples, self.weight, self.arm)
+                .map(|r| self.load = Some(r)),
+            Step::Review => Ok(()),
+        };
+        self.error = result.err().map(|err| err.to_string());
+    }
+
+    /// constants from `current` corrected with the results.
+    fn corrected(&self, current: RollerConstants) -> RollerConstants {
+        let mut rollers = current;
+        if let Some(distance) = &self.distance {
+            distance.apply(&mut rollers);
+        }
+        rollers
+    }
+
+    fn recorder_ui(&mut self, ui: &mut Ui, rollers: &RollerConstants) {
+        ui.horizontal(|ui| {
+            if self.recording {
+                if ui.stop_button().clicked() {
+                    self.recording = false;
+                    self.compute(rollers);
+                }
+                ui.spinner();
+            } else if ui.record_button().clicked() {
+                self.samples.clear();
+                self.error = None;
+                self.recording = true;
+            }
+            ui.label(tr!("{count} samples", count = self.samples.len()));
+        });
+        if let Some(err) = &self.error {
+            ui.colored_label(ui.style().visuals.error_fg_color, err);
+        }
+    }
+
+    fn step_ui(&mut self, ui: &mut Ui, rollers: &RollerConstants, load_factor: f64) {
+        match self.step {
+            Step::Distance => {
+                ui.label(tr!("Mark the roller, then turn the roller as far as the distance measured on the roller surface. The difference of the odometer corrects the Diameter Roller."));
+                Grid::new("dyno_calibration_distance_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        row_label_value!(
+                            ui,
+                            DragValue::new(&mut self.known_distance)
+                                .clamp_range(0.1..=1000.0)
+                                .speed(0.1)
+                                .suffix(" m"),
+                            "Known Distance",
+                            "distance measured on the roller surface"
+                        );
+                    });
+                self.recorder_ui(ui, rollers);
+                if let Some(r) = &self.distance {
+                    ui.label(tr!(
+                        "odometer {measured} m, known {known} m, correction x{factor}",
+                        measured = format!("{:.3}", r.measured),
+                        known = format!("{:.3}", r.known),
+                        factor = format!("{:.4}", r.factor),
+                    ));
+                }
+            }
+            Step::SpinDown => {
+                ui.label(tr!("Spin the roller without a vehicle up to a high rpm, then release it until it stops. The deceleration shows the friction loss of the roller bearings."));
+                ui.weak(tr!("The inertia is not measured, it is assumed as a solid cylinder from Berat Roller Beban and Diameter Roller Beban."));
+                self.recorder_ui(ui, rollers);
+                if let Some(r) = &self.spin_down {
+                    ui.label(tr!(
+                        "{from} rpm → {to} rpm in {duration} s, deceleration {deceleration} rad/s², assumed inertia {inertia} kg m², loss torque {loss} Nm",
+                        from = format!("{:.0}", r.from_rpm),
+                        to = format!("{:.0}", r.to_rpm),
+                        duration = format!("{:.1}", r.duration),
+                        deceleration = format!("{:.3}", r.deceleration),
+                        inertia = format!("{:.4}", r.inertia),
+                        loss = format!("{:.3}", r.loss_torque),
+                    ));
+                }
+            }
+            Step::Load => {
+                ui.label(tr!("Hang the reference weight on an arm of known length, and record the measured torque. The difference is saved as the Load Cell Factor of the calculation model."));
+                Grid::new("dyno_calibration_load_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        row_label_value!(
+                            ui,
+                            DragValue::new(&mut self.weight)
+                                .clamp_range(0.1..=500.0)
+                                .speed(0.1)
+                                .suffix(" kg"),
+                            "Reference Weight",
+                            "weight of the reference load"
+                        );
+                        ui.end_row();
+                        row_label_value!(
+                            ui,
+                            DragValue::new(&mut self.arm)
+                                .clamp_range(0.01..=5.0)
+                                .speed(0.01)
+                                .suffix(" m"),
+                            "Arm Length",
+                            "length of the arm from the roller shaft to the load point"
+                        );
+                    });
+                self.recorder_ui(ui, rollers);
+                if let Some(r) = &self.load {
+                    ui.label(tr!(
+                        "reference {reference} Nm, measured {measured} Nm, correction x{factor}",
+                        reference = format!("{:.3}", r.reference),
+                        measured = format!("{:.3}", r.measured),
+                        factor = format!("{:.4}", r.factor),
+                    ));
+                }
+            }
+            Step::Review => {
+                let corrected = self.corrected(*rollers);
+                Grid::new("dyno_calibration_review_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong(tr!("constant"));
+                        ui.strong(tr!("current"));
+                        ui.strong(tr!("calibrated"));
+                        ui.end_row();
+                        let rows = [
+                            (
+                                tr!("Diameter Roller"),
+                                rollers.diameter_roller,
+                                corrected.diameter_roller,
+                                "m",
+                            ),
+                            (
+                                tr!("Diameter Roller Beban"),
+                                rollers.diameter_roller_beban,
+                                corrected.diameter_roller_beban,
+                                "m",
+                            ),
+                            (
+                                tr!("Diameter Gear Encoder"),
+                                rollers.diameter_gear_encoder,
+                                corrected.diameter_gear_encoder,
+                                "m",
+                            ),
+                            (
+                                tr!("Diameter Gear Beban"),
+                                rollers.diameter_gear_beban,
+                                corrected.diameter_gear_beban,
+                                "m",
+                            ),
+                            (
+                                tr!("Jarak Antar Gear"),
+                                rollers.jarak_gear,
+                                corrected.jarak_gear,
+                                "m",
+                            ),
+                            (
+                                tr!("Berat Roller Beban"),
+                                rollers.berat_beban,
+                                corrected.berat_beban,
+                                "kg",
+                            ),
+                        ];
+                        let calibrated_factor = self.load.map_or(load_factor, |r| r.factor);
+                        let factor = (tr!("Load Cell Factor"), load_factor, calibrated_factor, "");
+                        for (name, current, calibrated, unit) in rows.into_iter().chain([factor]) {
+                            ui.label(name);
+                            ui.label(format!("{current:.4} {unit}"));
+                            let text = RichText::new(format!("{calibrated:.4} {unit}"));
+                            if (current - calibrated).abs() > f64::EPSILON {
+                                ui.label(text.strong());
+                            } else {
+                                ui.label(text);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                for warning in calibration::validate(&corrected) {
+                    ui.colored_label(ui.style().visuals.warn_fg_color, format!("⚠ {warning}"));
+                }
+                ui.add(
+                    TextEdit::multiline(&mut self.notes)
+                        .hint_text(tr!("notes"))
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+            }
+        }
+    }
+}
+
+impl super::WindowState for CalibrationWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        if self.recording {
+            self.record(control);
+            ctx.request_repaint();
+        }
+        let rollers = RollerConstants::from_config(&control.config);
+        let load_factor = control.app_config.calculation.load_factor;
+        let latest = control.calibration.latest().map(|r| r.time);
+        let mut save = None;
+        let mut open = self.open;
+
+        Window::new(tr!("Calibration"))
+            .id(Id::new("dyno_calibration_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                match latest {
+                    Some(time) => ui.weak(tr!(
+                        "last calibration {time}",
+                        time = time.format("%d-%m-%Y %T")
+                    )),
+                    None => ui.weak(tr!("never calibrated")),
+                };
+                ui.horizontal(|ui| {
+                    for step in Step::ALL {
+                        if ui
+                            .add_enabled(
+                                !self.recording,
+                                SelectableLabel::new(self.step == step, step.title()),
+                            )
+                            .clicked()
+                        {
+                            self.step = step;
+                            self.error = None;
+                        }
+                    }
+                });
+                ui.separator();
+                self.step_ui(ui, &rollers, load_factor);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let enabled = !self.recording;
+                    if ui
+                        .add_enabled(
+                            enabled && self.step != Step::Distance,
+                            Button::new(tr!("⏮ Back")),
+                        )
+                        .clicked()
+                    {
+                        self.step = self.step.prev();
+                        self.error = None;
+                    }
+                    if self.step != Step::Review {
+                        if ui
+                            .add_enabled(enabled, Button::new(tr!("⏭ Next")))
+                            .on_hover_text(tr!("next, a step without result is skipped"))
+                            .clicked()
+                        {
+                            self.step = self.step.next();
+                            self.error = None;
+                        }
+                        return;
+                    }
+                    let corrected = self.corrected(rollers);
+                    let valid = calibration::validate(&corrected).is_empty();
+                    if ui
+                        .add_enabled(enabled && valid, Button::new(tr!("✔ Apply & Save")))
+                        .on_hover_text(tr!(
+                            "apply the constants to the config and the load cell factor to the calculation model, and save the calibration record"
+                        ))
+                        .on_disabled_hover_text(tr!(
+                            "the constants are invalid, repeat the calibration"
+                        ))
+                        .clicked()
+                    {
+                        save = Some(CalibrationRecord {
+                            time: Utc::now().naive_local(),
+                            rollers: corrected,
+                            distance: self.distance,
+                            spin_down: self.spin_down,
+                            load: self.load,
+                            notes: self.notes.clone(),
+                        });
+                    }
+                    if ui.reset_button().clicked() {
+                        *self = Self {
+                            open: self.open,
+                            ..Self::default()
+                        };
+                    }
+                });
+            });
+
+        if let Some(record) = save {
+            record.rollers.apply(&mut control.config);
+            if let Some(load) = &record.load {
+                load.apply(&mut control.app_config.calculation);
+            }
+            match control.calibration.push(record) {
+                Ok(()) => {
+                    toast_success!(tr!("Calibration is applied and saved"));
+                    *self = Self::default();
+                    open = false;
+                }
+                Err(err) => {
+                    toast_error!(tr!("Failed to save calibration record - {err}", err = err))
+                }
+            }
+        }
+        if !open {
+            self.recording = false;
+        }
+        self.open = open;
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
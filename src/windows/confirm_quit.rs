@@ -1,4 +1,4 @@
-use crate::widgets::button::ButtonExt;
+use crate::{tr, widgets::button::ButtonExt};
 use dyno_core::serde;
 use eframe::egui::{
     Align2, Color32, Context, Id, InnerResponse, Key, LayerId, Order, Ui, Vec2, Window,
@@ -35,13 +35,16 @@ impl super::WindowState for ConfirmQuitWindow {
         if let Some(InnerResponse {
             inner: Some(Some(b)),
             ..
-        }) = Window::new("Do you wanna close the Application?")
+        }) = Window::new(tr!("Do you wanna close the Application?"))
+            .id(Id::new("dyno_confirm_quit_window"))
             .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, 0.0))
             .open(&mut self.open)
             .collapsible(false)
             .resizable(false)
             .show(ctx, |ui: &mut Ui| {
-                ui.small("click 'Ok' to close the app or 'No' to rebort close event");
+                ui.small(tr!(
+                    "click 'Ok' to close the app or 'No' to rebort close event"
+                ));
                 ui.horizontal(|horz_ui| {
                     if horz_ui.ok_button().clicked() || horz_ui.input(|i| i.key_down(Key::Enter)) {
                         Some(true)
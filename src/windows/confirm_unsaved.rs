@@ -1,4 +1,7 @@
-use crate::widgets::button::{ButtonExt, ButtonKind};
+use crate::{
+    tr,
+    widgets::button::{ButtonExt, ButtonKind},
+};
 use dyno_core::serde;
 use eframe::egui::{
     Align2, Color32, Context, Id, InnerResponse, Key, LayerId, Order, Vec2, Window,
@@ -18,7 +21,7 @@ impl super::WindowState for ConfirmUnsavedWindow {
     fn show_window(
         &mut self,
         ctx: &Context,
-        _control: &mut crate::control::DynoControl,
+        control: &mut crate::control::DynoControl,
         state: &mut crate::state::DynoState,
     ) {
         let painter = ctx.layer_painter(LayerId::new(
@@ -31,30 +34,34 @@ impl super::WindowState for ConfirmUnsavedWindow {
             Color32::from_black_alpha(192),
         );
 
-        match Window::new("Buffer Data Records is unsaved. Do you want to save it?")
-            .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, 0.0))
-            .open(&mut self.open)
-            .collapsible(false)
-            .resizable(false)
-            .show(ctx, |ui| {
-                ui.label("there is data recorded in buffer, and its not saved");
-                ui.small("click 'Save' to save it or 'No' to rebort it, or 'Cancel' to cancel it");
-                ui.horizontal(|horz_ui| {
-                    if horz_ui.save_button().clicked() || horz_ui.input(|i| i.key_down(Key::Enter))
-                    {
-                        Some(ButtonKind::Save)
-                    } else if horz_ui.no_button().clicked() {
-                        Some(ButtonKind::No)
-                    } else if horz_ui.cancel_button().clicked()
-                        || horz_ui.input(|i| i.key_down(Key::Escape))
-                    {
-                        Some(ButtonKind::Cancel)
-                    } else {
-                        None
-                    }
-                })
-                .inner
-            }) {
+        match Window::new(tr!(
+            "Buffer Data Records is unsaved. Do you want to save it?"
+        ))
+        .id(Id::new("dyno_confirm_unsaved_window"))
+        .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, 0.0))
+        .open(&mut self.open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(tr!("there is data recorded in buffer, and its not saved"));
+            ui.small(tr!(
+                "click 'Save' to save it or 'No' to rebort it, or 'Cancel' to cancel it"
+            ));
+            ui.horizontal(|horz_ui| {
+                if horz_ui.save_button().clicked() || horz_ui.input(|i| i.key_down(Key::Enter)) {
+                    Some(ButtonKind::Save)
+                } else if horz_ui.no_button().clicked() {
+                    Some(ButtonKind::No)
+                } else if horz_ui.cancel_button().clicked()
+                    || horz_ui.input(|i| i.key_down(Key::Escape))
+                {
+                    Some(ButtonKind::Cancel)
+                } else {
+                    None
+                }
+            })
+            .inner
+        }) {
             Some(InnerResponse {
                 inner: Some(Some(ButtonKind::Save)),
                 ..
@@ -70,12 +77,19 @@ impl super::WindowState for ConfirmUnsavedWindow {
                 state.set_operator(crate::state::OperatorData::Noop);
                 if state.quitable() {
                     state.set_quit(true);
+                } else {
+                    control.discard_unsaved(state);
                 }
             }
             Some(InnerResponse {
                 inner: Some(Some(ButtonKind::Cancel)),
                 ..
-            }) => self.open = !self.open,
+            }) => {
+                self.open = !self.open;
+                control.cancel_pending();
+            }
+            // closed from the title bar, same as cancel.
+            _ if !self.open => control.cancel_pending(),
             _ => {}
         }
     }
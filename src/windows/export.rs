@@ -0,0 +1,192 @@
+use crate::{
+    formats::{
+        export::{ExportOptions, ExportRange, Sampling, UnitSystem, ValueKind},
+        CHANNELS,
+    },
+    row_label_value,
+    state::DynoFileType,
+    tr,
+    widgets::DynoWidgets,
+};
+use eframe::egui::{Button, DragValue, Grid, Id, Ui, Window};
+
+const EXPORT_TYPES: [DynoFileType; 2] = [DynoFileType::Csv, DynoFileType::Excel];
+
+#[derive(Debug, Clone)]
+pub struct ExportWindow {
+    open: bool,
+    file_type: DynoFileType,
+    options: ExportOptions,
+}
+
+impl Default for ExportWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            file_type: DynoFileType::Csv,
+            options: ExportOptions::default(),
+        }
+    }
+}
+
+impl ExportWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn options_ui(&mut self, ui: &mut Ui, duration: f64) {
+        let opt = &mut self.options;
+        row_label_value!(
+            ui => ui.horizontal_wrapped(|ui| {
+                for (checked, channel) in opt.channels.iter_mut().zip(CHANNELS.iter()) {
+                    ui.checkbox(checked, channel.desc);
+                }
+            }),
+            "Channels",
+            "data columns to export"
+        );
+        ui.end_row();
+
+        row_label_value!(
+            ui => ui.horizontal(|ui| ui.selectable_value_from_slice(&mut opt.range, &ExportRange::ALL)),
+            "Range",
+            "time range of the data to export, all data, a time window, or the detected pull (acceleration)"
+        );
+        ui.end_row();
+        if opt.range == ExportRange::Window {
+            row_label_value!(
+                ui => ui.horizontal(|ui| {
+                    ui.add(DragValue::new(&mut opt.window.0).clamp_range(0.0..=duration).speed(0.1).suffix(" s"));
+                    ui.label("-");
+                    ui.add(DragValue::new(&mut opt.window.1).clamp_range(opt.window.0.min(duration)..=duration).speed(0.1).suffix(" s"));
+                }),
+                "Time Window",
+                "start and end time in seconds from the first data"
+            );
+            ui.end_row();
+        }
+
+        row_label_value!(
+            ui => ui.horizontal(|ui| ui.selectable_value_from_slice(&mut opt.sampling, &Sampling::ALL)),
+            "Sampling",
+            "amount of the exported data, the original data, decimation (every n data) or resample (Hz)"
+        );
+        ui.end_row();
+        match opt.sampling {
+            Sampling::Original => {}
+            Sampling::Decimate => {
+                row_label_value!(
+                    ui,
+                    DragValue::new(&mut opt.decimation)
+                        .clamp_range(1..=1000)
+                        .prefix(tr!("every ")),
+                    "Decimation",
+                    "take every n data"
+                );
+                ui.end_row();
+            }
+            Sampling::Resample => {
+                row_label_value!(
+                    ui,
+                    DragValue::new(&mut opt.resample_hz)
+                        .clamp_range(0.1..=1000.0)
+                        .speed(0.1)
+                        .suffix(" Hz"),
+                    "Resample Rate",
+                    "frequency of the resampled data (linear interpolation)"
+                );
+                ui.end_row();
+            }
+        }
+
+        row_label_value!(
+            ui => ui.horizontal(|ui| ui.selectable_value_from_slice(&mut opt.values, &ValueKind::ALL)),
+            "Values",
+            "original (raw) values or values filtered with a moving average"
+        );
+        ui.end_row();
+        if opt.values == ValueKind::Filtered {
+            row_label_value!(
+                ui,
+                DragValue::new(&mut opt.filter_window)
+                    .clamp_range(1..=101)
+                    .suffix(tr!(" samples")),
+                "Filter Window",
+                "amount of data for the moving average"
+            );
+            ui.end_row();
+        }
+
+        row_label_value!(
+            ui => ui.horizontal(|ui| ui.selectable_value_from_slice(&mut opt.units, &UnitSystem::ALL)),
+            "Unit System",
+            "metric units (km/h, Nm, km, °C) or imperial units (mph, lb·ft, mi, °F)"
+        );
+        ui.end_row();
+
+        row_label_value!(
+            ui => ui.checkbox(&mut opt.header, tr!("Run Info and Configuration")),
+            "Header",
+            "write the run info and the dyno config at the top of the file"
+        );
+        ui.end_row();
+
+        row_label_value!(
+            ui => ui.horizontal(|ui| ui.selectable_value_from_slice(&mut self.file_type, &EXPORT_TYPES)),
+            "File Type",
+            "type of the exported file"
+        );
+        ui.end_row();
+    }
+}
+
+impl super::WindowState for ExportWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let buffer = control.buffer();
+        let is_empty = buffer.is_empty();
+        let duration =
+            (buffer.time_stamp.last_value() - buffer.time_stamp.first_value()) as f64 / 1000.0;
+        let exportable = !is_empty && self.options.channels.iter().any(|c| *c);
+
+        let mut open = self.open;
+        Window::new(tr!("Export Data"))
+            .id(Id::new("id_export_data"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                Grid::new("dyno_export_grid_id")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .show(ui, |ui| self.options_ui(ui, duration.max(0.0)));
+                ui.separator();
+                if is_empty {
+                    ui.small(tr!("there is no data recorded in buffer to export"));
+                }
+                let export_btn = ui.add_enabled(
+                    exportable,
+                    Button::new(tr!("Export as {kind}", kind = self.file_type)),
+                );
+                if export_btn.clicked() {
+                    control.on_export(self.file_type, self.options.clone());
+                    self.open = false;
+                }
+            });
+        self.open = open && self.open;
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
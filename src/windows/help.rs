@@ -1,5 +1,7 @@
 use dyno_core::serde;
-use eframe::egui::Window;
+use eframe::egui::{Grid, Id, Window};
+
+use crate::{keymap::Action, tr};
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 #[serde(crate = "serde")]
@@ -17,15 +19,34 @@ impl super::WindowState for HelpWindow {
     fn show_window(
         &mut self,
         ctx: &eframe::egui::Context,
-        _control: &mut crate::control::DynoControl,
+        control: &mut crate::control::DynoControl,
         _state: &mut crate::state::DynoState,
     ) {
-        Window::new("Help")
+        Window::new(tr!("Help"))
+            .id(Id::new("dyno_help_window"))
             .open(&mut self.open)
             .resizable(false)
             .collapsible(false)
             .show(ctx, |ui: &mut eframe::egui::Ui| {
-                ui.heading("Upcoming Update! Help Window")
+                ui.heading(tr!("Keyboard Shortcuts"));
+                ui.weak(tr!("change the shortcuts in Config > Keymap"));
+                ui.separator();
+                Grid::new("dyno_help_shortcuts_grid")
+                    .num_columns(3)
+                    .spacing([20.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let keymap = &control.app_config.keymap;
+                        for action in Action::ALL {
+                            match keymap.get(action) {
+                                Some(shortcut) => ui.strong(shortcut.to_string()),
+                                None => ui.weak(tr!("none")),
+                            };
+                            ui.label(action.to_string());
+                            ui.weak(action.description());
+                            ui.end_row();
+                        }
+                    });
             });
     }
 
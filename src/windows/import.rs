@@ -0,0 +1,278 @@
+use std::path::PathBuf;
+
+use crate::{
+    formats::{
+        import::{
+            self, ColumnMap, CsvPreview, Delimiter, ImportProfile, ImportProfiles, ImportUnit,
+            TimeUnit,
+        },
+        CHANNELS,
+    },
+    toast_error, toast_success, tr,
+    widgets::{DynoFileManager, DynoWidgets},
+};
+use eframe::egui::*;
+
+const NONE_COLUMN: &str = "-- none --";
+
+fn column_combobox<U>(
+    ui: &mut Ui,
+    id: impl std::hash::Hash,
+    map: &mut ColumnMap<U>,
+    headers: &[String],
+) {
+    ComboBox::from_id_source(id)
+        .width(200.)
+        .selected_text(map.column.as_deref().unwrap_or(NONE_COLUMN))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut map.column, None, NONE_COLUMN);
+            for header in headers {
+                ui.selectable_value(&mut map.column, Some(header.clone()), header.as_str());
+            }
+        });
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportWindow {
+    open: bool,
+    path: Option<PathBuf>,
+    preview: CsvPreview,
+    profile: ImportProfile,
+    /// saved profiles from `import_profiles.toml`, loaded when the window first shown.
+    profiles: Option<Vec<ImportProfile>>,
+}
+
+impl ImportWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn reload_preview(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match import::preview(path, &self.profile) {
+            Ok(preview) => {
+                self.profile.guess_columns(&preview.headers);
+                self.preview = preview;
+            }
+            Err(err) => {
+                self.preview = CsvPreview::default();
+                toast_error!("{err}");
+            }
+        }
+    }
+
+    fn save_profile(&mut self, paths: &crate::paths::DynoPaths) {
+        let profiles = self.profiles.get_or_insert_with(Vec::new);
+        match profiles.iter_mut().find(|p| p.name == self.profile.name) {
+            Some(profile) => *profile = self.profile.clone(),
+            None => profiles.push(self.profile.clone()),
+        }
+        let profiles = ImportProfiles {
+            profiles: profiles.clone(),
+        };
+        match paths.set_config(profiles, ImportProfiles::FILE_NAME) {
+            Ok(()) => toast_success!(tr!(
+                "Import profile `{name}` saved",
+                name = self.profile.name
+            )),
+            Err(err) => toast_error!(tr!("Failed to save import profile - {err}", err = err)),
+        }
+    }
+
+    fn profile_ui(&mut self, ui: &mut Ui, paths: &crate::paths::DynoPaths) {
+        let profiles = self
+            .profiles
+            .get_or_insert_with(|| {
+                paths
+                    .get_config::<ImportProfiles>(ImportProfiles::FILE_NAME)
+                    .map(|p| p.profiles)
+                    .unwrap_or_default()
+            })
+            .clone();
+
+        let mut selected = None;
+        ui.horizontal(|ui| {
+            ComboBox::from_label(tr!("Profile"))
+                .selected_text(self.profile.name.as_str())
+                .show_ui(ui, |ui| {
+                    for profile in ImportProfile::builtin().into_iter().chain(profiles) {
+                        let label = ui.selectable_label(
+                            profile.name == self.profile.name,
+                            profile.name.as_str(),
+                        );
+                        if label.clicked() {
+                            selected = Some(profile);
+                        }
+                    }
+                });
+            ui.add(TextEdit::singleline(&mut self.profile.name).desired_width(140.));
+            if ui.button(tr!("Save Profile")).clicked() {
+                self.save_profile(paths);
+            }
+            if ui.button(tr!("Delete Profile")).clicked() {
+                let name = self.profile.name.clone();
+                if let Some(profiles) = &mut self.profiles {
+                    profiles.retain(|p| p.name != name);
+                    let profiles = ImportProfiles {
+                        profiles: profiles.clone(),
+                    };
+                    if let Err(err) = paths.set_config(profiles, ImportProfiles::FILE_NAME) {
+                        toast_error!(tr!("Failed to delete import profile - {err}", err = err));
+                    }
+                }
+            }
+        });
+        if let Some(profile) = selected {
+            self.profile = profile;
+            self.reload_preview();
+        }
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(tr!("Delimiter"));
+            changed |= ui
+                .selectable_value_from_slice(&mut self.profile.delimiter, &Delimiter::ALL)
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            changed |= ui
+                .add(DragValue::new(&mut self.profile.skip_rows).clamp_range(0..=100))
+                .changed();
+            ui.label(tr!("rows before header"));
+            ui.separator();
+            changed |= ui
+                .checkbox(&mut self.profile.decimal_comma, tr!("Decimal Comma (1,5)"))
+                .changed();
+        });
+        if changed {
+            self.reload_preview();
+        }
+    }
+
+    fn mapping_ui(&mut self, ui: &mut Ui) {
+        let headers = &self.preview.headers;
+        let profile = &mut self.profile;
+        Grid::new("dyno_import_mapping_grid")
+            .num_columns(3)
+            .spacing([20.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr!("Time"));
+                column_combobox(ui, "import_column_time", &mut profile.time, headers);
+                ui.horizontal(|ui| {
+                    ui.selectable_value_from_slice(&mut profile.time.unit, &TimeUnit::ALL)
+                });
+                ui.end_row();
+                for (idx, map) in profile.channels.iter_mut().enumerate() {
+                    ui.strong(CHANNELS[idx].desc);
+                    column_combobox(ui, ("import_column", idx), map, headers);
+                    ui.horizontal(|ui| {
+                        for unit in ImportUnit::for_channel(idx) {
+                            ui.selectable_value(&mut map.unit, *unit, unit.label(idx));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn preview_ui(&self, ui: &mut Ui) {
+        ScrollArea::both()
+            .id_source("dyno_import_preview")
+            .max_height(160.)
+            .show(ui, |ui| {
+                Grid::new("dyno_import_preview_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for header in &self.preview.headers {
+                            ui.strong(header.as_str());
+                        }
+                        ui.end_row();
+                        for row in &self.preview.rows {
+                            for value in row {
+                                ui.monospace(value.as_str());
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
+
+impl super::WindowState for ImportWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let mut open = self.open;
+        Window::new(tr!("Import Third-Party Data"))
+            .id(Id::new("id_import_data"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("Pick Csv File")).clicked() {
+                        let dir = control.paths.get_data_dir_folder("Saved");
+                        if let Some(path) = DynoFileManager::pick_csv(dir) {
+                            self.path = Some(path);
+                            self.reload_preview();
+                        }
+                    }
+                    match &self.path {
+                        Some(path) => ui.monospace(path.display().to_string()),
+                        None => ui.small(tr!("no file selected")),
+                    };
+                });
+                ui.separator();
+                self.profile_ui(ui, &control.paths);
+                ui.separator();
+                self.mapping_ui(ui);
+                ui.separator();
+                self.preview_ui(ui);
+                ui.separator();
+
+                let importable = self.path.is_some() && self.profile.time.column.is_some();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(importable, Button::new(tr!("Import as Comparison")))
+                        .on_hover_text(tr!("overlay the imported run on the plots"))
+                        .clicked()
+                    {
+                        if let Some(path) = self.path.clone() {
+                            control.on_import(path, self.profile.clone(), true);
+                        }
+                    }
+                    let buffer_saved = control.is_buffer_saved();
+                    if ui
+                        .add_enabled(
+                            importable && buffer_saved,
+                            Button::new(tr!("Import as Current Data")),
+                        )
+                        .on_disabled_hover_text(tr!("save the recorded data first"))
+                        .clicked()
+                    {
+                        if let Some(path) = self.path.clone() {
+                            control.on_import(path, self.profile.clone(), false);
+                            self.open = false;
+                        }
+                    }
+                });
+            });
+        self.open = open && self.open;
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
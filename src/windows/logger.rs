@@ -6,6 +6,7 @@ use eframe::{
 };
 use itertools::Itertools;
 
+use crate::tr;
 use crate::widgets::{button::ButtonExt, DynoFileManager, DynoWidgets};
 use dyno_core::{log::Level, RECORDS_LOGGER};
 
@@ -65,20 +66,20 @@ impl LoggerWindow {
         }
 
         ui.horizontal(|ui| {
-            if ui.button("Clear").clicked() {
+            if ui.button(tr!("Clear")).clicked() {
                 logs.clear();
             }
-            ui.menu_button("Log Levels", |ui| {
+            ui.menu_button(tr!("Log Levels"), |ui| {
                 ui.selectable_label_from_slice(&mut self.loglevels, |idx| LEVELS[idx].as_str());
             });
         });
 
         ui.horizontal(|ui| {
-            ui.label("Search: ");
+            ui.label(tr!("Search: "));
             let _response = ui.text_edit_singleline(&mut self.term);
             if ui
                 .selectable_label(self.case_sensitive, "Aa")
-                .on_hover_text("Case sensitive")
+                .on_hover_text(tr!("Case sensitive"))
                 .clicked()
             {
                 self.case_sensitive = !self.case_sensitive;
@@ -86,12 +87,12 @@ impl LoggerWindow {
         });
 
         ui.horizontal(|ui| {
-            ui.label("Max Log output");
+            ui.label(tr!("Max Log output"));
             ui.add(widgets::DragValue::new(&mut self.max_log_len).speed(1));
         });
 
         ui.horizontal(|ui| {
-            if ui.button("Sort").clicked() {
+            if ui.button(tr!("Sort")).clicked() {
                 logs.sort()
             }
         });
@@ -113,14 +114,14 @@ impl LoggerWindow {
             });
 
         ui.horizontal(|ui| {
-            ui.label(format!("Log size: {}", logs.len()));
-            ui.label(format!("Displayed: {}", logs_displayed));
+            ui.label(tr!("Log size: {size}", size = logs.len()));
+            ui.label(tr!("Displayed: {count}", count = logs_displayed));
             ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                 #[allow(deprecated)]
                 if ui.save_button().clicked() {
                     let homedir = env::home_dir().unwrap_or(PathBuf::from("/temp"));
                     if let Some(file) = DynoFileManager::save_file(
-                        "Saving Log File",
+                        tr!("Saving Log File"),
                         "gui_log",
                         homedir,
                         &[("logfile", &["log", "log.log", "dlog"])],
@@ -133,7 +134,7 @@ impl LoggerWindow {
                         }
                     }
                 }
-                if ui.button("Copy").clicked() {
+                if ui.button(tr!("Copy")).clicked() {
                     ui.output_mut(|o| o.copied_text = logs.iter().map(|(_, s)| s).join("\n"));
                 }
             });
@@ -164,7 +165,7 @@ impl super::WindowState for LoggerWindow {
         _state: &mut crate::state::DynoState,
     ) {
         let mut open = self.open;
-        Window::new("Dyno Log Window")
+        Window::new(tr!("Dyno Log Window"))
             .open(&mut open)
             .resizable(true)
             .id("dyno_log_window".into())
@@ -0,0 +1,121 @@
+use eframe::egui::{DragValue, Grid, Id, ScrollArea, TextEdit, Window};
+
+use crate::tr;
+
+/// list of the event markers in the run, to add, edit or delete them.
+#[derive(Debug, Clone, Default)]
+pub struct MarkersWindow {
+    open: bool,
+    /// text of the next marker.
+    text: String,
+}
+
+impl MarkersWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl super::WindowState for MarkersWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let mut open = self.open;
+        let mut mark = false;
+        Window::new(tr!("Event Markers"))
+            .id(Id::new("dyno_markers_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.text)
+                            .hint_text(tr!("gear shift, knock heard, throttle closed..")),
+                    );
+                    mark = ui
+                        .button(tr!("🚩 Mark Now"))
+                        .on_hover_text(tr!("add a marker at the last record of the run"))
+                        .clicked();
+                });
+                ui.separator();
+                if control.markers().is_empty() {
+                    ui.weak(tr!("there is no marker in this run"));
+                    return;
+                }
+
+                let first = control.buffer().time_stamp.first_value();
+                let duration = (control.buffer().time_stamp.last_value() - first) as f64 / 1000.0;
+                // edited in a copy, the run is only marked as unsaved after a change.
+                let mut markers = control.markers().clone();
+                let (mut changed, mut editing, mut remove) = (false, false, None);
+                ScrollArea::vertical()
+                    .id_source("dyno_markers_scroll")
+                    .max_height(320.)
+                    .show(ui, |ui| {
+                        Grid::new("dyno_markers_grid")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr!("Time [s]"));
+                                ui.strong(tr!("Text"));
+                                ui.end_row();
+                                for (idx, marker) in markers.iter_mut().enumerate() {
+                                    let mut secs = (marker.time_stamp - first) as f64 / 1000.0;
+                                    let time = ui.add(
+                                        DragValue::new(&mut secs)
+                                            .speed(0.05)
+                                            .fixed_decimals(3)
+                                            .clamp_range(0.0..=duration),
+                                    );
+                                    if time.changed() {
+                                        marker.time_stamp = first + (secs * 1000.0).round() as i64;
+                                        changed = true;
+                                    }
+                                    editing |= time.dragged() || time.has_focus();
+                                    changed |= ui.text_edit_singleline(&mut marker.text).changed();
+                                    if ui
+                                        .small_button("🗑")
+                                        .on_hover_text(tr!("delete the marker"))
+                                        .clicked()
+                                    {
+                                        remove = Some(idx);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                if let Some(idx) = remove {
+                    markers.remove(idx);
+                    changed = true;
+                }
+                // ordered by time again after the time is edited, not while it is dragged.
+                let times = markers.iter().map(|m| m.time_stamp).collect::<Vec<_>>();
+                if !editing && times.windows(2).any(|w| w[0] > w[1]) {
+                    markers.sort();
+                    changed = true;
+                }
+                if changed {
+                    *control.markers_mut() = markers;
+                }
+            });
+        self.open = open;
+
+        if mark {
+            control.mark_event(std::mem::take(&mut self.text).trim());
+        }
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
@@ -1,12 +1,21 @@
 pub mod about;
 pub mod auth;
+pub mod calibration;
 pub mod confirm_quit;
 pub mod confirm_unsaved;
+pub mod export;
 pub mod help;
+pub mod import;
 pub mod logger;
+pub mod markers;
 pub mod open_server;
+pub mod outbox;
+pub mod reprocess;
+pub mod review;
+pub mod runs;
 pub mod save_server;
 pub mod setting;
+pub mod vehicles;
 
 #[cfg(debug_assertions)]
 pub mod debug;
@@ -39,15 +48,24 @@ impl_downcast!(sync WindowState);
 pub enum WSIdx {
     About = 0,
     Auth,
+    Calibration,
     ConfirmQuit,
     ConfirmUnsaved,
     #[cfg(debug_assertions)]
     Debug,
+    Export,
     Help,
+    Import,
     Logger,
+    Markers,
     OpenServer,
+    Outbox,
+    Reprocess,
+    Review,
+    Runs,
     SaveServer,
     Setting,
+    Vehicles,
     WindowStateSize,
 }
 const WS_SIZE: usize = WSIdx::WindowStateSize as usize;
@@ -68,15 +86,24 @@ impl WindowStack {
             stack: [
                 Box::new(about::AboutWindow::new()),
                 Box::new(auth::AuthWindow::new()),
+                Box::new(calibration::CalibrationWindow::new()),
                 Box::new(confirm_quit::ConfirmQuitWindow::new()),
                 Box::new(confirm_unsaved::ConfirmUnsavedWindow::new()),
                 #[cfg(debug_assertions)]
                 Box::<debug::DebugAction>::default(),
+                Box::new(export::ExportWindow::new()),
                 Box::new(help::HelpWindow::new()),
+                Box::new(import::ImportWindow::new()),
                 Box::new(logger::LoggerWindow::new()),
+                Box::new(markers::MarkersWindow::new()),
                 Box::new(open_server::OpenServerWindow::new()),
+                Box::new(outbox::OutboxWindow::new()),
+                Box::new(reprocess::ReprocessWindow::new()),
+                Box::new(review::ReviewWindow::new()),
+                Box::new(runs::RunsWindow::new()),
                 Box::new(save_server::SaveServerWindow::new()),
                 Box::new(setting::SettingWindow::new()),
+                Box::new(vehicles::VehiclesWindow::new()),
             ],
         }
     }
@@ -1,25 +1,222 @@
-use std::ops::Index;
+use std::collections::BTreeSet;
 
-use dyno_core::dynotests::DynoTest;
-use eframe::egui::{Button, Id, LayerId, Layout, RichText, Ui, Window};
+use dyno_core::{chrono::NaiveDate, DynoConfig};
+use eframe::egui::{
+    Button, ComboBox, DragValue, Grid, Id, LayerId, Layout, RichText, TextEdit, Ui, Window,
+};
 use eframe::emath::{Align, Align2};
 use eframe::epaint::{vec2, Color32, Rounding, Vec2};
 use egui_extras::{Column, TableBuilder};
 
+use crate::control::DynoControl;
+use crate::row_label_value;
+use crate::service::{DynoPage, DynoQuery, DynoSort, DynoTestEntry};
 use crate::widgets::button::ButtonExt;
-use crate::{toast_error, toast_warn};
+use crate::{toast_error, toast_warn, tr};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
 
 #[derive(Debug, Clone, Default)]
 pub struct OpenServerWindow {
-    data: Vec<DynoTest>,
+    data: DynoPage,
+    query: DynoQuery,
+    /// text of the date filter, parsed when searching.
+    from: String,
+    to: String,
+    /// index of the selected runs in the current page.
+    selected: BTreeSet<usize>,
+    preview: Option<usize>,
+    fetched: bool,
     open: bool,
 }
 impl OpenServerWindow {
     pub fn new() -> Self {
         Self::default()
     }
-    pub fn set_data(&mut self, data: Vec<DynoTest>) {
+    pub fn set_data(&mut self, data: DynoPage) {
+        self.query.page = data.page.max(1);
         self.data = data;
+        self.selected.clear();
+        self.preview = None;
+    }
+
+    fn fetch(&mut self, control: &mut DynoControl) {
+        self.fetched = true;
+        match control.api() {
+            Some(api) => {
+                control.set_loading();
+                api.get_dyno(self.query.clone(), control.tx().clone());
+            }
+            None => {
+                toast_warn!(tr!("Not connected to API, trying to reconnecting.."));
+                control.reconnect_api();
+            }
+        }
+    }
+
+    /// search from the first page with the filters, `false` if the date filter is invalid.
+    fn apply_filters(&mut self) -> bool {
+        let parse = |text: &str| match text.trim() {
+            "" => Ok(None),
+            date => NaiveDate::parse_from_str(date, DATE_FORMAT).map(Some),
+        };
+        match (parse(&self.from), parse(&self.to)) {
+            (Ok(from), Ok(to)) => {
+                self.query.from = from;
+                self.query.to = to;
+                self.query.page = 1;
+                true
+            }
+            _ => {
+                toast_warn!(tr!("Invalid date filter, use format YYYY-MM-DD"));
+                false
+            }
+        }
+    }
+
+    /// sort by `sort`, or reverse the order if it's already sorted by `sort`.
+    fn sort_by(&mut self, sort: DynoSort) {
+        if self.query.sort == sort {
+            self.query.descending = !self.query.descending;
+        } else {
+            self.query.sort = sort;
+            self.query.descending = true;
+        }
+        self.query.page = 1;
+    }
+
+    fn filters_ui(&mut self, ui: &mut Ui) -> bool {
+        let mut search = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr!("From"));
+            ui.add(
+                TextEdit::singleline(&mut self.from)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(90.),
+            );
+            ui.label(tr!("To"));
+            ui.add(
+                TextEdit::singleline(&mut self.to)
+                    .hint_text("YYYY-MM-DD")
+                    .desired_width(90.),
+            );
+            ui.label(tr!("Motor"));
+            ui.add(TextEdit::singleline(&mut self.query.motor).desired_width(100.));
+            ui.label(tr!("Owner"));
+            ui.add(TextEdit::singleline(&mut self.query.owner).desired_width(100.));
+            ComboBox::from_id_source("dyno_server_verified_filter")
+                .selected_text(match self.query.verified {
+                    None => tr!("All"),
+                    Some(true) => tr!("Verified"),
+                    Some(false) => tr!("Not Verified"),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.query.verified, None, tr!("All"));
+                    ui.selectable_value(&mut self.query.verified, Some(true), tr!("Verified"));
+                    ui.selectable_value(&mut self.query.verified, Some(false), tr!("Not Verified"));
+                });
+            ui.add(
+                DragValue::new(&mut self.query.per_page)
+                    .clamp_range(10..=200)
+                    .suffix(tr!(" / page")),
+            );
+            if ui.button(tr!("🔍 Search")).clicked() {
+                search = self.apply_filters();
+            }
+        });
+        search
+    }
+
+    pub(super) fn preview_ui(ui: &mut Ui, entry: &DynoTestEntry) {
+        let info = match &entry.info {
+            Some(info) => info,
+            None => {
+                ui.weak(tr!("no info of the data in server"));
+                return;
+            }
+        };
+        let DynoConfig {
+            motor_type,
+            diameter_roller,
+            diameter_roller_beban,
+            diameter_gear_encoder,
+            diameter_gear_beban,
+            jarak_gear,
+            berat_beban,
+            ..
+        } = &info.config;
+        Grid::new("dyno_server_preview_grid_id")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(tr!("Motor"));
+                ui.label(motor_type.to_string());
+                ui.end_row();
+                ui.label(tr!("Start - Stop"));
+                ui.label(format!(
+                    "{} - {}",
+                    info.start.format("%d-%m-%Y %T"),
+                    info.stop.format("%T")
+                ));
+                ui.end_row();
+                ui.label(tr!("Checksum"));
+                ui.monospace(&info.checksum_hex);
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    diameter_roller,
+                    "Diameter Roller",
+                    "diameter of the roller of the dynotests chassis"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    diameter_roller_beban,
+                    "Diameter Roller Beban",
+                    "diameter of the load roller"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    diameter_gear_encoder,
+                    "Diameter Gear Encoder",
+                    "diameter of the gear on the encoder"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    diameter_gear_beban,
+                    "Diameter Gear Beban",
+                    "diameter of the gear on the load roller"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    jarak_gear,
+                    "Jarak Antar Gear",
+                    "distance between the gear of the load roller and the encoder"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    berat_beban,
+                    "Berat Roller Beban",
+                    "weight of the load roller"
+                );
+                ui.end_row();
+                if let Some(summary) = &entry.summary {
+                    ui.label(tr!("Peak"));
+                    ui.label(format!(
+                        "{:.2} HP @ {:.0} rpm, {:.2} Nm @ {:.0} rpm",
+                        summary.peak_hp,
+                        summary.peak_hp_rpm,
+                        summary.peak_torque,
+                        summary.peak_torque_rpm
+                    ));
+                    ui.end_row();
+                }
+            });
     }
 }
 
@@ -30,6 +227,9 @@ impl super::WindowState for OpenServerWindow {
         control: &mut crate::control::DynoControl,
         _state: &mut crate::state::DynoState,
     ) {
+        if !self.fetched {
+            self.fetch(control);
+        }
         ctx.layer_painter(LayerId::new(
             eframe::egui::Order::Background,
             Id::new("confirmation_popup_unsaved"),
@@ -40,112 +240,236 @@ impl super::WindowState for OpenServerWindow {
             Color32::from_black_alpha(192),
         );
 
-        let ui_window = |ui: &mut Ui| {
-            ui.heading("List File in Server");
+        let mut refresh = false;
+        let mut sort = None;
+        let mut open = None;
+        let mut download_selected = false;
+        // the peak values are only in the paged query of the server.
+        let has_peak = control.api().map_or(false, |api| api.has_paged_query());
+
+        let mut ui_window = |ui: &mut Ui| {
+            ui.heading(tr!("List File in Server"));
             ui.add_space(10.);
+            refresh |= self.filters_ui(ui);
+            ui.separator();
+
+            let (current_sort, descending) = (self.query.sort, self.query.descending);
+            let columns = [
+                (DynoSort::Id, "id"),
+                (DynoSort::Motor, tr!("motor")),
+                (DynoSort::Owner, tr!("owner")),
+                (DynoSort::Verified, tr!("verified")),
+                (DynoSort::Updated, tr!("updated")),
+                (DynoSort::Created, tr!("created")),
+            ];
             TableBuilder::new(ui)
                 .striped(true)
                 .cell_layout(Layout::left_to_right(Align::Center))
-                .columns(Column::remainder().at_least(40.0), 6)
+                .column(Column::auto())
+                .columns(Column::remainder().at_least(40.0), columns.len())
+                .columns(Column::auto().at_least(80.0), usize::from(has_peak))
+                .column(Column::auto())
+                .max_scroll_height(320.)
                 .resizable(true)
                 .header(20.0, |mut head_ui| {
                     head_ui.col(|col_ui| {
-                        col_ui.strong("id");
-                    });
-                    head_ui.col(|col_ui| {
-                        col_ui.strong("info id");
-                    });
-                    head_ui.col(|col_ui| {
-                        col_ui.strong("verified");
-                    });
-                    head_ui.col(|col_ui| {
-                        col_ui.strong("updated");
-                    });
-                    head_ui.col(|col_ui| {
-                        col_ui.strong("created");
+                        col_ui.strong("✔");
                     });
+                    for (column, name) in columns {
+                        head_ui.col(|col_ui| {
+                            let text = match (current_sort == column, descending) {
+                                (true, true) => format!("{name} ⏷"),
+                                (true, false) => format!("{name} ⏶"),
+                                (false, _) => name.to_owned(),
+                            };
+                            if col_ui
+                                .add(Button::new(RichText::new(text).strong()).frame(false))
+                                .on_hover_text(tr!("sort by this column"))
+                                .clicked()
+                            {
+                                sort = Some(column);
+                            }
+                        });
+                    }
+                    if has_peak {
+                        head_ui.col(|col_ui| {
+                            col_ui.strong(tr!("peak"));
+                        });
+                    }
                     head_ui.col(|col_ui| {
-                        col_ui.strong("open");
+                        col_ui.strong(tr!("open"));
                     });
                 })
                 .body(|body_ui| {
                     let row_height = 18.0;
-                    let num_rows = self.data.len();
+                    let num_rows = self.data.items.len();
                     body_ui.rows(row_height, num_rows, |row_idx, mut row| {
-                        let DynoTest {
-                            id,
-                            info_id,
-                            verified,
-                            updated_at,
-                            created_at,
-                            data_url,
-                        data_checksum,
-                            ..
-                        } = self.data.index(row_idx);
+                        let entry = &self.data.items[row_idx];
 
                         row.col(|ui| {
-                            ui.label(id.to_string());
+                            let mut checked = self.selected.contains(&row_idx);
+                            if ui.checkbox(&mut checked, "").changed() {
+                                if checked {
+                                    self.selected.insert(row_idx);
+                                } else {
+                                    self.selected.remove(&row_idx);
+                                }
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui
+                                .selectable_label(
+                                    self.preview == Some(row_idx),
+                                    entry.dyno.id.to_string(),
+                                )
+                                .on_hover_text(tr!("show the details"))
+                                .clicked()
+                            {
+                                self.preview = Some(row_idx);
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.motor_name());
                         });
                         row.col(|ui| {
-                            ui.label((*info_id).map(|x| x.to_string()).unwrap_or_default());
+                            ui.label(entry.owner.as_deref().unwrap_or("-"));
                         });
                         row.col(|ui| {
-                            ui.label(if *verified {
-                                "verified"
+                            ui.label(if entry.dyno.verified {
+                                tr!("verified")
                             } else {
-                                "not verified"
+                                tr!("not verified")
                             });
                         });
                         row.col(|ui| {
-                            ui.label(updated_at.format("%d-%m-%Y %T").to_string());
+                            ui.label(entry.dyno.updated_at.format("%d-%m-%Y %T").to_string());
                         });
                         row.col(|ui| {
-                            ui.label(created_at.format("%d-%m-%Y %T").to_string());
+                            ui.label(entry.dyno.created_at.format("%d-%m-%Y %T").to_string());
                         });
+                        if has_peak {
+                            row.col(|ui| {
+                                match &entry.summary {
+                                    Some(summary) => ui.label(format!("{:.2} HP", summary.peak_hp)),
+                                    None => ui.weak("-"),
+                                };
+                            });
+                        }
                         row.col(|ui| {
-                            let open_btn = ui.small_open_button();
-                            match (open_btn.clicked(), control.api()) {
-                                (true, None) => {
-                                    toast_error!("Something Wrong, Api is not Connected! trying to reconnecting..");
-                                    control.reconnect_api();
-                                }
-                                (true, Some(api)) => {
-                                    control.set_loading();
-                                    api.load_dyno_file(data_url.clone(), data_checksum.clone(), control.tx().clone());
-                                }
-                                _ => {}
+                            if ui.small_open_button().clicked() {
+                                open = Some(row_idx);
                             }
                         });
                     })
                 });
 
+            ui.add_space(6.);
+            ui.horizontal(|ui| {
+                let pages = self.data.pages();
+                if ui
+                    .add_enabled(self.query.page > 1, Button::new(tr!("◀ Prev")))
+                    .clicked()
+                {
+                    self.query.page -= 1;
+                    refresh = true;
+                }
+                ui.label(tr!(
+                    "Page {page} / {pages} ({total} runs)",
+                    page = self.query.page,
+                    pages = pages,
+                    total = self.data.total
+                ));
+                if ui
+                    .add_enabled(self.query.page < pages, Button::new(tr!("Next ▶")))
+                    .clicked()
+                {
+                    self.query.page += 1;
+                    refresh = true;
+                }
+                ui.separator();
+                if ui
+                    .add_enabled(
+                        !self.selected.is_empty(),
+                        Button::new(tr!(
+                            "Download Selected ({count})",
+                            count = self.selected.len()
+                        )),
+                    )
+                    .on_hover_text(tr!("open the selected runs as comparison on the plots"))
+                    .clicked()
+                {
+                    download_selected = true;
+                }
+            });
+
+            if let Some(entry) = self.preview.and_then(|idx| self.data.items.get(idx)) {
+                ui.separator();
+                ui.strong(tr!("Details of #{id}", id = entry.dyno.id));
+                Self::preview_ui(ui, entry);
+            }
+
             ui.add_space(10.);
             let refresh_btn = ui.add(
-                Button::new(RichText::new("Refresh").color(Color32::BLACK))
+                Button::new(RichText::new(tr!("Refresh")).color(Color32::BLACK))
                     .rounding(Rounding::same(4.))
                     .fill(Color32::LIGHT_BLUE)
                     .min_size(vec2(280., 30.)),
             );
             ui.add_space(20.);
-            if refresh_btn.clicked() {
-                match control.api() {
-                    Some(api) => api.get_dyno(control.tx().clone()),
-                    None => {
-                        toast_warn!("Not connected to API, trying to reconnecting..");
-                        control.reconnect_api();
-                    }
-                }
-            }
+            refresh |= refresh_btn.clicked();
         };
 
-        Window::new("Save DynoTests to Server")
-            .id("dyno_save_server".into())
+        let mut window_open = self.open;
+        Window::new(tr!("Open DynoTests from Server"))
+            .id("dyno_open_server".into())
             .anchor(Align2::CENTER_CENTER, Vec2::new(0.0, 0.0))
-            .open(&mut self.open)
+            .open(&mut window_open)
             .movable(false)
             .collapsible(false)
             .resizable(false)
-            .show(ctx, |ui| ui.vertical_centered_justified(ui_window));
+            .show(ctx, |ui| ui.vertical_centered_justified(&mut ui_window));
+        self.open = window_open;
+
+        if let Some(column) = sort {
+            self.sort_by(column);
+            refresh = true;
+        }
+        if refresh {
+            self.fetch(control);
+        }
+
+        let downloads = match (open, download_selected) {
+            (Some(idx), _) => vec![(idx, false)],
+            (None, true) => self.selected.iter().map(|idx| (*idx, true)).collect(),
+            (None, false) => vec![],
+        };
+        if downloads.is_empty() {
+            return;
+        }
+        let api = match control.api() {
+            Some(api) => api,
+            None => {
+                toast_error!(tr!(
+                    "Something Wrong, Api is not Connected! trying to reconnecting.."
+                ));
+                control.reconnect_api();
+                return;
+            }
+        };
+        for (idx, as_compare) in downloads {
+            let Some(entry) = self.data.items.get(idx) else {
+                continue;
+            };
+            let compare = as_compare.then(|| format!("#{} {}", entry.dyno.id, entry.motor_name()));
+            api.load_dyno_file(
+                entry.dyno.data_url.clone(),
+                entry.dyno.data_checksum.clone(),
+                compare,
+                control.tx().clone(),
+            );
+        }
+        control.set_loading();
+        self.selected.clear();
     }
 
     #[inline]
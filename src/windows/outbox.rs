@@ -0,0 +1,125 @@
+use eframe::egui::{Button, Id, Layout, Ui, Window};
+use eframe::emath::Align;
+use egui_extras::{Column, TableBuilder};
+
+use crate::{toast_error, tr};
+
+#[derive(Debug, Clone, Default)]
+pub struct OutboxWindow {
+    open: bool,
+}
+impl OutboxWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl super::WindowState for OutboxWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let logined = control.api().map_or(false, |api| api.is_logined());
+        let mut remove = None;
+
+        let ui_window = |ui: &mut Ui| {
+            if !logined {
+                ui.small(tr!("login to the server to upload the pending data"));
+            }
+            TableBuilder::new(ui)
+                .striped(true)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(60.0))
+                .column(Column::remainder().at_least(160.0))
+                .column(Column::auto())
+                .resizable(true)
+                .header(20.0, |mut head_ui| {
+                    for name in [
+                        tr!("created"),
+                        tr!("motor"),
+                        tr!("attempts"),
+                        tr!("status"),
+                        "",
+                    ] {
+                        head_ui.col(|col_ui| {
+                            col_ui.strong(name);
+                        });
+                    }
+                })
+                .body(|body_ui| {
+                    let entries = control.outbox.entries();
+                    body_ui.rows(18.0, entries.len(), |row_idx, mut row| {
+                        let entry = &entries[row_idx];
+                        row.col(|ui| {
+                            ui.label(entry.created.format("%d-%m-%Y %T").to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.config.motor_type.to_string());
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.attempts.to_string());
+                        });
+                        row.col(|ui| {
+                            let status = match (entry.is_uploading(), entry.retry_in()) {
+                                (true, _) => match control.transfers.get(&entry.id) {
+                                    Some(progress) => progress.to_string(),
+                                    None => tr!("uploading..").to_owned(),
+                                },
+                                (false, Some(wait)) => {
+                                    tr!("retry in {secs} s", secs = wait.as_secs())
+                                }
+                                (false, None) => tr!("waiting").to_owned(),
+                            };
+                            let label = ui.label(status);
+                            if let Some(err) = &entry.last_error {
+                                label.on_hover_text(err.as_str());
+                            }
+                        });
+                        row.col(|ui| {
+                            if ui
+                                .add_enabled(!entry.is_uploading(), Button::new("🗑").small())
+                                .on_hover_text(tr!("discard the data, it will not be uploaded"))
+                                .clicked()
+                            {
+                                remove = Some(entry.id.clone());
+                            }
+                        });
+                    });
+                });
+            ui.separator();
+            if ui
+                .add_enabled(logined, Button::new(tr!("Retry Now")))
+                .clicked()
+            {
+                control.outbox.retry_now();
+            }
+        };
+
+        Window::new(tr!("Pending Uploads"))
+            .id(Id::new("dyno_outbox_window"))
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, ui_window);
+
+        if let Some(id) = remove {
+            if let Err(err) = control.outbox.remove(&id) {
+                toast_error!(tr!("Failed to remove pending upload - {err}", err = err));
+            }
+        }
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
@@ -0,0 +1,202 @@
+use dyno_core::{BufferData, DynoConfig};
+use eframe::egui::{Button, CollapsingHeader, Grid, Id, ScrollArea, Window};
+
+use crate::formats::summary::RunSummary;
+use crate::{tr, AsyncMsg};
+
+use super::setting::SettingWindow;
+
+/// reprocessed run, and the summary of the buffer and the reprocessed run.
+#[derive(Debug, Clone)]
+struct Reprocessed {
+    buffer: BufferData,
+    old: (RunSummary, f64),
+    new: (RunSummary, f64),
+}
+
+/// process the raw frames of the run again with a different `DynoConfig`.
+#[derive(Debug, Clone, Default)]
+pub struct ReprocessWindow {
+    open: bool,
+    config: Option<DynoConfig>,
+    result: Option<Reprocessed>,
+}
+
+impl ReprocessWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn summary(buffer: &BufferData) -> (RunSummary, f64) {
+        (RunSummary::from_buffer(buffer), buffer.last().odo.value())
+    }
+
+    fn compare_ui(ui: &mut eframe::egui::Ui, result: &Reprocessed) {
+        let ((old, old_odo), (new, new_odo)) = (&result.old, &result.new);
+        Grid::new("dyno_reprocess_compare_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("");
+                ui.strong(tr!("current"));
+                ui.strong(tr!("reprocessed"));
+                ui.end_row();
+                let rows = [
+                    (
+                        tr!("Samples"),
+                        old.samples.to_string(),
+                        new.samples.to_string(),
+                    ),
+                    (
+                        tr!("Duration"),
+                        format!("{:.3} s", old.duration),
+                        format!("{:.3} s", new.duration),
+                    ),
+                    (
+                        tr!("Max Speed"),
+                        format!("{:.2} km/h", old.max_speed),
+                        format!("{:.2} km/h", new.max_speed),
+                    ),
+                    (
+                        tr!("Max RPM"),
+                        format!("{:.0} rpm", old.max_rpm),
+                        format!("{:.0} rpm", new.max_rpm),
+                    ),
+                    (
+                        tr!("Peak Power"),
+                        format!("{:.2} HP @ {:.0} rpm", old.peak_hp, old.peak_hp_rpm),
+                        format!("{:.2} HP @ {:.0} rpm", new.peak_hp, new.peak_hp_rpm),
+                    ),
+                    (
+                        tr!("Peak Torque"),
+                        format!("{:.2} Nm @ {:.0} rpm", old.peak_torque, old.peak_torque_rpm),
+                        format!("{:.2} Nm @ {:.0} rpm", new.peak_torque, new.peak_torque_rpm),
+                    ),
+                    (
+                        tr!("ODO"),
+                        format!("{old_odo:.3} km"),
+                        format!("{new_odo:.3} km"),
+                    ),
+                ];
+                for (name, old, new) in rows {
+                    ui.label(name);
+                    ui.label(old.as_str());
+                    if old == new {
+                        ui.label(new);
+                    } else {
+                        ui.strong(new);
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+impl super::WindowState for ReprocessWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let mut save = None;
+        let mut open = self.open;
+        let config = self.config.get_or_insert_with(|| control.config.clone());
+
+        Window::new(tr!("Reprocess Run"))
+            .id(Id::new("dyno_reprocess_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                match control.run_file() {
+                    Some(path) => ui.label(tr!("run: {path}", path = path.display())),
+                    None => ui.weak(tr!("run: not saved")),
+                };
+                let frames = control.raw_frames();
+                if frames.is_empty() {
+                    ui.weak(tr!("this run has no raw frames (imported, or saved without the raw file), it can't be reprocessed"));
+                    return;
+                }
+                ui.label(tr!("{count} raw frames", count = frames.len()));
+                ui.separator();
+
+                CollapsingHeader::new(tr!("Config for Reprocess"))
+                    .id_source("dyno_reprocess_config")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        ScrollArea::vertical()
+                            .id_source("dyno_reprocess_config_scroll")
+                            .max_height(280.)
+                            .show(ui, |ui| SettingWindow::setting_info(ui, config));
+                    });
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr!("Reset to Current Config"))
+                        .on_hover_text(tr!("fill with the config that is active now"))
+                        .clicked()
+                    {
+                        *config = control.config.clone();
+                    }
+                    if ui
+                        .button(tr!("⚙ Reprocess"))
+                        .on_hover_text(tr!("process the raw data again with the config above"))
+                        .clicked()
+                    {
+                        let buffer = frames.replay(config);
+                        self.result = Some(Reprocessed {
+                            old: Self::summary(control.buffer()),
+                            new: Self::summary(&buffer),
+                            buffer,
+                        });
+                    }
+                });
+
+                let result = match &self.result {
+                    Some(result) => result,
+                    None => return,
+                };
+                ui.separator();
+                Self::compare_ui(ui, result);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(tr!("Compare in Plot"))
+                        .on_hover_text(tr!("show the reprocessed run as a comparison in the plot"))
+                        .clicked()
+                    {
+                        let name = tr!("reprocessed ({motor})", motor = config.motor_type);
+                        let msg = AsyncMsg::open_compare(name, result.buffer.clone());
+                        dyno_core::ignore_err!(control.tx().send(msg));
+                    }
+                    if ui
+                        .add_enabled(control.run_file().is_some(), Button::new(tr!("💾 Save as Revision")))
+                        .on_hover_text(tr!("save as a new revision next to the run file, the original file is not changed"))
+                        .on_disabled_hover_text(tr!("save the run to a file first"))
+                        .clicked()
+                    {
+                        save = Some((result.buffer.clone(), config.clone()));
+                    }
+                });
+            });
+
+        if let Some((buffer, config)) = save {
+            control.save_revision(buffer, config);
+            self.result = None;
+        }
+        if !open {
+            self.config = None;
+            self.result = None;
+        }
+        self.open = open;
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
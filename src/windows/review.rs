@@ -0,0 +1,307 @@
+use eframe::egui::{Button, Color32, Id, Layout, RichText, ScrollArea, TextEdit, Ui, Window};
+use eframe::emath::Align;
+use egui_extras::{Column, TableBuilder};
+
+use crate::control::DynoControl;
+use crate::service::{DynoPage, ReviewAction, ReviewComment, ReviewEvent, ReviewRequest};
+use crate::{toast_success, toast_warn, tr};
+
+use super::open_server::OpenServerWindow;
+
+/// runs waiting for review, only for instructors (`Roles::Admin`).
+#[derive(Debug, Clone, Default)]
+pub struct ReviewWindow {
+    data: DynoPage,
+    page: u32,
+    selected: Option<usize>,
+    comments: Vec<ReviewComment>,
+    comment: String,
+    fetch_page: bool,
+    fetch_comments: bool,
+    open: bool,
+}
+
+impl ReviewWindow {
+    pub fn new() -> Self {
+        Self {
+            page: 1,
+            fetch_page: true,
+            ..Default::default()
+        }
+    }
+
+    fn selected_id(&self) -> Option<i64> {
+        self.selected
+            .and_then(|idx| self.data.items.get(idx))
+            .map(|entry| i64::from(entry.dyno.id))
+    }
+
+    pub fn on_event(&mut self, event: ReviewEvent) {
+        match event {
+            ReviewEvent::Pending(data) => {
+                self.page = data.page.max(1);
+                self.data = data;
+                self.selected = None;
+                self.comments.clear();
+            }
+            ReviewEvent::Comments(id, comments) => {
+                if self.selected_id() == Some(id) {
+                    self.comments = comments;
+                }
+            }
+            ReviewEvent::Done(request) => match request.action {
+                ReviewAction::Comment => {
+                    self.comment.clear();
+                    self.fetch_comments = true;
+                }
+                ReviewAction::Verify | ReviewAction::Reject => {
+                    let verified = request.action == ReviewAction::Verify;
+                    if verified {
+                        toast_success!(tr!("Run #{id} is verified", id = request.id));
+                    } else {
+                        toast_success!(tr!("Run #{id} is rejected", id = request.id));
+                    }
+                    self.data
+                        .items
+                        .retain(|entry| i64::from(entry.dyno.id) != request.id);
+                    self.data.total = self.data.total.saturating_sub(1);
+                    self.selected = None;
+                    self.comments.clear();
+                    self.comment.clear();
+                }
+            },
+        }
+    }
+}
+
+impl super::WindowState for ReviewWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut DynoControl,
+        _state: &mut crate::state::DynoState,
+    ) {
+        let has_review = control.api().map_or(false, |api| api.has_review());
+        let is_reviewer = control.api().map_or(false, |api| api.is_reviewer());
+        if is_reviewer {
+            self.fetch(control);
+        }
+
+        let mut action = None;
+        let mut open_run = false;
+        let ui_window = |ui: &mut Ui| {
+            if !has_review {
+                ui.label(tr!("The server does not support the review of runs"));
+                return;
+            }
+            if !is_reviewer {
+                ui.label(tr!("only instructor (Admin) can review the runs"));
+                return;
+            }
+            TableBuilder::new(ui)
+                .striped(true)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::auto().at_least(50.0))
+                .column(Column::remainder().at_least(120.0))
+                .column(Column::auto().at_least(100.0))
+                .column(Column::auto().at_least(120.0))
+                .max_scroll_height(220.)
+                .resizable(true)
+                .header(20.0, |mut head_ui| {
+                    for name in ["id", tr!("motor"), tr!("owner"), tr!("created")] {
+                        head_ui.col(|col_ui| {
+                            col_ui.strong(name);
+                        });
+                    }
+                })
+                .body(|body_ui| {
+                    body_ui.rows(18.0, self.data.items.len(), |row_idx, mut row| {
+                        let entry = &self.data.items[row_idx];
+                        row.col(|ui| {
+                            if ui
+                                .selectable_label(
+                                    self.selected == Some(row_idx),
+                                    entry.dyno.id.to_string(),
+                                )
+                                .clicked()
+                                && self.selected != Some(row_idx)
+                            {
+                                self.selected = Some(row_idx);
+                                self.comments.clear();
+                                self.fetch_comments = true;
+                            }
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.motor_name());
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.owner.as_deref().unwrap_or("-"));
+                        });
+                        row.col(|ui| {
+                            ui.label(entry.dyno.created_at.format("%d-%m-%Y %T").to_string());
+                        });
+                    });
+                });
+            ui.horizontal(|ui| {
+                let pages = self.data.pages();
+                if ui
+                    .add_enabled(self.page > 1, Button::new(tr!("◀ Prev")))
+                    .clicked()
+                {
+                    self.page -= 1;
+                    self.fetch_page = true;
+                }
+                ui.label(tr!(
+                    "Page {page} / {pages} ({total} runs waiting)",
+                    page = self.page,
+                    pages = pages,
+                    total = self.data.total
+                ));
+                if ui
+                    .add_enabled(self.page < pages, Button::new(tr!("Next ▶")))
+                    .clicked()
+                {
+                    self.page += 1;
+                    self.fetch_page = true;
+                }
+                if ui.button(tr!("Refresh")).clicked() {
+                    self.fetch_page = true;
+                }
+            });
+
+            let entry = match self.selected.and_then(|idx| self.data.items.get(idx)) {
+                Some(entry) => entry,
+                None => return,
+            };
+            let id = i64::from(entry.dyno.id);
+            ui.separator();
+            ui.strong(tr!("Review of #{id}", id = id));
+            OpenServerWindow::preview_ui(ui, entry);
+            ui.separator();
+            comments_ui(ui, &self.comments);
+            ui.add(
+                TextEdit::multiline(&mut self.comment)
+                    .hint_text(tr!("comment for the student"))
+                    .desired_rows(3),
+            );
+            let has_comment = !self.comment.trim().is_empty();
+            ui.horizontal(|ui| {
+                if ui.button(tr!("Open")).clicked() {
+                    open_run = true;
+                }
+                if ui
+                    .add_enabled(has_comment, Button::new(tr!("💬 Comment")))
+                    .clicked()
+                {
+                    action = Some((id, ReviewAction::Comment));
+                }
+                if ui
+                    .add(Button::new(
+                        RichText::new(tr!("✔ Verify")).color(Color32::LIGHT_GREEN),
+                    ))
+                    .clicked()
+                {
+                    action = Some((id, ReviewAction::Verify));
+                }
+                if ui
+                    .add_enabled(
+                        has_comment,
+                        Button::new(RichText::new(tr!("✖ Reject")).color(Color32::LIGHT_RED)),
+                    )
+                    .on_disabled_hover_text(tr!("write the reason of the rejection in the comment"))
+                    .clicked()
+                {
+                    action = Some((id, ReviewAction::Reject));
+                }
+            });
+        };
+
+        Window::new(tr!("Review Runs"))
+            .id(Id::new("dyno_review_window"))
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, ui_window);
+
+        let api = match control.api() {
+            Some(api) => api,
+            None => return,
+        };
+        if open_run {
+            if let Some(entry) = self.selected.and_then(|idx| self.data.items.get(idx)) {
+                control.set_loading();
+                api.load_dyno_file(
+                    entry.dyno.data_url.clone(),
+                    entry.dyno.data_checksum.clone(),
+                    None,
+                    control.tx().clone(),
+                );
+            }
+        }
+        if let Some((id, action)) = action {
+            if action == ReviewAction::Verify && self.comment.trim().is_empty() {
+                toast_warn!(tr!("Verifying run #{id} without comment", id = id));
+            }
+            api.review(
+                ReviewRequest {
+                    id,
+                    action,
+                    comment: self.comment.trim().to_owned(),
+                },
+                control.tx().clone(),
+            );
+        }
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+impl ReviewWindow {
+    fn fetch(&mut self, control: &DynoControl) {
+        let api = match control.api() {
+            Some(api) => api,
+            None => return,
+        };
+        if std::mem::take(&mut self.fetch_page) {
+            api.get_reviews(self.page, control.tx().clone());
+        }
+        if std::mem::take(&mut self.fetch_comments) {
+            if let Some(id) = self.selected_id() {
+                api.get_comments(id, control.tx().clone());
+            }
+        }
+    }
+}
+
+fn comments_ui(ui: &mut Ui, comments: &[ReviewComment]) {
+    if comments.is_empty() {
+        ui.weak(tr!("no comments"));
+        return;
+    }
+    ScrollArea::vertical()
+        .id_source("dyno_review_comments")
+        .max_height(140.)
+        .show(ui, |ui| {
+            for comment in comments {
+                ui.horizontal(|ui| {
+                    ui.strong(&comment.user);
+                    ui.weak(comment.created_at.format("%d-%m-%Y %T").to_string());
+                    match comment.verified {
+                        Some(true) => ui.colored_label(Color32::LIGHT_GREEN, tr!("✔ verified")),
+                        Some(false) => ui.colored_label(Color32::LIGHT_RED, tr!("✖ rejected")),
+                        None => ui.label(""),
+                    };
+                });
+                ui.label(&comment.comment);
+                ui.add_space(4.);
+            }
+        });
+}
@@ -0,0 +1,157 @@
+use eframe::egui::{Button, Color32, Id, Layout, RichText, Ui, Window};
+use eframe::emath::Align;
+use egui_extras::{Column, TableBuilder};
+
+use crate::service::SyncState;
+use crate::tr;
+use crate::widgets::button::ButtonExt;
+
+#[derive(Debug, Clone, Default)]
+pub struct RunsWindow {
+    open: bool,
+}
+impl RunsWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn badge_color(state: SyncState) -> Color32 {
+    match state {
+        SyncState::Synced => Color32::LIGHT_GREEN,
+        SyncState::LocalOnly | SyncState::ServerOnly => Color32::LIGHT_BLUE,
+        SyncState::Uploading | SyncState::Downloading => Color32::YELLOW,
+        SyncState::Failed => Color32::LIGHT_RED,
+    }
+}
+
+impl super::WindowState for RunsWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        state: &mut crate::state::DynoState,
+    ) {
+        let logined = control.api().map_or(false, |api| api.is_logined());
+        let mut open_path = None;
+        let mut sync_now = false;
+
+        let ui_window = |ui: &mut Ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("Folder: {dir}", dir = control.sync.dir().display()));
+            });
+            TableBuilder::new(ui)
+                .striped(true)
+                .cell_layout(Layout::left_to_right(Align::Center))
+                .column(Column::remainder().at_least(180.0))
+                .column(Column::auto().at_least(60.0))
+                .column(Column::auto().at_least(120.0))
+                .column(Column::auto().at_least(100.0))
+                .column(Column::auto())
+                .max_scroll_height(360.)
+                .resizable(true)
+                .header(20.0, |mut head_ui| {
+                    for name in [
+                        tr!("file"),
+                        tr!("server id"),
+                        tr!("motor"),
+                        tr!("sync"),
+                        tr!("open"),
+                    ] {
+                        head_ui.col(|col_ui| {
+                            col_ui.strong(name);
+                        });
+                    }
+                })
+                .body(|body_ui| {
+                    let runs = control.sync.runs();
+                    body_ui.rows(18.0, runs.len(), |row_idx, mut row| {
+                        let run = &runs[row_idx];
+                        row.col(|ui| {
+                            match &run.local {
+                                Some(local) => ui.label(local.name()),
+                                None => ui.weak("-"),
+                            };
+                        });
+                        row.col(|ui| {
+                            match &run.remote {
+                                Some(remote) => ui.label(format!("#{}", remote.id)),
+                                None => ui.weak("-"),
+                            };
+                        });
+                        row.col(|ui| {
+                            ui.label(run.remote.as_ref().map_or("", |r| r.motor.as_str()));
+                        });
+                        row.col(|ui| {
+                            let badge = ui.label(
+                                RichText::new(run.state.badge()).color(badge_color(run.state)),
+                            );
+                            match (&run.error, control.transfers.get(&run.checksum)) {
+                                (Some(err), _) => badge.on_hover_text(err.as_str()),
+                                (None, Some(progress)) => badge.on_hover_text(progress.to_string()),
+                                (None, None) => badge.on_hover_text(run.state.description()),
+                            };
+                        });
+                        row.col(|ui| {
+                            if let Some(local) = &run.local {
+                                if ui.small_open_button().clicked() {
+                                    open_path = Some(local.path.clone());
+                                }
+                            }
+                        });
+                    });
+                });
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        logined && !control.sync.is_running(),
+                        Button::new(tr!("🔄 Sync Now")),
+                    )
+                    .on_hover_text(tr!(
+                        "upload the local runs to server, and download the runs from server"
+                    ))
+                    .clicked()
+                {
+                    sync_now = true;
+                }
+                if control.sync.is_running() {
+                    ui.spinner();
+                }
+                match control.sync.last_sync() {
+                    Some(time) => {
+                        ui.small(tr!("last sync {time}", time = time.format("%d-%m-%Y %T")))
+                    }
+                    None => ui.small(tr!("never synced")),
+                };
+            });
+            if !logined {
+                ui.small(tr!("login to the server to sync the runs"));
+            }
+        };
+
+        Window::new(tr!("Runs"))
+            .id(Id::new("dyno_runs_window"))
+            .open(&mut self.open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, ui_window);
+
+        if sync_now {
+            control.start_sync();
+        }
+        if let Some(path) = open_path {
+            control.open_path(path, state);
+        }
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
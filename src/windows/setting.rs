@@ -1,4 +1,16 @@
-use crate::{config::ApplicationConfig, paths::DynoPaths, row_label_value, widgets::DynoWidgets};
+use crate::{
+    calculation::{CalculationConfig, CalculationModel},
+    config::{ApplicationConfig, ServerProfile, ServerProfiles, TelemetryConfig},
+    keymap::{Action, KeyMap, Shortcut},
+    paths::DynoPaths,
+    row_label_value,
+    service::{
+        ApiService, CalibrationLog, CalibrationStatus, Presets, TelemetryService, VehicleDb,
+    },
+    settings::SettingsSection,
+    toast_error, toast_success, toast_warn, tr,
+    widgets::{DynoFileManager, DynoWidgets},
+};
 use dyno_core::{
     serde, Cylinder, DynoConfig, ElectricMotor, InfoMotor, MotorType, Stroke as InfoMotorStroke,
     Transmition,
@@ -11,14 +23,45 @@ enum PanelSetting {
     #[default]
     Generic,
     Info,
+    Keymap,
+    Server,
     Style,
 }
 
+/// action from the server panel, done after the settings window is drawn.
+enum ServerAction {
+    Reconnect,
+    TestConnection(ServerProfile),
+}
+
+/// action from the config presets, done after the settings window is drawn.
+enum PresetAction {
+    Apply(String),
+    Save(String),
+    Delete(String),
+    Import(std::path::PathBuf),
+    Export(String, std::path::PathBuf),
+    Reload,
+}
+
 #[derive(Clone, Default)]
 pub struct SettingWindow {
     open: bool,
     panel: PanelSetting,
     edit_path: bool,
+    server_selected: usize,
+    /// name of the selected server profile while edited, renamed when the edit is done.
+    server_name: Option<String>,
+    preset_name: String,
+    /// paths edited in the generic panel, not used before applied.
+    paths_draft: Option<DynoPaths>,
+    move_data: bool,
+    /// section waiting for the confirmation of reset.
+    reset_section: Option<SettingsSection>,
+    /// action waiting for the key of its new shortcut.
+    capture: Option<Action>,
+    /// shortcut refused because it is used by another action.
+    keymap_conflict: Option<(Shortcut, Action)>,
 }
 
 impl SettingWindow {
@@ -29,31 +72,581 @@ impl SettingWindow {
         }
     }
 
-    #[allow(unused)]
+    /// paths are edited in a copy, returns the paths to apply.
+    fn setting_paths(&mut self, ui: &mut Ui, paths: &DynoPaths) -> Option<DynoPaths> {
+        let mut apply = None;
+        let mut draft = self.paths_draft.take().unwrap_or_else(|| paths.clone());
+        draft.draw(ui, &mut self.edit_path);
+        if !self.edit_path {
+            return None;
+        }
+        ui.checkbox(&mut self.move_data, tr!("Move existing data"))
+            .on_hover_text(
+                tr!("move the content of the old folders to the new folders, the existing files are not overwritten"),
+            );
+        ui.horizontal(|ui| {
+            let changed = *paths != draft;
+            if ui
+                .add_enabled(changed, Button::new(tr!("✔ Apply Paths")))
+                .on_hover_text(tr!(
+                    "check the folders are writable (created if not exists), then use them"
+                ))
+                .clicked()
+            {
+                apply = Some(draft.clone());
+            }
+            if ui
+                .add_enabled(changed, Button::new(tr!("Revert")))
+                .on_hover_text(tr!("back to the folders used now"))
+                .clicked()
+            {
+                draft = paths.clone();
+            }
+        });
+        self.paths_draft = Some(draft);
+        apply
+    }
+
     fn setting_generic(
         &mut self,
         ui: &mut Ui,
         app_config: &mut ApplicationConfig,
-        paths: &mut DynoPaths,
-    ) {
-        let path_ui = |ui: &mut Ui| {};
-        CollapsingHeader::new("✒ Paths")
+        paths: &DynoPaths,
+        telemetry: &mut TelemetryService,
+    ) -> Option<DynoPaths> {
+        let apply_paths = CollapsingHeader::new(tr!("✒ Paths"))
+            .id_source("dyno_setting_paths")
             .default_open(true)
-            .show(ui, |path_ui| paths.draw(path_ui, &mut self.edit_path));
+            .show(ui, |path_ui| self.setting_paths(path_ui, paths))
+            .body_returned
+            .flatten();
         ui.separator();
 
-        CollapsingHeader::new(" Configurations")
+        CollapsingHeader::new(tr!(" Configurations"))
+            .id_source("dyno_setting_configurations")
             .default_open(true)
             .show(ui, |config_ui| {
                 app_config.draw(config_ui);
             });
+        ui.separator();
+
+        CollapsingHeader::new(tr!("📡 Live Telemetry"))
+            .id_source("dyno_setting_telemetry")
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("dyno_telemetry_grid_id")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .show(ui, |ui| {
+                        Self::setting_telemetry(ui, &mut app_config.telemetry, telemetry)
+                    })
+            });
+        apply_paths
     }
 
-    #[allow(unused)]
-    pub fn setting_info(ui: &mut Ui, conf: &mut DynoConfig) {
-        let info_motor_ui = |im_ui: &mut Ui| match &mut conf.motor_type {
+    /// reset a section of the settings to default, after confirmation.
+    fn setting_reset(&mut self, ui: &mut Ui) -> Option<SettingsSection> {
+        let mut reset = None;
+        CollapsingHeader::new(tr!("↺ Reset to Default"))
+            .id_source("dyno_setting_reset")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for section in SettingsSection::ALL {
+                        if ui
+                            .button(section.to_string())
+                            .on_hover_text(tr!("reset the settings of this section to the default"))
+                            .clicked()
+                        {
+                            self.reset_section = Some(section);
+                        }
+                    }
+                });
+                let Some(section) = self.reset_section else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        ui.style().visuals.warn_fg_color,
+                        tr!("Reset {section} settings to default?", section = section),
+                    );
+                    if ui.button(tr!("Yes")).clicked() {
+                        reset = Some(section);
+                        self.reset_section = None;
+                    }
+                    if ui.button(tr!("No")).clicked() {
+                        self.reset_section = None;
+                    }
+                });
+            });
+        reset
+    }
+
+    /// key pressed for the action in `capture`, the key event is consumed.
+    fn capture_shortcut(&mut self, ui: &mut Ui, keymap: &mut KeyMap) {
+        let Some(action) = self.capture else {
+            return;
+        };
+        let pressed = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => Some(Shortcut::from_modifiers(*key, *modifiers)),
+                _ => None,
+            })
+        });
+        let Some(shortcut) = pressed else {
+            return;
+        };
+        ui.ctx()
+            .input_mut(|i| i.consume_key(shortcut.modifiers(), shortcut.key));
+        self.capture = None;
+        if shortcut == Shortcut::new(Key::Escape) {
+            return;
+        }
+        match keymap.conflict(action, shortcut) {
+            Some(other) => self.keymap_conflict = Some((shortcut, other)),
+            None => {
+                keymap.set(action, Some(shortcut));
+                self.keymap_conflict = None;
+            }
+        }
+    }
+
+    fn setting_keymap(&mut self, ui: &mut Ui, keymap: &mut KeyMap) {
+        // before the buttons, so the captured key doesn't click the focused button.
+        self.capture_shortcut(ui, keymap);
+        ui.heading(tr!("Keyboard Shortcuts"));
+        ui.weak(tr!(
+            "click a shortcut then press the new key, Escape to cancel"
+        ));
+        ui.separator();
+        let conflicts = keymap.conflicts();
+        Grid::new("dyno_keymap_grid_id")
+            .num_columns(3)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for action in Action::ALL {
+                    ui.label(action.to_string())
+                        .on_hover_text(action.description());
+                    let text = match (self.capture == Some(action), keymap.get(action)) {
+                        (true, _) => RichText::new(tr!("press a key..")).italics(),
+                        (false, Some(shortcut)) if conflicts.contains(&action) => {
+                            RichText::new(shortcut.to_string())
+                                .color(ui.style().visuals.error_fg_color)
+                        }
+                        (false, Some(shortcut)) => RichText::new(shortcut.to_string()),
+                        (false, None) => RichText::new(tr!("none")).weak(),
+                    };
+                    let response = ui.add(Button::new(text).min_size(vec2(120.0, 0.0)));
+                    let response = if conflicts.contains(&action) {
+                        response.on_hover_text(tr!(
+                            "the shortcut is used by another action, only one of them works"
+                        ))
+                    } else {
+                        response.on_hover_text(tr!("click to change the shortcut"))
+                    };
+                    if response.clicked() {
+                        self.capture = Some(action);
+                        self.keymap_conflict = None;
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text(tr!("remove the shortcut"))
+                            .clicked()
+                        {
+                            keymap.set(action, None);
+                        }
+                        let default = action.default_shortcut();
+                        if ui
+                            .small_button("↺")
+                            .on_hover_text(tr!("default: {shortcut}", shortcut = default))
+                            .clicked()
+                        {
+                            match keymap.conflict(action, default) {
+                                Some(other) => self.keymap_conflict = Some((default, other)),
+                                None => keymap.set(action, Some(default)),
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if let Some((shortcut, other)) = self.keymap_conflict {
+            ui.colored_label(
+                ui.style().visuals.warn_fg_color,
+                tr!(
+                    "`{shortcut}` is already used by {action}, remove it from {action} first",
+                    shortcut = shortcut,
+                    action = other
+                ),
+            );
+        }
+        ui.separator();
+        if ui
+            .button(tr!("↺ Reset All Shortcuts"))
+            .on_hover_text(tr!("use the default keymap"))
+            .clicked()
+        {
+            *keymap = KeyMap::default();
+            self.capture = None;
+            self.keymap_conflict = None;
+        }
+    }
+
+    fn setting_telemetry(ui: &mut Ui, conf: &mut TelemetryConfig, service: &mut TelemetryService) {
+        row_label_value!(
+            ui => ui.checkbox(&mut conf.enabled, tr!("Start with Application")),
+            "Enabled",
+            "start the telemetry server when the application is opened"
+        );
+        ui.end_row();
+        row_label_value!(
+            ui => ui.add_enabled(
+                !service.is_running(),
+                TextEdit::singleline(&mut conf.address).hint_text("127.0.0.1:8080")
+            ),
+            "Address",
+            "address and port of the server, 127.0.0.1 is only reachable from this computer, use 0.0.0.0 to open it to every device in the local network (TV or phone)"
+        );
+        ui.end_row();
+        let mut shared = conf.is_shared();
+        row_label_value!(
+            ui => ui.vertical(|ui| {
+                let checkbox = Checkbox::new(&mut shared, tr!("Share in Local Network"));
+                if ui.add_enabled(!service.is_running(), checkbox).changed() {
+                    conf.set_shared(shared);
+                }
+                if shared {
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        tr!("every device in the local network can see the live data"),
+                    );
+                } else {
+                    ui.weak(tr!("only reachable from this computer"));
+                }
+            }),
+            "Network",
+            "listen on 0.0.0.0 to open the live page on a TV or phone in the same network, or on 127.0.0.1 (default) to keep it on this computer, the port is kept"
+        );
+        ui.end_row();
+        row_label_value!(
+            ui => ui.horizontal(|ui| match service.address() {
+                Some(addr) => {
+                    ui.hyperlink(format!("http://{addr}"));
+                    ui.label(tr!("({count} client)", count = service.clients()));
+                    if ui.button(tr!("Stop")).clicked() {
+                        service.stop();
+                    }
+                }
+                None => {
+                    ui.label(tr!("stopped"));
+                    if ui.button(tr!("Start")).clicked() {
+                        match service.start(&conf.address) {
+                            Ok(addr) => toast_success!(tr!(
+                                "Live Telemetry Server running on http://{addr}",
+                                addr = addr
+                            )),
+                            Err(err) => toast_error!(tr!(
+                                "Failed to start Live Telemetry Server - {err}",
+                                err = err
+                            )),
+                        }
+                    }
+                }
+            }),
+            "Server",
+            "live page in `/`, data WebSocket in `/ws`, summary of the run (json) in `/api/summary`"
+        );
+        ui.end_row();
+    }
+
+    fn setting_server(
+        &mut self,
+        ui: &mut Ui,
+        servers: &mut ServerProfiles,
+    ) -> Option<ServerAction> {
+        let mut action = None;
+        let mut remove = None;
+        let mut activate = None;
+        ui.heading(tr!("Server Profiles"));
+        ui.separator();
+        for (idx, profile) in servers.profiles.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .radio(servers.active == profile.name, "")
+                    .on_hover_text(tr!("use this profile to connect to the server"))
+                    .clicked()
+                    && servers.active != profile.name
+                {
+                    activate = Some(idx);
+                }
+                if ui
+                    .selectable_value(&mut self.server_selected, idx, &profile.name)
+                    .clicked()
+                {
+                    self.server_name = None;
+                }
+                ui.weak(&profile.base_url);
+                if ui
+                    .add_enabled(servers.profiles.len() > 1, Button::new("🗑").small())
+                    .on_hover_text(tr!("delete the profile"))
+                    .clicked()
+                {
+                    remove = Some(idx);
+                }
+            });
+        }
+        if let Some(idx) = activate {
+            servers.active = servers.profiles[idx].name.clone();
+            action = Some(ServerAction::Reconnect);
+        }
+        if let Some(idx) = remove {
+            servers.profiles.remove(idx);
+            self.server_name = None;
+            if !servers.profiles.iter().any(|p| p.name == servers.active) {
+                servers.active = servers.profiles[0].name.clone();
+                action = Some(ServerAction::Reconnect);
+            }
+        }
+        if ui.button(tr!("➕ Add Profile")).clicked() {
+            self.server_selected = servers.add();
+            self.server_name = None;
+        }
+        ui.separator();
+
+        self.server_selected = self.server_selected.min(servers.profiles.len() - 1);
+        let selected = self.server_selected;
+        let is_active = servers.profiles[selected].name == servers.active;
+        let profile = &mut servers.profiles[selected];
+        let name = self.server_name.get_or_insert_with(|| profile.name.clone());
+        let mut rename = None;
+        Grid::new("dyno_server_profile_grid_id")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                let response = row_label_value!(
+                    ui, TextEdit::singleline(name).hint_text(tr!("production")),
+                    "Name",
+                    "name of the profile, e.g. production, staging, or local, must be unique"
+                );
+                if response.lost_focus() && *name != profile.name {
+                    rename = Some(name.clone());
+                }
+                ui.end_row();
+                row_label_value!(
+                    ui, TextEdit::singleline(&mut profile.base_url).hint_text("https://dyno.example.com"),
+                    "Base URL",
+                    "address of the server without `/api`, the environment variable DYNO_SERVER_URL replaces the address of the `local` profile"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui, DragValue::new(&mut profile.timeout).clamp_range(1..=600).suffix(" s"),
+                    "Timeout",
+                    "time limit of every request to the server"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui => ui.checkbox(&mut profile.accept_invalid_certs, tr!("Accept Invalid Certificates")),
+                    "TLS",
+                    "accept invalid (self signed) TLS certificates, only for local or staging servers"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui => ui.horizontal(|ui| {
+                        match &profile.ca_certificate {
+                            Some(path) => ui.label(path.display().to_string()),
+                            None => ui.weak(tr!("none")),
+                        };
+                        if ui.small_button("📂").clicked() {
+                            if let Some(path) = DynoFileManager::pick_file(
+                                tr!("Open CA Certificate"),
+                                std::env::current_dir().unwrap_or_default(),
+                                &[("PEM Certificate", &["pem", "crt"])],
+                            ) {
+                                profile.ca_certificate = Some(path);
+                            }
+                        }
+                        if profile.ca_certificate.is_some() && ui.small_button("✖").clicked() {
+                            profile.ca_certificate = None;
+                        }
+                    }),
+                    "CA Certificate",
+                    "additional root certificate (PEM) for a server with its own CA"
+                );
+                ui.end_row();
+            });
+        if let Some(name) = rename {
+            if !servers.rename(selected, &name) {
+                toast_warn!(tr!(
+                    "The profile name `{name}` is empty or used by another profile",
+                    name = name
+                ));
+            }
+            self.server_name = None;
+        }
+        let profile = servers.profiles[selected].clone();
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui
+                .button(tr!("Test Connection"))
+                .on_hover_text(tr!("check the connection to the server with `/api/health`"))
+                .clicked()
+            {
+                action = Some(ServerAction::TestConnection(profile));
+            }
+            if ui
+                .add_enabled(is_active, Button::new(tr!("Apply")))
+                .on_hover_text(tr!(
+                    "reconnect to the server with the settings of this profile"
+                ))
+                .clicked()
+            {
+                action = Some(ServerAction::Reconnect);
+            }
+        });
+        action
+    }
+
+    /// vehicle from the database to fill the info, returns the new selection.
+    fn setting_vehicle(
+        ui: &mut Ui,
+        vehicles: &VehicleDb,
+        selected: &Option<String>,
+    ) -> Option<Option<String>> {
+        let mut select = None;
+        let text = selected
+            .as_deref()
+            .and_then(|id| vehicles.vehicle(id))
+            .map_or_else(|| "-".to_owned(), |v| format!("{} - {}", v.plate, v.motor));
+        ui.horizontal(|ui| {
+            ui.label(tr!("Vehicle")).on_hover_text(
+                tr!("select a vehicle from the Vehicles menu to fill the motor info and the roller constants"),
+            );
+            ComboBox::from_id_source("dyno_setting_vehicle")
+                .selected_text(text)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(selected.is_none(), "-").clicked() {
+                        select = Some(None);
+                    }
+                    for vehicle in vehicles.vehicles() {
+                        let is_selected = selected.as_deref() == Some(vehicle.id.as_str());
+                        if ui
+                            .selectable_label(
+                                is_selected,
+                                format!("{} - {}", vehicle.plate, vehicle.motor),
+                            )
+                            .clicked()
+                            && !is_selected
+                        {
+                            select = Some(Some(vehicle.id.clone()));
+                        }
+                    }
+                });
+        });
+        ui.separator();
+        select
+    }
+
+    /// config presets, load the preset or save the config as preset.
+    fn setting_preset(
+        &mut self,
+        ui: &mut Ui,
+        presets: &Presets,
+        active: &Option<String>,
+        conf: &DynoConfig,
+    ) -> Option<PresetAction> {
+        let mut action = None;
+        let active = active.as_deref().and_then(|name| presets.get(name));
+        let text = match active {
+            Some(preset) if preset.is_same(conf) => preset.name.clone(),
+            Some(preset) => format!("{} *", preset.name),
+            None => "-".to_owned(),
+        };
+        ui.horizontal(|ui| {
+            ui.label(tr!("Preset")).on_hover_text(tr!(
+                "saved config, marked with `*` if the config is changed from the preset"
+            ));
+            ComboBox::from_id_source("dyno_setting_preset")
+                .selected_text(text)
+                .show_ui(ui, |ui| {
+                    for preset in presets.iter() {
+                        let is_selected = active.map_or(false, |a| a.name == preset.name);
+                        if ui
+                            .selectable_label(is_selected, preset.name.as_str())
+                            .clicked()
+                        {
+                            self.preset_name = preset.name.clone();
+                            action = Some(PresetAction::Apply(preset.name.clone()));
+                        }
+                    }
+                });
+            if ui
+                .small_button("🔄")
+                .on_hover_text(tr!("read the presets from the config folder again"))
+                .clicked()
+            {
+                action = Some(PresetAction::Reload);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut self.preset_name)
+                    .hint_text(tr!("preset name"))
+                    .desired_width(160.),
+            );
+            let name = self.preset_name.trim().to_owned();
+            if ui
+                .add_enabled(!name.is_empty(), Button::new(tr!("💾 Save")))
+                .on_hover_text(tr!("save the current config as a preset with this name"))
+                .clicked()
+            {
+                action = Some(PresetAction::Save(name.clone()));
+            }
+            let exists = presets.get(&name).is_some();
+            if ui
+                .add_enabled(exists, Button::new("🗑"))
+                .on_hover_text(tr!("delete the preset"))
+                .clicked()
+            {
+                action = Some(PresetAction::Delete(name.clone()));
+            }
+            if ui
+                .button(tr!("Import.."))
+                .on_hover_text(tr!("add a preset from a TOML file"))
+                .clicked()
+            {
+                let dir = std::env::current_dir().unwrap_or_default();
+                if let Some(path) = DynoFileManager::pick_toml(dir) {
+                    action = Some(PresetAction::Import(path));
+                }
+            }
+            if ui
+                .add_enabled(exists, Button::new(tr!("Export..")))
+                .on_hover_text(tr!("save the preset to a TOML file for another PC"))
+                .clicked()
+            {
+                let dir = std::env::current_dir().unwrap_or_default();
+                if let Some(path) = DynoFileManager::save_toml(Presets::export_name(&name), dir) {
+                    action = Some(PresetAction::Export(name, path));
+                }
+            }
+        });
+        ui.separator();
+        action
+    }
+
+    /// grid rows of the motor spec (name, cc, cylinder, stroke, transmission).
+    pub fn setting_motor(im_ui: &mut Ui, motor: &mut MotorType) {
+        match motor {
             MotorType::Electric(ElectricMotor { name }) => {
-                im_ui.add(TextEdit::singleline(name).hint_text("isi nama motor"));
+                im_ui.add(TextEdit::singleline(name).hint_text(tr!("fill the motor name")));
             }
             MotorType::Engine(InfoMotor {
                 name,
@@ -64,93 +657,200 @@ impl SettingWindow {
             }) => {
                 row_label_value!(
                     im_ui,
-                    TextEdit::singleline(name).hint_text("isi nama motor"),
+                    TextEdit::singleline(name).hint_text(tr!("fill the motor name")),
                     "Motor Name",
-                    "nama motor (hanya untuk informasi data)"
+                    "name of the motor (only for the data info)"
                 );
                 im_ui.end_row();
                 row_label_value!(
                     im_ui,
                     Slider::new(cc, 20u32..=2000u32).suffix(" cc"),
                     "CC",
-                    "nama motor (hanya untuk informasi data)"
+                    "engine displacement (only for the data info)"
                 );
                 im_ui.end_row();
                 row_label_value!(
                     im_ui => im_ui.combobox_from_iter(
-                        "Cylinder of Engine",
+                        tr!("Cylinder of Engine"),
                         cylinder,
                         Cylinder::into_iter()
                     ),
                     "Cylinder",
-                    "silinder mesin (hanya untuk informasi data dan perhitungan rpm engine)"
+                    "cylinders of the engine (only for the data info and the engine rpm calculation)"
                 );
                 im_ui.end_row();
                 row_label_value!(
                     im_ui => im_ui.combobox_from_iter(
-                        "Stroke of Engine",
+                        tr!("Stroke of Engine"),
                         stroke,
                         InfoMotorStroke::into_iter()
                     ),
                     "Stroke",
-                    "stroke mesin (hanya untuk informasi data dan perhitungan rpm engine)"
+                    "stroke of the engine (only for the data info and the engine rpm calculation)"
                 );
                 im_ui.end_row();
                 row_label_value!(
                     im_ui => im_ui.combobox_from_iter(
-                        "Transmitions of Engine",
+                        tr!("Transmitions of Engine"),
                         transmition,
                         Transmition::into_iter()
                     ),
                     "Transmitions",
-                    "transmisi mesin (hanya untuk informasi data)"
+                    "transmission of the engine (only for the data info)"
                 );
             }
+        }
+    }
+
+    /// status of the calibration for the roller constants below.
+    fn setting_calibration(ui: &mut Ui, calibration: &CalibrationLog, conf: &DynoConfig) {
+        let warning = match calibration.status(conf) {
+            CalibrationStatus::Valid => {
+                if let Some(latest) = calibration.latest() {
+                    ui.weak(tr!(
+                        "✔ calibrated {time}",
+                        time = latest.time.format("%d-%m-%Y")
+                    ));
+                }
+                return;
+            }
+            CalibrationStatus::Never => tr!("never calibrated").to_owned(),
+            CalibrationStatus::Changed => {
+                tr!("constants changed since the last calibration").to_owned()
+            }
+            CalibrationStatus::Stale(days) => {
+                tr!("last calibration is {days} days ago", days = days)
+            }
         };
+        ui.colored_label(ui.style().visuals.warn_fg_color, format!("⚠ {warning}"))
+            .on_hover_text(tr!(
+                "run the wizard from the Calibration menu to check the roller constants"
+            ));
+    }
+
+    /// calculation model of the torque and power, returns `true` to recompute the current run.
+    fn setting_calculation(
+        ui: &mut Ui,
+        calculation: &mut CalculationConfig,
+        calibration: &CalibrationLog,
+    ) -> bool {
+        let mut recompute = false;
+        CollapsingHeader::new(tr!("Calculation Model"))
+            .id_source("dyno_calculation_model_id")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("dyno_calculation_model_grid_id")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .show(ui, |ui| {
+                        row_label_value!(
+                            ui => ui.combobox_from_iter(
+                                tr!("Model"),
+                                &mut calculation.model,
+                                CalculationModel::ALL.into_iter()
+                            ),
+                            "Model",
+                            "Load Cell: torque from the sensor, Inertia: moment of inertia of the roller x angular acceleration, Combined: the sum of both"
+                        );
+                        ui.end_row();
+                        row_label_value!(
+                            ui, DragValue::new(&mut calculation.inertia).clamp_range(0.0..=100.0).speed(0.001).suffix(" kg m²"),
+                            "Roller Inertia",
+                            "moment of inertia of the roller, filled manually or assumed from the roller constants by the spin-down calibration"
+                        );
+                        ui.end_row();
+                        row_label_value!(
+                            ui, DragValue::new(&mut calculation.loss_torque).clamp_range(0.0..=100.0).speed(0.01).suffix(" Nm"),
+                            "Loss Torque",
+                            "friction torque of the roller (spin-down), added in the Inertia and Combined models"
+                        );
+                        ui.end_row();
+                        row_label_value!(
+                            ui, DragValue::new(&mut calculation.load_factor).clamp_range(0.5..=2.0).speed(0.0001).fixed_decimals(4),
+                            "Load Cell Factor",
+                            "correction of the torque from the sensor, from the load reference calibration"
+                        );
+                        ui.end_row();
+                        row_label_value!(
+                            ui, DragValue::new(&mut calculation.smoothing).clamp_range(1..=50),
+                            "Smoothing",
+                            "number of samples on each side to compute the angular acceleration of the roller"
+                        );
+                        ui.end_row();
+                    });
+                ui.horizontal(|ui| {
+                    let spin_down = calibration.latest().and_then(|r| r.spin_down);
+                    let load = calibration.latest().and_then(|r| r.load);
+                    if ui
+                        .add_enabled(spin_down.is_some() || load.is_some(), Button::new(tr!("Use Calibration")))
+                        .on_hover_text(tr!("fill the inertia, the friction torque and the load cell factor from the last calibration"))
+                        .on_disabled_hover_text(tr!("no spin-down or load calibration yet"))
+                        .clicked()
+                    {
+                        if let Some(spin_down) = spin_down {
+                            calculation.inertia = spin_down.inertia;
+                            calculation.loss_torque = spin_down.loss_torque;
+                        }
+                        if let Some(load) = load {
+                            load.apply(calculation);
+                        }
+                    }
+                    recompute = ui
+                        .button(tr!("Recompute Current Run"))
+                        .on_hover_text(tr!("compute the torque and power of the current data again with this model"))
+                        .clicked();
+                });
+            });
+        recompute
+    }
+
+    #[allow(unused)]
+    pub fn setting_info(ui: &mut Ui, conf: &mut DynoConfig) {
+        let info_motor_ui = |im_ui: &mut Ui| Self::setting_motor(im_ui, &mut conf.motor_type);
         let other_motor_info_config_ui = |ui: &mut Ui| {
             // diameter_roller: length::Metres,
             row_label_value!(
                 ui, DragValue::new(conf.diameter_roller.value_mut()).suffix(" m"),
                 "Diameter Roller",
-                "Diameter dari roller dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "Diameter of the roller of the dynotests chassis (used to compute the data from the sensor)"
             );
             ui.end_row();
             // diameter_roller_beban: length::Metres,
             row_label_value!(
                 ui, DragValue::new(conf.diameter_roller_beban.value_mut()).suffix(" m"),
                 "Diameter Roller Beban",
-                "Beban dari roller dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "Load of the roller of the dynotests chassis (used to compute the data from the sensor)"
             );
             ui.end_row();
             // diameter_gear_encoder: length::Metres,
             row_label_value!(
                 ui, DragValue::new(conf.diameter_gear_encoder.value_mut()).suffix(" m"),
                 "Diameter Gear Encoder",
-                "Diameter dari gear yang terdapat pada Encoder dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "Diameter of the gear on the Encoder of the dynotests chassis (used to compute the data from the sensor)"
             );
             ui.end_row();
             // diameter_gear_beban: length::Metres,
             row_label_value!(
                 ui, DragValue::new(conf.diameter_gear_beban.value_mut()).suffix(" m"),
                 "Diameter Gear Beban",
-                "Diameter dari gear yang terdapat pada roller Beban dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "Diameter of the gear on the load roller of the dynotests chassis (used to compute the data from the sensor)"
             );
             ui.end_row();
             // jarak_gear: length::Metres,
             row_label_value!(
                 ui, DragValue::new(conf.jarak_gear.value_mut()).suffix(" m"),
                 "Jarak Antar Gear",
-                "jarak diantara gear pada roller Beban dan sensor Encoder dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "distance between the gear of the load roller and the Encoder sensor of the dynotests chassis (used to compute the data from the sensor)"
             );
             ui.end_row();
             // berat_beban: weight::KiloGram,
             row_label_value!(
                 ui, DragValue::new(conf.berat_beban.value_mut()).suffix(" kg"),
                 "Berat Roller Beban",
-                "berat roller Beban pada dynotest chasis (digunakan untuk menghitung informasi data sensor)"
+                "weight of the load roller of the dynotests chassis (used to compute the data from the sensor)"
             );
         };
-        CollapsingHeader::new("Info Motor Config")
+        CollapsingHeader::new(tr!("Info Motor Config"))
             .id_source("dyno_info_motor_config_id")
             .default_open(true)
             .show(ui, |ui| {
@@ -159,7 +859,7 @@ impl SettingWindow {
                     .spacing([40.0, 4.0])
                     .show(ui, info_motor_ui)
             });
-        CollapsingHeader::new("Data Configuration")
+        CollapsingHeader::new(tr!("Data Configuration"))
             .id_source("dyno_configuration_id")
             .default_open(true)
             .show(ui, |ui| {
@@ -179,7 +879,13 @@ impl super::WindowState for SettingWindow {
         _state: &mut crate::state::DynoState,
     ) {
         let mut open = self.open;
-        Window::new("Dyno Control Settings")
+        let mut server_action = None;
+        let mut select_vehicle = None;
+        let mut recompute = false;
+        let mut preset_action = None;
+        let mut reset = None;
+        let mut apply_paths = None;
+        Window::new(tr!("Dyno Control Settings"))
             .id(Id::new("id_control_setting"))
             .open(&mut open)
             .collapsible(false)
@@ -188,9 +894,11 @@ impl super::WindowState for SettingWindow {
                 ui.vertical_centered_justified(|ui| {
                     ui.horizontal(|ui| {
                         use PanelSetting::*;
-                        ui.selectable_value(&mut self.panel, Generic, stringify!(Generic));
-                        ui.selectable_value(&mut self.panel, Info, stringify!(InfoMotor));
-                        ui.selectable_value(&mut self.panel, Style, stringify!(Style));
+                        ui.selectable_value(&mut self.panel, Generic, tr!("Generic"));
+                        ui.selectable_value(&mut self.panel, Info, tr!("InfoMotor"));
+                        ui.selectable_value(&mut self.panel, Keymap, tr!("Keymap"));
+                        ui.selectable_value(&mut self.panel, Server, tr!("Server"));
+                        ui.selectable_value(&mut self.panel, Style, tr!("Style"));
                     });
                 });
                 ui.separator();
@@ -202,16 +910,40 @@ impl super::WindowState for SettingWindow {
                             paths,
                             app_config,
                             config,
+                            telemetry,
+                            vehicles,
+                            vehicle,
+                            calibration,
+                            presets,
+                            preset,
                             ..
                         } = control;
                         match self.panel {
                             PanelSetting::Generic => {
-                                self.setting_generic(scr_ui, app_config, paths)
+                                apply_paths =
+                                    self.setting_generic(scr_ui, app_config, paths, telemetry);
+                                scr_ui.separator();
+                                reset = self.setting_reset(scr_ui);
                             }
                             PanelSetting::Info => {
-                                scr_ui.heading("Info Setting");
+                                scr_ui.heading(tr!("Info Setting"));
                                 scr_ui.separator();
-                                Self::setting_info(scr_ui, config)
+                                preset_action =
+                                    self.setting_preset(scr_ui, presets, preset, config);
+                                select_vehicle = Self::setting_vehicle(scr_ui, vehicles, vehicle);
+                                Self::setting_calibration(scr_ui, calibration, config);
+                                Self::setting_info(scr_ui, config);
+                                recompute = Self::setting_calculation(
+                                    scr_ui,
+                                    &mut app_config.calculation,
+                                    calibration,
+                                );
+                            }
+                            PanelSetting::Keymap => {
+                                self.setting_keymap(scr_ui, &mut app_config.keymap)
+                            }
+                            PanelSetting::Server => {
+                                server_action = self.setting_server(scr_ui, &mut app_config.server)
                             }
                             PanelSetting::Style => {
                                 ctx.settings_ui(scr_ui);
@@ -221,7 +953,86 @@ impl super::WindowState for SettingWindow {
                         };
                     });
             });
+        // the headless `record` use the config of the application, kept when the settings closed.
+        if self.open && !open {
+            control.save_active_config();
+        }
         self.open = open;
+        if !open {
+            self.capture = None;
+        }
+
+        if let Some(id) = select_vehicle {
+            control.select_vehicle(id);
+        }
+        if recompute {
+            control.recompute_power();
+        }
+        if let Some(section) = reset {
+            control.reset_settings(section);
+        }
+        if let Some(paths) = apply_paths {
+            match control.set_paths(paths, self.move_data) {
+                Ok(migration) => {
+                    self.paths_draft = None;
+                    toast_success!(tr!(
+                        "Paths applied, {count} files moved",
+                        count = migration.moved
+                    ));
+                    if !migration.skipped.is_empty() {
+                        toast_warn!(tr!(
+                            "{count} files are not moved, the same name exists in the new folder",
+                            count = migration.skipped.len()
+                        ));
+                    }
+                }
+                Err(err) => toast_error!(tr!("Failed to apply the paths - {err}", err = err)),
+            }
+        }
+        match preset_action {
+            Some(PresetAction::Apply(name)) => control.apply_preset(&name),
+            Some(PresetAction::Save(name)) => match control.presets.save(&name, &control.config) {
+                Ok(()) => {
+                    toast_success!(tr!("Preset `{name}` saved", name = name));
+                    control.preset = Some(name);
+                }
+                Err(err) => toast_error!(tr!("Failed to save preset - {err}", err = err)),
+            },
+            Some(PresetAction::Delete(name)) => {
+                if let Err(err) = control.presets.remove(&name) {
+                    toast_error!(tr!("Failed to delete preset - {err}", err = err));
+                }
+                if control.preset.as_deref() == Some(name.as_str()) {
+                    control.preset = None;
+                }
+            }
+            Some(PresetAction::Import(path)) => match control.presets.import(&path) {
+                Ok(name) => {
+                    toast_success!(tr!("Preset `{name}` imported", name = name));
+                    self.preset_name = name;
+                }
+                Err(err) => toast_error!(tr!("Failed to import preset - {err}", err = err)),
+            },
+            Some(PresetAction::Export(name, path)) => match control.presets.export(&name, &path) {
+                Ok(()) => toast_success!(tr!(
+                    "Preset `{name}` exported to `{path}`",
+                    name = name,
+                    path = path.display()
+                )),
+                Err(err) => toast_error!(tr!("Failed to export preset - {err}", err = err)),
+            },
+            Some(PresetAction::Reload) => control.presets.reload(),
+            None => {}
+        }
+        match server_action {
+            Some(ServerAction::Reconnect) => control.reconnect_api(),
+            Some(ServerAction::TestConnection(profile)) => {
+                if let Some(api) = ApiService::new(&profile) {
+                    api.check_health(control.tx().clone());
+                }
+            }
+            None => {}
+        }
     }
 
     #[inline]
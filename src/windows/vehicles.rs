@@ -0,0 +1,434 @@
+use eframe::egui::{
+    Button, CollapsingHeader, ComboBox, DragValue, Grid, Id, Layout, ScrollArea, TextEdit, Ui,
+    Window,
+};
+use eframe::emath::Align;
+use egui_extras::{Column, TableBuilder};
+
+use crate::service::{RollerConstants, VehicleDb};
+use crate::widgets::button::ButtonExt;
+use crate::{row_label_value, toast_error, tr};
+
+use super::setting::SettingWindow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selected {
+    Customer(String),
+    Vehicle(String),
+}
+
+/// customers and their vehicles, select the vehicle for the next runs.
+#[derive(Debug, Clone, Default)]
+pub struct VehiclesWindow {
+    open: bool,
+    search: String,
+    selected: Option<Selected>,
+    dirty: bool,
+}
+
+impl VehiclesWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn list_ui(&mut self, ui: &mut Ui, db: &VehicleDb, active: Option<&str>) {
+        ui.add(
+            TextEdit::singleline(&mut self.search)
+                .hint_text(tr!("🔍 plate number, motor or owner"))
+                .desired_width(f32::INFINITY),
+        );
+        ScrollArea::vertical()
+            .id_source("dyno_vehicles_list")
+            .max_height(240.)
+            .show(ui, |ui| {
+                for customer in db.customers() {
+                    let vehicles = db
+                        .vehicles()
+                        .iter()
+                        .filter(|v| v.owner == customer.id)
+                        .filter(|v| v.matches(&self.search, Some(customer)))
+                        .collect::<Vec<_>>();
+                    if vehicles.is_empty() && !self.search.trim().is_empty() {
+                        continue;
+                    }
+                    let selected = Selected::Customer(customer.id.clone());
+                    if ui
+                        .selectable_label(
+                            self.selected.as_ref() == Some(&selected),
+                            format!("👤 {}", customer.name),
+                        )
+                        .clicked()
+                    {
+                        self.selected = Some(selected);
+                    }
+                    ui.indent(&customer.id, |ui| {
+                        for vehicle in vehicles {
+                            let selected = Selected::Vehicle(vehicle.id.clone());
+                            let mark = if active == Some(vehicle.id.as_str()) {
+                                "✔ "
+                            } else {
+                                ""
+                            };
+                            if ui
+                                .selectable_label(
+                                    self.selected.as_ref() == Some(&selected),
+                                    format!("{mark}🏍 {} - {}", vehicle.plate, vehicle.motor),
+                                )
+                                .clicked()
+                            {
+                                self.selected = Some(selected);
+                            }
+                        }
+                    });
+                }
+            });
+    }
+
+    fn customer_ui(&mut self, ui: &mut Ui, db: &mut VehicleDb, id: &str) {
+        let customer = match db.customers_mut().iter_mut().find(|c| c.id == id) {
+            Some(customer) => customer,
+            None => return,
+        };
+        let before = customer.clone();
+        Grid::new("dyno_customer_grid_id")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                row_label_value!(
+                    ui,
+                    TextEdit::singleline(&mut customer.name).hint_text(tr!("owner name")),
+                    "Name",
+                    "name of the vehicle owner"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    TextEdit::singleline(&mut customer.phone).hint_text("08xx"),
+                    "Phone",
+                    "phone number of the owner"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    TextEdit::singleline(&mut customer.email).hint_text("email@example.com"),
+                    "Email",
+                    "email of the owner"
+                );
+                ui.end_row();
+            });
+        self.dirty |= before != *customer;
+    }
+
+    fn vehicle_ui(&mut self, ui: &mut Ui, db: &mut VehicleDb, id: &str, rollers: RollerConstants) {
+        let customers = db
+            .customers()
+            .iter()
+            .map(|c| (c.id.clone(), c.name.clone()))
+            .collect::<Vec<_>>();
+        let vehicle = match db.vehicles_mut().iter_mut().find(|v| v.id == id) {
+            Some(vehicle) => vehicle,
+            None => return,
+        };
+        let before = (
+            vehicle.plate.clone(),
+            vehicle.notes.clone(),
+            vehicle.rollers,
+        );
+        let motor_before = vehicle.motor.to_string();
+        let owner_before = vehicle.owner.clone();
+
+        Grid::new("dyno_vehicle_grid_id")
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .show(ui, |ui| {
+                row_label_value!(
+                    ui,
+                    TextEdit::singleline(&mut vehicle.plate).hint_text("P 1234 XY"),
+                    "Plate Number",
+                    "plate number of the vehicle"
+                );
+                ui.end_row();
+                row_label_value!(
+                    ui => ComboBox::from_id_source("dyno_vehicle_owner")
+                        .selected_text(
+                            customers
+                                .iter()
+                                .find(|(id, _)| *id == vehicle.owner)
+                                .map_or("-", |(_, name)| name.as_str()),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (id, name) in &customers {
+                                ui.selectable_value(&mut vehicle.owner, id.clone(), name);
+                            }
+                        }),
+                    "Owner",
+                    "owner of the vehicle"
+                );
+                ui.end_row();
+                SettingWindow::setting_motor(ui, &mut vehicle.motor);
+                ui.end_row();
+                row_label_value!(
+                    ui,
+                    TextEdit::multiline(&mut vehicle.notes).desired_rows(2),
+                    "Notes",
+                    "notes of the vehicle, e.g. engine modifications"
+                );
+                ui.end_row();
+            });
+
+        let mut override_rollers = vehicle.rollers.is_some();
+        if ui
+            .checkbox(&mut override_rollers, tr!("Override Roller Constants"))
+            .on_hover_text(tr!("use specific roller constants for this vehicle"))
+            .changed()
+        {
+            vehicle.rollers = override_rollers.then_some(rollers);
+        }
+        if let Some(rollers) = &mut vehicle.rollers {
+            Grid::new("dyno_vehicle_rollers_grid_id")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .show(ui, |ui| roller_ui(ui, rollers));
+        }
+
+        self.dirty |= before
+            != (
+                vehicle.plate.clone(),
+                vehicle.notes.clone(),
+                vehicle.rollers,
+            )
+            || motor_before != vehicle.motor.to_string()
+            || owner_before != vehicle.owner;
+    }
+}
+
+fn roller_ui(ui: &mut Ui, rollers: &mut RollerConstants) {
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.diameter_roller)
+            .speed(0.001)
+            .suffix(" m"),
+        "Diameter Roller",
+        "Diameter of the roller of the dynotests chassis"
+    );
+    ui.end_row();
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.diameter_roller_beban)
+            .speed(0.001)
+            .suffix(" m"),
+        "Diameter Roller Beban",
+        "Diameter of the load roller of the dynotests chassis"
+    );
+    ui.end_row();
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.diameter_gear_encoder)
+            .speed(0.001)
+            .suffix(" m"),
+        "Diameter Gear Encoder",
+        "Diameter of the gear on the Encoder of the dynotests chassis"
+    );
+    ui.end_row();
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.diameter_gear_beban)
+            .speed(0.001)
+            .suffix(" m"),
+        "Diameter Gear Beban",
+        "Diameter of the gear on the load roller of the dynotests chassis"
+    );
+    ui.end_row();
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.jarak_gear)
+            .speed(0.001)
+            .suffix(" m"),
+        "Jarak Antar Gear",
+        "distance between the gear of the load roller and the Encoder sensor"
+    );
+    ui.end_row();
+    row_label_value!(
+        ui,
+        DragValue::new(&mut rollers.berat_beban)
+            .speed(0.1)
+            .suffix(" kg"),
+        "Berat Roller Beban",
+        "weight of the load roller of the dynotests chassis"
+    );
+    ui.end_row();
+}
+
+impl super::WindowState for VehiclesWindow {
+    fn show_window(
+        &mut self,
+        ctx: &eframe::egui::Context,
+        control: &mut crate::control::DynoControl,
+        state: &mut crate::state::DynoState,
+    ) {
+        let rollers = RollerConstants::from_config(&control.config);
+        let active = control.vehicle.clone();
+        let mut select = None;
+        let mut open_path = None;
+        let mut open = self.open;
+
+        Window::new(tr!("Vehicles"))
+            .id(Id::new("dyno_vehicles_window"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                let db = &mut control.vehicles;
+                self.list_ui(ui, db, active.as_deref());
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("➕ Customer")).clicked() {
+                        let id = db.add_customer("new customer");
+                        self.selected = Some(Selected::Customer(id));
+                        self.dirty = true;
+                    }
+                    let owner = match &self.selected {
+                        Some(Selected::Customer(id)) => Some(id.clone()),
+                        Some(Selected::Vehicle(id)) => db.vehicle(id).map(|v| v.owner.clone()),
+                        None => None,
+                    };
+                    if ui
+                        .add_enabled(owner.is_some(), Button::new(tr!("➕ Vehicle")))
+                        .on_disabled_hover_text(tr!("select the vehicle owner first"))
+                        .clicked()
+                    {
+                        if let Some(owner) = owner {
+                            let id = db.add_vehicle(&owner, &control.config);
+                            self.selected = Some(Selected::Vehicle(id));
+                            self.dirty = true;
+                        }
+                    }
+                    if ui
+                        .add_enabled(self.selected.is_some(), Button::new("🗑"))
+                        .on_hover_text(tr!("delete, an owner is deleted together with the vehicles"))
+                        .clicked()
+                    {
+                        match self.selected.take() {
+                            Some(Selected::Customer(id)) => db.remove_customer(&id),
+                            Some(Selected::Vehicle(id)) => db.remove_vehicle(&id),
+                            None => {}
+                        }
+                        self.dirty = true;
+                    }
+                });
+                ui.separator();
+
+                match self.selected.clone() {
+                    Some(Selected::Customer(id)) => self.customer_ui(ui, db, &id),
+                    Some(Selected::Vehicle(id)) => {
+                        self.vehicle_ui(ui, db, &id, rollers);
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button(tr!("✔ Use for Next Run"))
+                                .on_hover_text(
+                                    tr!("fill the config with the data of this vehicle, the saved runs are linked to this vehicle"),
+                                )
+                                .clicked()
+                            {
+                                select = Some(Some(id.clone()));
+                            }
+                            if active.as_deref() == Some(id.as_str())
+                                && ui.button(tr!("✖ Unselect")).clicked()
+                            {
+                                select = Some(None);
+                            }
+                        });
+                        if let Some(vehicle) = db.vehicle(&id) {
+                            CollapsingHeader::new(tr!("Run History ({count})", count = vehicle.runs.len()))
+                                .id_source("dyno_vehicle_runs")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    TableBuilder::new(ui)
+                                        .striped(true)
+                                        .cell_layout(Layout::left_to_right(Align::Center))
+                                        .column(Column::auto().at_least(120.0))
+                                        .column(Column::remainder().at_least(160.0))
+                                        .column(Column::auto())
+                                        .max_scroll_height(160.)
+                                        .header(20.0, |mut head_ui| {
+                                            for name in [tr!("time"), tr!("run"), tr!("open")] {
+                                                head_ui.col(|col_ui| {
+                                                    col_ui.strong(name);
+                                                });
+                                            }
+                                        })
+                                        .body(|body_ui| {
+                                            body_ui.rows(18.0, vehicle.runs.len(), |row_idx, mut row| {
+                                                let run = &vehicle.runs[vehicle.runs.len() - 1 - row_idx];
+                                                row.col(|ui| {
+                                                    ui.label(run.time.format("%d-%m-%Y %T").to_string());
+                                                });
+                                                row.col(|ui| {
+                                                    match (&run.path, run.server_id) {
+                                                        (Some(path), _) => ui.label(
+                                                            path.file_name()
+                                                                .unwrap_or_default()
+                                                                .to_string_lossy(),
+                                                        ),
+                                                        (None, Some(id)) => ui.label(tr!("server #{id}", id = id)),
+                                                        (None, None) => ui.weak("-"),
+                                                    };
+                                                });
+                                                row.col(|ui| {
+                                                    if let Some(path) = &run.path {
+                                                        if path.exists() && ui.small_open_button().clicked() {
+                                                            open_path = Some(path.clone());
+                                                        }
+                                                    }
+                                                });
+                                            });
+                                        });
+                                });
+                        }
+                    }
+                    None => {
+                        ui.weak(tr!("select an owner or a vehicle"));
+                    }
+                }
+
+                ui.separator();
+                if ui
+                    .add_enabled(self.dirty, Button::new(tr!("💾 Save")))
+                    .clicked()
+                {
+                    self.save(db);
+                }
+            });
+
+        // save the changes when the window is closed.
+        if self.open && !open && self.dirty {
+            self.save(&control.vehicles);
+        }
+        self.open = open;
+
+        if let Some(id) = select {
+            control.select_vehicle(id);
+        }
+        if let Some(path) = open_path {
+            control.open_path(path, state);
+        }
+    }
+
+    #[inline]
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    #[inline]
+    fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+impl VehiclesWindow {
+    fn save(&mut self, db: &VehicleDb) {
+        match db.persist() {
+            Ok(()) => self.dirty = false,
+            Err(err) => toast_error!(tr!("Failed to save vehicle database - {err}", err = err)),
+        }
+    }
+}